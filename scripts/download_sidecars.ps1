@@ -1,5 +1,5 @@
 # download_sidecars.ps1
-# Downloads yt-dlp, ffmpeg, and deno to src-tauri/binaries/ with target-triple naming.
+# Downloads yt-dlp, ffmpeg, deno and aria2c to src-tauri/binaries/ with target-triple naming.
 # Run this before `npm run tauri dev` or `npm run tauri build`.
 
 $ErrorActionPreference = "Stop"
@@ -93,5 +93,37 @@ else {
   Write-Host "Deno already exists." -ForegroundColor Gray
 }
 
+# --- aria2c (optional multi-connection downloader) ---
+# Not bundled with the app; this copy is for development. Installed apps use aria2c from PATH.
+$ARIA2_URL = "https://github.com/aria2/aria2/releases/download/release-1.37.0/aria2-1.37.0-win-64bit-build1.zip"
+$ARIA2_ZIP = Join-Path $env:TEMP "aria2.zip"
+$ARIA2_EXTRACT = Join-Path $env:TEMP "aria2-extract"
+$ARIA2_PATH = Join-Path $BINARIES_DIR "aria2c-$TARGET_TRIPLE.exe"
+
+if (-not (Test-Path $ARIA2_PATH)) {
+  Write-Host "Downloading aria2c..." -ForegroundColor Cyan
+  Invoke-WebRequest -Uri $ARIA2_URL -OutFile $ARIA2_ZIP -UseBasicParsing
+
+  Write-Host "  -> Extracting..." -ForegroundColor Cyan
+  if (Test-Path $ARIA2_EXTRACT) { Remove-Item -Path $ARIA2_EXTRACT -Recurse -Force }
+  Expand-Archive -Path $ARIA2_ZIP -DestinationPath $ARIA2_EXTRACT -Force
+
+  $aria2Exe = Get-ChildItem -Path $ARIA2_EXTRACT -Recurse -Filter "aria2c.exe" | Select-Object -First 1
+  if ($aria2Exe) {
+    Copy-Item -Path $aria2Exe.FullName -Destination $ARIA2_PATH
+    Write-Host "  -> Saved to $ARIA2_PATH" -ForegroundColor Green
+  }
+  else {
+    Write-Error "aria2c.exe not found in archive!"
+  }
+
+  # Cleanup
+  Remove-Item -Path $ARIA2_ZIP -Force -ErrorAction SilentlyContinue
+  Remove-Item -Path $ARIA2_EXTRACT -Recurse -Force -ErrorAction SilentlyContinue
+}
+else {
+  Write-Host "aria2c already exists." -ForegroundColor Gray
+}
+
 Write-Host ""
 Write-Host "All sidecar binaries are ready!" -ForegroundColor Green
@@ -0,0 +1,28 @@
+use sea_orm::DatabaseConnection;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::time::{sleep, Duration};
+
+use crate::backup::run_scheduled;
+
+/// How often to check whether a scheduled backup is due.
+const CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// Make a backup whenever the last scheduled one is older than
+/// `backup_interval_days`, keeping the newest `backup_keep`.
+pub fn start_backup_scheduler(app: &AppHandle, db: Arc<DatabaseConnection>, data_dir: PathBuf) {
+    let _app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tracing::info!("Starting background backup scheduler task");
+
+        loop {
+            match run_scheduled(&db, &data_dir).await {
+                Ok(Some(path)) => tracing::info!("Scheduled backup written to {}", path.display()),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Error during scheduled backup: {}", e),
+            }
+            sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
@@ -0,0 +1,28 @@
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::time::{sleep, Duration};
+
+use crate::maintenance::run_scheduled;
+
+/// Let startup and the library scan finish before the first check.
+const STARTUP_DELAY_SECS: u64 = 120;
+
+/// How often to check whether maintenance is due.
+const CHECK_INTERVAL_SECS: u64 = 6 * 3600;
+
+/// Run database maintenance every `maintenance_interval_days`.
+pub fn start_db_maintenance(app: &AppHandle, db: Arc<DatabaseConnection>) {
+    let _app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tracing::info!("Starting background database maintenance task");
+        sleep(Duration::from_secs(STARTUP_DELAY_SECS)).await;
+
+        loop {
+            if let Err(e) = run_scheduled(&db).await {
+                tracing::error!("Error during database maintenance: {}", e);
+            }
+            sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
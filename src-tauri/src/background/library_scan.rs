@@ -0,0 +1,24 @@
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::time::{sleep, Duration};
+
+/// Let startup settle before walking the download folder.
+const STARTUP_DELAY_SECS: u64 = 10;
+
+/// Reconcile the library with the disk once after startup, reporting the result
+/// through `library-reconciled`.
+pub fn start_library_scan(app: &AppHandle, db: Arc<DatabaseConnection>) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        sleep(Duration::from_secs(STARTUP_DELAY_SECS)).await;
+        match crate::library::reconcile::reconcile(&db).await {
+            Ok(report) => {
+                if let Err(e) = app_handle.emit("library-reconciled", &report) {
+                    tracing::warn!("Failed to emit library-reconciled event: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Library scan failed: {}", e),
+        }
+    });
+}
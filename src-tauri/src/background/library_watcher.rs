@@ -0,0 +1,138 @@
+use notify_debouncer_full::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use sea_orm::DatabaseConnection;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::time::{interval, Duration};
+
+use crate::library::folders::download_root;
+use crate::library::watcher::{apply, classify};
+
+/// How long the file system must be quiet before a batch of events is applied.
+const DEBOUNCE_SECS: u64 = 2;
+
+/// How often to check whether the download folder setting changed.
+const ROOT_CHECK_SECS: u64 = 30;
+
+/// Handle to the library watcher, stored as Tauri managed state so a library
+/// relocation can suspend it while it moves files itself.
+#[derive(Clone)]
+pub struct LibraryWatcher {
+    suspended: tokio::sync::watch::Sender<bool>,
+}
+
+impl LibraryWatcher {
+    /// Stop watching the download folder until the returned guard is dropped.
+    pub fn suspend(&self) -> WatcherSuspension {
+        self.suspended.send_replace(true);
+        WatcherSuspension(self.suspended.clone())
+    }
+}
+
+/// Keeps the library watcher suspended while alive.
+pub struct WatcherSuspension(tokio::sync::watch::Sender<bool>);
+
+impl Drop for WatcherSuspension {
+    fn drop(&mut self) {
+        self.0.send_replace(false);
+    }
+}
+
+/// Watch the download folder and keep media rows in step with renames, moves and
+/// deletions made outside the app, reporting each batch through
+/// `library-files-changed`.
+pub fn start_library_watcher(app: &AppHandle, db: Arc<DatabaseConnection>) -> LibraryWatcher {
+    let app_handle = app.clone();
+    let (suspend_tx, mut suspended) = tokio::sync::watch::channel(false);
+    tauri::async_runtime::spawn(async move {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut watching: Option<(PathBuf, Debouncer<RecommendedWatcher, RecommendedCache>)> = None;
+        let mut check = interval(Duration::from_secs(ROOT_CHECK_SECS));
+
+        loop {
+            tokio::select! {
+                Ok(()) = suspended.changed() => {
+                    if *suspended.borrow_and_update() {
+                        tracing::info!("Library watcher suspended");
+                        watching = None;
+                    } else {
+                        // The download folder may have changed meanwhile.
+                        check.reset_immediately();
+                    }
+                }
+                _ = check.tick() => {
+                    if *suspended.borrow() {
+                        continue;
+                    }
+                    let root = download_root(&*db).await;
+                    if watching.as_ref().is_some_and(|(current, _)| *current == root) {
+                        continue;
+                    }
+                    // Dropping the old debouncer stops watching the previous folder.
+                    watching = None;
+                    if !root.is_dir() {
+                        continue;
+                    }
+                    match watch(&root, tx.clone()) {
+                        Ok(debouncer) => {
+                            tracing::info!("Watching download folder {}", root.display());
+                            watching = Some((root, debouncer));
+                        }
+                        Err(e) => tracing::warn!("{}", e),
+                    }
+                }
+                Some(result) = rx.recv() => {
+                    if *suspended.borrow() {
+                        continue;
+                    }
+                    let events = match result {
+                        Ok(events) => events,
+                        Err(errors) => {
+                            for e in errors {
+                                tracing::warn!("File watcher error: {}", e);
+                            }
+                            continue;
+                        }
+                    };
+                    let changes: Vec<_> = events.iter().flat_map(|e| classify(e)).collect();
+                    if changes.is_empty() {
+                        continue;
+                    }
+                    match apply(&db, &changes).await {
+                        Ok(report) if !report.is_empty() => {
+                            if let Err(e) = app_handle.emit("library-files-changed", &report) {
+                                tracing::warn!("Failed to emit library-files-changed event: {}", e);
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(e) => tracing::error!("Failed to apply file changes: {}", e),
+                    }
+                }
+            }
+        }
+    });
+    LibraryWatcher {
+        suspended: suspend_tx,
+    }
+}
+
+/// A recursive, debounced watch on `root` that sends each batch of events to `tx`.
+fn watch(
+    root: &Path,
+    tx: UnboundedSender<DebounceEventResult>,
+) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, String> {
+    let mut debouncer = new_debouncer(
+        Duration::from_secs(DEBOUNCE_SECS),
+        None,
+        move |result: DebounceEventResult| {
+            let _ = tx.send(result);
+        },
+    )
+    .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+    debouncer
+        .watch(root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+    Ok(debouncer)
+}
@@ -1 +1,5 @@
+pub mod backup_scheduler;
+pub mod db_maintenance;
+pub mod library_scan;
+pub mod library_watcher;
 pub mod trash_cleaner;
@@ -1,6 +1,5 @@
 use chrono::{Duration, Utc};
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
-use std::path::Path;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tokio::time::{sleep, Duration as StdDuration};
@@ -54,6 +53,9 @@ async fn run_cleanup(db: &DatabaseConnection) -> Result<(), String> {
     }
 
     tracing::info!("Found {} old posts to hard-delete", posts_to_delete.len());
+    let library = crate::library::Library::load(db)
+        .await
+        .map_err(|e| format!("DB error loading folders: {}", e))?;
 
     for p in posts_to_delete {
         let medias = media::Entity::find()
@@ -63,22 +65,16 @@ async fn run_cleanup(db: &DatabaseConnection) -> Result<(), String> {
             .unwrap_or_default();
 
         for m in medias {
-            if Path::new(&m.file_path).exists() {
-                let _ = move_to_trash(&m.file_path);
-            }
-            if let Some(thumb) = &m.thumbnail_path {
-                if Path::new(thumb).exists() {
-                    let _ = move_to_trash(thumb);
+            for path in library.asset_paths(&m) {
+                if path.exists() {
+                    let _ = move_to_trash(path);
                 }
             }
         }
 
-        let _ = media::Entity::delete_many()
-            .filter(media::Column::PostId.eq(p.id))
-            .exec(db)
-            .await;
-
-        let _ = post::Entity::delete_by_id(p.id).exec(db).await;
+        if let Err(e) = crate::metadata::store::delete_post(db, p.id).await {
+            tracing::error!("Failed to delete post {}: {}", p.id, e);
+        }
     }
 
     Ok(())
@@ -0,0 +1,593 @@
+//! Backups of the database. A backup is a `.tar.gz` holding a consistent
+//! snapshot taken with `VACUUM INTO` (safe while the app writes through WAL), the
+//! settings as JSON for reading outside the app, and a manifest. Session cookies
+//! are only kept when asked for.
+//!
+//! A restore can't replace the database under the open connection pool, so it
+//! validates the snapshot, migrates it to the current schema and stages it next
+//! to the live database; [`apply_staged_restore`] swaps it in on the next start.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryResult, SqlxSqliteConnector,
+    Statement,
+};
+use sea_orm_migration::MigratorTrait;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::db::DB_FILE_NAME;
+use crate::entity::{platform_session, setting};
+use crate::migration::Migrator;
+
+/// Version of the archive layout; newer backups are refused.
+const BACKUP_FORMAT: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Scheduled backups are named `<prefix><timestamp>.tar.gz` so rotation only touches them.
+const SCHEDULED_PREFIX: &str = "videodownloaderpro-";
+const SCHEDULED_SUFFIX: &str = ".tar.gz";
+const SCHEDULED_TIMESTAMP: &str = "%Y%m%d-%H%M%S";
+
+/// Days between scheduled backups; 0 turns them off.
+pub const INTERVAL_DAYS_KEY: &str = "backup_interval_days";
+/// How many scheduled backups to keep.
+pub const KEEP_KEY: &str = "backup_keep";
+/// Folder for scheduled backups; defaults to `backups` in the app data folder.
+pub const DIR_KEY: &str = "backup_dir";
+/// Whether scheduled backups include the encrypted session cookies.
+pub const INCLUDE_SESSIONS_KEY: &str = "backup_include_sessions";
+
+const DEFAULT_INTERVAL_DAYS: i64 = 0;
+const DEFAULT_KEEP: usize = 7;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: u32,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    /// Last migration applied to the snapshot.
+    pub schema_version: String,
+    pub includes_sessions: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupReport {
+    pub path: String,
+    pub size: u64,
+    pub manifest: BackupManifest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub manifest: BackupManifest,
+    /// Migrations run to bring an older backup up to date.
+    pub migrations_applied: usize,
+    /// Whether the current sessions were carried over because the backup had none.
+    pub sessions_kept: bool,
+}
+
+/// Write a backup of the database at `path`.
+pub async fn create_backup(
+    db: &DatabaseConnection,
+    data_dir: &Path,
+    path: &Path,
+    include_sessions: bool,
+) -> Result<BackupReport, String> {
+    let staging = staging_dir(data_dir, "backup").await?;
+    let result = write_backup(db, &staging, path, include_sessions).await;
+    let _ = tokio::fs::remove_dir_all(&staging).await;
+    result
+}
+
+async fn write_backup(
+    db: &DatabaseConnection,
+    staging: &Path,
+    path: &Path,
+    include_sessions: bool,
+) -> Result<BackupReport, String> {
+    let db_error = |e: DbErr| format!("Database error creating backup: {}", e);
+    let snapshot_path = staging.join(DB_FILE_NAME);
+    db.execute_unprepared(&format!(
+        "VACUUM INTO '{}'",
+        snapshot_path.to_string_lossy().replace('\'', "''")
+    ))
+    .await
+    .map_err(db_error)?;
+
+    let snapshot = open(&snapshot_path).await.map_err(db_error)?;
+    let contents = async {
+        if !include_sessions {
+            platform_session::Entity::delete_many()
+                .exec(&snapshot)
+                .await?;
+            // Rewrite the file so the deleted cookies don't linger in free pages.
+            snapshot.execute_unprepared("VACUUM").await?;
+        }
+        let settings: BTreeMap<String, String> = setting::Entity::find()
+            .all(&snapshot)
+            .await?
+            .into_iter()
+            .map(|s| (s.key, s.value))
+            .collect();
+        let schema_version = applied_migrations(&snapshot)
+            .await?
+            .pop()
+            .unwrap_or_default();
+        Ok::<_, DbErr>((settings, schema_version))
+    }
+    .await;
+    let _ = snapshot.close().await;
+    let (settings, schema_version) = contents.map_err(db_error)?;
+
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        schema_version,
+        includes_sessions: include_sessions,
+    };
+    write_json(&staging.join(MANIFEST_FILE), &manifest).await?;
+    write_json(&staging.join(SETTINGS_FILE), &settings).await?;
+
+    let (staging, target) = (staging.to_path_buf(), path.to_path_buf());
+    tokio::task::spawn_blocking(move || pack(&staging, &target))
+        .await
+        .map_err(|e| format!("Backup task failed: {}", e))??;
+
+    let size = tokio::fs::metadata(path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or_default();
+    tracing::info!("Wrote backup {} ({} bytes)", path.display(), size);
+    Ok(BackupReport {
+        path: path.to_string_lossy().to_string(),
+        size,
+        manifest,
+    })
+}
+
+/// Check the backup at `path` and stage its database to replace the live one on
+/// the next start. The snapshot must pass `integrity_check` and only contain
+/// migrations this version knows; older snapshots are migrated forward. A backup
+/// without sessions gets the current ones, so restoring doesn't sign out.
+pub async fn restore_backup(
+    db: &DatabaseConnection,
+    data_dir: &Path,
+    path: &Path,
+) -> Result<RestoreReport, String> {
+    let staging = staging_dir(data_dir, "restore").await?;
+    let result = stage_restore(db, data_dir, &staging, path).await;
+    let _ = tokio::fs::remove_dir_all(&staging).await;
+    result
+}
+
+async fn stage_restore(
+    db: &DatabaseConnection,
+    data_dir: &Path,
+    staging: &Path,
+    path: &Path,
+) -> Result<RestoreReport, String> {
+    let (source, target) = (path.to_path_buf(), staging.to_path_buf());
+    tokio::task::spawn_blocking(move || unpack(&source, &target))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))??;
+
+    let manifest: BackupManifest = {
+        let bytes = tokio::fs::read(staging.join(MANIFEST_FILE))
+            .await
+            .map_err(|_| format!("{} is not a backup: it has no manifest", path.display()))?;
+        serde_json::from_slice(&bytes).map_err(|e| format!("Invalid backup manifest: {}", e))?
+    };
+    if manifest.format > BACKUP_FORMAT {
+        return Err(format!(
+            "The backup was made by a newer version of the app ({})",
+            manifest.app_version
+        ));
+    }
+    let snapshot_path = staging.join(DB_FILE_NAME);
+    if !snapshot_path.is_file() {
+        return Err(format!("{} holds no database", path.display()));
+    }
+
+    let snapshot = open(&snapshot_path)
+        .await
+        .map_err(|e| format!("Failed to open the backed up database: {}", e))?;
+    let checked = check_snapshot(db, &snapshot, &manifest).await;
+    let _ = snapshot.close().await;
+    let (migrations_applied, sessions_kept) = checked?;
+
+    let staged = staged_path(data_dir);
+    tokio::fs::rename(&snapshot_path, &staged)
+        .await
+        .map_err(|e| format!("Failed to stage the restored database: {}", e))?;
+    tracing::info!(
+        "Staged restore of backup {} from {}",
+        path.display(),
+        manifest.created_at
+    );
+    Ok(RestoreReport {
+        manifest,
+        migrations_applied,
+        sessions_kept,
+    })
+}
+
+/// Validate and migrate a restored snapshot. Returns the migrations run and
+/// whether the live sessions were copied in.
+async fn check_snapshot(
+    live: &DatabaseConnection,
+    snapshot: &DatabaseConnection,
+    manifest: &BackupManifest,
+) -> Result<(usize, bool), String> {
+    let db_error = |e: DbErr| format!("Database error checking backup: {}", e);
+
+    let integrity = snapshot
+        .query_one(Statement::from_string(
+            snapshot.get_database_backend(),
+            "PRAGMA integrity_check".to_owned(),
+        ))
+        .await
+        .map_err(db_error)?
+        .and_then(|row| row.try_get_by_index::<String>(0).ok());
+    if integrity.as_deref() != Some("ok") {
+        return Err(format!(
+            "The backed up database is damaged: {}",
+            integrity.unwrap_or_default()
+        ));
+    }
+
+    let applied = applied_migrations(snapshot)
+        .await
+        .map_err(|_| "The backup does not hold a library database".to_string())?;
+    let known: HashSet<String> = Migrator::migrations()
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect();
+    if let Some(unknown) = applied.iter().find(|m| !known.contains(*m)) {
+        return Err(format!(
+            "The backup was made by a newer version of the app ({}): unknown migration {}",
+            manifest.app_version, unknown
+        ));
+    }
+    let pending = known.len() - applied.len();
+    Migrator::up(snapshot, None).await.map_err(db_error)?;
+
+    let sessions_kept = !manifest.includes_sessions;
+    if sessions_kept {
+        let sessions = platform_session::Entity::find()
+            .all(live)
+            .await
+            .map_err(db_error)?;
+        for s in sessions {
+            let row: platform_session::ActiveModel = s.into();
+            platform_session::Entity::insert(row)
+                .exec_without_returning(snapshot)
+                .await
+                .map_err(db_error)?;
+        }
+    }
+    Ok((pending, sessions_kept))
+}
+
+/// Swap a database staged by [`restore_backup`] into place before it is opened.
+/// The replaced database is kept as `<name>.pre-restore`, with its WAL files.
+pub fn apply_staged_restore(data_dir: &Path) -> std::io::Result<bool> {
+    let staged = staged_path(data_dir);
+    if !staged.is_file() {
+        return Ok(false);
+    }
+    let live = data_dir.join(DB_FILE_NAME);
+    let previous = data_dir.join(format!("{}.pre-restore", DB_FILE_NAME));
+    for suffix in ["", "-wal", "-shm"] {
+        let from = PathBuf::from(format!("{}{}", live.display(), suffix));
+        let to = PathBuf::from(format!("{}{}", previous.display(), suffix));
+        let _ = std::fs::remove_file(&to);
+        if from.exists() {
+            std::fs::rename(&from, &to)?;
+        }
+    }
+    std::fs::rename(&staged, &live)?;
+    tracing::info!(
+        "Restored database from backup; the previous one is {}",
+        previous.display()
+    );
+    Ok(true)
+}
+
+/// Make a scheduled backup if the last one is older than `backup_interval_days`,
+/// then delete all but the newest `backup_keep`. Returns the new backup, if any.
+pub async fn run_scheduled(
+    db: &DatabaseConnection,
+    data_dir: &Path,
+) -> Result<Option<PathBuf>, String> {
+    let interval_days = crate::db::get_setting(db, INTERVAL_DAYS_KEY)
+        .await
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_DAYS);
+    if interval_days <= 0 {
+        return Ok(None);
+    }
+    let keep = crate::db::get_setting(db, KEEP_KEY)
+        .await
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_KEEP)
+        .max(1);
+    let include_sessions = crate::db::get_bool_setting(db, INCLUDE_SESSIONS_KEY, false).await;
+    let dir = scheduled_dir(db, data_dir).await;
+
+    let newest = scheduled_backups(&dir).last().map(|(at, _)| *at);
+    if newest.is_some_and(|at| Utc::now() - at < chrono::Duration::days(interval_days)) {
+        return Ok(None);
+    }
+
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!(
+        "{}{}{}",
+        SCHEDULED_PREFIX,
+        Utc::now().format(SCHEDULED_TIMESTAMP),
+        SCHEDULED_SUFFIX
+    ));
+    create_backup(db, data_dir, &path, include_sessions).await?;
+    for old in rotate(&dir, keep) {
+        tracing::info!("Removed old backup {}", old.display());
+    }
+    Ok(Some(path))
+}
+
+/// Folder scheduled backups are written to.
+pub async fn scheduled_dir(db: &impl ConnectionTrait, data_dir: &Path) -> PathBuf {
+    crate::db::get_setting(db, DIR_KEY)
+        .await
+        .map(PathBuf::from)
+        .unwrap_or_else(|| data_dir.join("backups"))
+}
+
+/// Delete all but the newest `keep` scheduled backups in `dir`. Returns the ones removed.
+pub fn rotate(dir: &Path, keep: usize) -> Vec<PathBuf> {
+    let backups = scheduled_backups(dir);
+    let excess = backups.len().saturating_sub(keep);
+    backups
+        .into_iter()
+        .take(excess)
+        .filter_map(|(_, path)| std::fs::remove_file(&path).ok().map(|_| path))
+        .collect()
+}
+
+/// The scheduled backups in `dir` with their timestamps, oldest first.
+fn scheduled_backups(dir: &Path) -> Vec<(DateTime<Utc>, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stamp = name
+                .strip_prefix(SCHEDULED_PREFIX)?
+                .strip_suffix(SCHEDULED_SUFFIX)?;
+            let at = NaiveDateTime::parse_from_str(stamp, SCHEDULED_TIMESTAMP).ok()?;
+            Some((at.and_utc(), entry.path()))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+fn staged_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(format!("{}.restore", DB_FILE_NAME))
+}
+
+async fn staging_dir(data_dir: &Path, kind: &str) -> Result<PathBuf, String> {
+    let dir =
+        data_dir
+            .join("system")
+            .join("temp")
+            .join(format!("{}-{}", kind, uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Open an existing database file read-write. The path is passed as-is rather than
+/// through a connection URL, so `?` or `#` in folder names can't change its meaning.
+async fn open(path: &Path) -> Result<DatabaseConnection, DbErr> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(false)
+        .read_only(false);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .map_err(|e| DbErr::Conn(sea_orm::RuntimeErr::SqlxError(e)))?;
+    Ok(SqlxSqliteConnector::from_sqlx_sqlite_pool(pool))
+}
+
+/// Names of the migrations applied to `db`, in order.
+async fn applied_migrations(db: &impl ConnectionTrait) -> Result<Vec<String>, DbErr> {
+    let rows: Vec<QueryResult> = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT version FROM seaql_migrations ORDER BY version".to_owned(),
+        ))
+        .await?;
+    rows.iter()
+        .map(|row| row.try_get_by_index::<String>(0))
+        .collect()
+}
+
+async fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json =
+        serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize: {}", e))?;
+    tokio::fs::write(path, json)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Pack the staged files into `target`, replacing it in one step.
+fn pack(staging: &Path, target: &Path) -> Result<(), String> {
+    let write_error = |e: std::io::Error| format!("Failed to write {}: {}", target.display(), e);
+    let mut partial = target.as_os_str().to_owned();
+    partial.push(".tmp");
+    let file = std::fs::File::create(&partial).map_err(write_error)?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    for name in [MANIFEST_FILE, SETTINGS_FILE, DB_FILE_NAME] {
+        archive
+            .append_path_with_name(staging.join(name), name)
+            .map_err(write_error)?;
+    }
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(write_error)?;
+    std::fs::rename(&partial, target).map_err(write_error)
+}
+
+/// Extract the files a backup is made of into `staging`, ignoring anything else.
+fn unpack(source: &Path, staging: &Path) -> Result<(), String> {
+    let read_error = |e: std::io::Error| format!("Failed to read {}: {}", source.display(), e);
+    let file = std::fs::File::open(source).map_err(read_error)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let name = entry
+            .path()
+            .map_err(read_error)?
+            .to_string_lossy()
+            .to_string();
+        if [MANIFEST_FILE, SETTINGS_FILE, DB_FILE_NAME].contains(&name.as_str()) {
+            entry.unpack(staging.join(&name)).map_err(read_error)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use sea_orm::Set;
+
+    async fn add_session(db: &impl ConnectionTrait, platform_id: &str) {
+        platform_session::Entity::insert(platform_session::ActiveModel {
+            platform_id: Set(platform_id.to_string()),
+            status: Set("ACTIVE".to_string()),
+            encrypted_cookies: Set(Some(b"secret".to_vec())),
+            cookie_method: Set("MANUAL".to_string()),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        })
+        .exec_without_returning(db)
+        .await
+        .unwrap();
+    }
+
+    async fn session_ids(db: &impl ConnectionTrait) -> Vec<String> {
+        platform_session::Entity::find()
+            .all(db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.platform_id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let dir = std::env::temp_dir().join(format!("vdp-backup-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        // A file in WAL mode like the app's; `VACUUM INTO` can't copy an in-memory database.
+        let db = db::init_db(dir.join("live")).await.unwrap();
+        add_session(&db, "youtube").await;
+
+        let path = dir.join("backup.tar.gz");
+        let report = create_backup(&db, &dir, &path, false).await.unwrap();
+        assert!(!report.manifest.includes_sessions);
+        assert_eq!(
+            Some(report.manifest.schema_version.as_str()),
+            Migrator::migrations().last().map(|m| m.name())
+        );
+
+        // The live library signed in elsewhere since; restoring keeps that session.
+        platform_session::Entity::delete_many()
+            .exec(&db)
+            .await
+            .unwrap();
+        add_session(&db, "x").await;
+        let restored = restore_backup(&db, &dir, &path).await.unwrap();
+        assert_eq!(restored.migrations_applied, 0);
+        assert!(restored.sessions_kept);
+
+        tokio::fs::write(dir.join(DB_FILE_NAME), b"old")
+            .await
+            .unwrap();
+        assert!(apply_staged_restore(&dir).unwrap());
+        assert!(!apply_staged_restore(&dir).unwrap());
+        let swapped = open(&dir.join(DB_FILE_NAME)).await.unwrap();
+        assert_eq!(session_ids(&swapped).await, vec!["x".to_string()]);
+        assert!(db::get_setting(&swapped, "download_path").await.is_some());
+        swapped.close().await.unwrap();
+        assert_eq!(
+            tokio::fs::read(dir.join(format!("{}.pre-restore", DB_FILE_NAME)))
+                .await
+                .unwrap(),
+            b"old"
+        );
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_restore_refuses_newer_schema() {
+        let dir = std::env::temp_dir().join(format!("vdp-backup-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let db = db::init_db(dir.join("live")).await.unwrap();
+        db.execute_unprepared(
+            "INSERT INTO seaql_migrations (version, applied_at) VALUES ('m29990101_000001_future', 0)",
+        )
+        .await
+        .unwrap();
+
+        let path = dir.join("backup.tar.gz");
+        create_backup(&db, &dir, &path, true).await.unwrap();
+        let error = restore_backup(&db, &dir, &path).await.unwrap_err();
+        assert!(error.contains("m29990101_000001_future"), "{}", error);
+        assert!(!staged_path(&dir).exists());
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[test]
+    fn test_rotate_keeps_newest_scheduled_backups() {
+        let dir = std::env::temp_dir().join(format!("vdp-backup-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "videodownloaderpro-20261001-120000.tar.gz",
+            "videodownloaderpro-20261003-120000.tar.gz",
+            "videodownloaderpro-20261002-120000.tar.gz",
+            "manual.tar.gz",
+        ] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let removed = rotate(&dir, 2);
+        assert_eq!(
+            removed,
+            vec![dir.join("videodownloaderpro-20261001-120000.tar.gz")]
+        );
+        assert!(dir.join("manual.tar.gz").exists());
+        assert_eq!(scheduled_backups(&dir).len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+use tauri::{Manager, State};
+
+use crate::backup::{self, BackupReport, RestoreReport};
+use crate::AppState;
+
+fn app_data_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Write a backup of the database and settings to `path`, with the encrypted
+/// session cookies if `include_sessions` is set.
+#[tauri::command]
+pub async fn create_backup(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    include_sessions: bool,
+) -> Result<BackupReport, String> {
+    let data_dir = app_data_dir(&app_handle)?;
+    backup::create_backup(&state.db, &data_dir, &PathBuf::from(path), include_sessions).await
+}
+
+/// Check the backup at `path` and stage it to replace the database; it takes
+/// effect when the app restarts (see `restart_app`).
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<RestoreReport, String> {
+    let data_dir = app_data_dir(&app_handle)?;
+    backup::restore_backup(&state.db, &data_dir, &PathBuf::from(path)).await
+}
+
+/// Restart the app, e.g. to finish a restore.
+#[tauri::command]
+pub fn restart_app(app_handle: tauri::AppHandle) {
+    app_handle.request_restart();
+}
@@ -45,19 +45,29 @@ pub struct DownloadTaskInfo {
     pub source_id: Option<i64>,
     /// Playlist source name (if this task belongs to a playlist).
     pub source_name: Option<String>,
+    /// Requested time ranges / chapters, if only part of the video is downloaded.
+    pub sections: Option<String>,
+    /// The post is a live stream; downloading it records until stopped or the stream ends.
+    pub is_live: bool,
+    /// When a WAITING task starts (or is checked again), RFC 3339.
+    pub scheduled_at: Option<String>,
+    /// When a WAITING task is given up on, RFC 3339.
+    pub wait_deadline: Option<String>,
 }
 
 impl DownloadTaskInfo {
     fn new(m: download_task::Model, p: Option<post::Model>, source_name: Option<String>) -> Self {
-        let (title, thumbnail, source_id) = if let Some(ref post) = p {
+        let (title, thumbnail, source_id, is_live) = if let Some(ref post) = p {
             let title = post.title.clone();
-            let thumbnail = post.raw_json.as_ref().and_then(|json| {
-                let video: Result<YtDlpVideo, _> = serde_json::from_str(json);
-                video.ok().and_then(|v| v.best_thumbnail())
-            });
-            (title, thumbnail, post.source_id)
+            let video = post
+                .raw_json
+                .as_ref()
+                .and_then(|json| serde_json::from_str::<YtDlpVideo>(json).ok());
+            let thumbnail = video.as_ref().and_then(|v| v.best_thumbnail());
+            let is_live = video.as_ref().is_some_and(|v| v.is_live_now());
+            (title, thumbnail, post.source_id, is_live)
         } else {
-            (None, None, None)
+            (None, None, None, false)
         };
 
         Self {
@@ -76,6 +86,10 @@ impl DownloadTaskInfo {
             completed_at: m.completed_at.map(|t| t.to_rfc3339()),
             downloaded_bytes: m.downloaded_bytes,
             total_bytes: m.total_bytes,
+            sections: m.sections,
+            is_live,
+            scheduled_at: m.scheduled_at.map(|t| t.to_rfc3339()),
+            wait_deadline: m.wait_deadline.map(|t| t.to_rfc3339()),
             title,
             thumbnail,
             source_id,
@@ -91,6 +105,13 @@ pub async fn create_download_task(
     request: CreateDownloadTaskRequest,
 ) -> Result<i64, String> {
     // 1. Create task in DB
+    let sections = request
+        .format_selection
+        .as_deref()
+        .and_then(|s| serde_json::from_str::<format_processor::DownloadOptions>(s).ok())
+        .map(|o| o.sections.join("; "))
+        .filter(|s| !s.trim().is_empty());
+
     let new_task: download_task::ActiveModel = download_task::ActiveModel {
         id: sea_orm::ActiveValue::NotSet,
         url: Set(request.url.clone()),
@@ -100,6 +121,7 @@ pub async fn create_download_task(
         retries: Set(0),
         max_retries: Set(MANUAL_TASK_MAX_RETRIES),
         format_selection: Set(request.format_selection),
+        sections: Set(sections),
         created_at: Set(Utc::now()),
         ..Default::default()
     };
@@ -144,6 +166,17 @@ pub async fn cancel_download_task(
     Ok(())
 }
 
+/// Stop a live recording. Unlike cancelling, the recorded part is finalized and
+/// added to the library.
+#[tauri::command]
+pub async fn stop_recording(queue: State<'_, DownloadQueue>, task_id: i64) -> Result<(), String> {
+    if !queue.stop_recording(task_id).await {
+        return Err(format!("Task {} is not recording", task_id));
+    }
+    tracing::info!("Stopping recording: {}", task_id);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn retry_download_task(
     state: State<'_, AppState>,
@@ -175,6 +208,8 @@ pub async fn retry_download_task(
         completed_at: Set(None),
         speed: Set(None),
         eta: Set(None),
+        scheduled_at: Set(None),
+        wait_deadline: Set(None),
         ..Default::default()
     })
     .exec(&state.db)
@@ -259,6 +294,14 @@ pub async fn pause_download_task(
         .ok_or("Task not found")?;
 
     match task.status.as_str() {
+        // Pausing would restart the download, which then finds its output on disk and
+        // leaves the transcode, normalization and tags undone.
+        "PROCESSING" if queue.is_post_processing(task_id).await => {
+            return Err(format!(
+                "Task {} has finished downloading and can't be paused while it's processed",
+                task_id
+            ));
+        }
         "PROCESSING" => {
             // Update DB first to avoid race condition where manager sees "PROCESSING"
             // and marks it as CANCELLED
@@ -276,7 +319,8 @@ pub async fn pause_download_task(
             // Then cancel the running worker
             queue.cancel_task(task_id).await;
         }
-        "QUEUED" => {
+        // A waiting task holds no worker; pausing keeps it from being requeued.
+        "QUEUED" | "WAITING" => {
             download_task::Entity::update(download_task::ActiveModel {
                 id: Set(task_id),
                 status: Set("PAUSED".to_string()),
@@ -288,7 +332,7 @@ pub async fn pause_download_task(
         }
         _ => {
             return Err(format!(
-                "Cannot pause task with status '{}'. Only QUEUED or PROCESSING tasks can be paused.",
+                "Cannot pause task with status '{}'. Only QUEUED, WAITING or PROCESSING tasks can be paused.",
                 task.status
             ));
         }
@@ -398,17 +442,31 @@ pub async fn fetch_metadata_command(
 ///
 /// Failed tasks are intentionally preserved so the user can retry them.
 /// This only clears the download *log* — it never deletes downloaded files.
+/// Posts that existed only for a cleared cancelled download go with it.
 #[tauri::command]
 pub async fn clear_download_history(state: State<'_, AppState>) -> Result<u64, String> {
-    let result = download_task::Entity::delete_many()
+    let cleared = download_task::Entity::find()
         .filter(download_task::Column::Status.is_in(["COMPLETED", "CANCELLED"]))
+        .all(&state.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    let post_ids: Vec<i64> = cleared.iter().filter_map(|t| t.post_id).collect();
+
+    let result = download_task::Entity::delete_many()
+        .filter(download_task::Column::Id.is_in(cleared.iter().map(|t| t.id)))
         .exec(&state.db)
         .await
         .map_err(|e| format!("Database error: {}", e))?;
 
+    // Posts created only for a cancelled one-off download have nothing left.
+    let removed = crate::maintenance::remove_unused_posts(&state.db, Some(&post_ids), None)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
     tracing::info!(
-        "Cleared {} completed/cancelled tasks from history",
-        result.rows_affected
+        "Cleared {} completed/cancelled tasks from history ({} unused posts removed)",
+        result.rows_affected,
+        removed
     );
 
     Ok(result.rows_affected)
@@ -469,3 +527,9 @@ pub async fn retry_all_failed(
 
     Ok(result.rows_affected)
 }
+
+/// Transcode profiles that can be picked per task or as the default.
+#[tauri::command]
+pub fn get_transcode_profiles() -> Vec<&'static crate::download::transcode::TranscodeProfile> {
+    crate::download::transcode::PROFILES.iter().collect()
+}
@@ -0,0 +1,234 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use std::path::PathBuf;
+use tauri::{Emitter, State};
+
+use crate::{
+    background::library_watcher::LibraryWatcher,
+    entity::{download_task, media, post},
+    library::archive::{self, ArchiveImportReport},
+    library::catalog::{self, CatalogCounts, CatalogFormat, CatalogImportReport},
+    library::import::{self, ImportReport},
+    library::reconcile::{self, ReconcileReport},
+    library::relocate::{self, RelocateMode, RelocateReport, Relocation},
+    queue::DownloadQueue,
+    AppState,
+};
+
+const REDOWNLOAD_PRIORITY: i32 = 5;
+const REDOWNLOAD_MAX_RETRIES: i32 = 3;
+
+/// Task states in which a post is already being downloaded.
+const ACTIVE_TASK_STATUSES: &[&str] = &["QUEUED", "PROCESSING", "PAUSED", "WAITING"];
+
+/// The relocation a crashed or quit run left unfinished, so the UI can offer to resume it.
+#[tauri::command]
+pub async fn get_pending_relocation(
+    state: State<'_, AppState>,
+) -> Result<Option<Relocation>, String> {
+    Ok(relocate::pending(&state.db).await)
+}
+
+/// Move (or copy) every library file to `target` and make it the download folder,
+/// or resume the unfinished relocation to it. Progress is reported through
+/// `library-relocate-progress`; the queue is held paused and the library watcher
+/// suspended while files move.
+#[tauri::command]
+pub async fn relocate_library(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    queue: State<'_, DownloadQueue>,
+    watcher: State<'_, LibraryWatcher>,
+    target: String,
+    mode: RelocateMode,
+) -> Result<RelocateReport, String> {
+    // Pause before checking, so no download can start between the check and the move.
+    let was_paused = queue.is_paused();
+    queue.pause_queue();
+    let _suspended = watcher.suspend();
+    let result = async {
+        let active = download_task::Entity::find()
+            .filter(download_task::Column::Status.eq("PROCESSING"))
+            .count(&state.db)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        if active > 0 {
+            return Err(
+                "Wait for active downloads to finish before moving the library".to_string(),
+            );
+        }
+        relocate::relocate(&state.db, &PathBuf::from(target), mode, |progress| {
+            let _ = app_handle.emit("library-relocate-progress", progress);
+        })
+        .await
+    }
+    .await;
+    if !was_paused {
+        queue.resume_queue();
+    }
+    result
+}
+
+/// Import the media files below `source` (e.g. earlier yt-dlp downloads) into the
+/// library, moving or copying them into place. Files known only by ID get their
+/// metadata fetched when `refetch_metadata` is set. Progress is reported through
+/// `library-import-progress`.
+#[tauri::command]
+pub async fn import_local_files(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    source: String,
+    mode: RelocateMode,
+    refetch_metadata: bool,
+) -> Result<ImportReport, String> {
+    import::import(
+        &state.db,
+        Some(&app_handle),
+        &PathBuf::from(source),
+        mode,
+        refetch_metadata,
+        |progress| {
+            let _ = app_handle.emit("library-import-progress", progress);
+        },
+    )
+    .await
+}
+
+/// Read a yt-dlp `--download-archive` file: its videos count as downloaded, so
+/// syncs skip them. The configured archive file is rewritten to include them.
+#[tauri::command]
+pub async fn import_download_archive(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ArchiveImportReport, String> {
+    let report = archive::import_archive(&state.db, &PathBuf::from(path)).await?;
+    archive::export_configured(&state.db).await;
+    Ok(report)
+}
+
+/// Write a yt-dlp `--download-archive` file listing every completed post and
+/// imported entry. Returns the number of lines written.
+#[tauri::command]
+pub async fn export_download_archive(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<usize, String> {
+    archive::export_archive(&state.db, &PathBuf::from(path)).await
+}
+
+/// Export platforms, creators, sources, posts and media to `path`: a JSON file,
+/// or a directory of CSV files. Returns the rows written per table.
+#[tauri::command]
+pub async fn export_catalog(
+    state: State<'_, AppState>,
+    path: String,
+    format: CatalogFormat,
+) -> Result<CatalogCounts, String> {
+    catalog::export_catalog(&state.db, &PathBuf::from(path), format).await
+}
+
+/// Merge a catalog export into the library, skipping rows it already has.
+#[tauri::command]
+pub async fn import_catalog(
+    state: State<'_, AppState>,
+    path: String,
+    format: CatalogFormat,
+) -> Result<CatalogImportReport, String> {
+    catalog::import_catalog(&state.db, &PathBuf::from(path), format).await
+}
+
+/// Check every media file against the disk: re-find moved files, mark missing ones
+/// MISSING and list orphaned thumbnails. The report is also sent as `library-reconciled`.
+#[tauri::command]
+pub async fn reconcile_library(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ReconcileReport, String> {
+    let report = reconcile::reconcile(&state.db).await?;
+    if let Err(e) = app_handle.emit("library-reconciled", &report) {
+        tracing::warn!("Failed to emit library-reconciled event: {}", e);
+    }
+    Ok(report)
+}
+
+/// Queue a fresh download of each post with MISSING media (`post_ids`, or all of
+/// them), with the options it was last downloaded with. The post's media rows are
+/// dropped first; the download records them again. Returns the number queued.
+#[tauri::command]
+pub async fn redownload_missing(
+    state: State<'_, AppState>,
+    queue: State<'_, DownloadQueue>,
+    post_ids: Option<Vec<i64>>,
+) -> Result<usize, String> {
+    let db_error = |e: sea_orm::DbErr| format!("Database error: {}", e);
+    let missing = reconcile::missing_media_by_post(&state.db).await?;
+
+    let mut queued = 0;
+    for (post_id, rows) in missing {
+        if post_ids.as_ref().is_some_and(|ids| !ids.contains(&post_id)) {
+            continue;
+        }
+        if rows
+            .iter()
+            .all(|m| matches!(m.media_type.as_str(), "SUBTITLE" | "METADATA"))
+        {
+            continue;
+        }
+        let Some(p) = post::Entity::find_by_id(post_id)
+            .one(&state.db)
+            .await
+            .map_err(db_error)?
+        else {
+            continue;
+        };
+        // Imported local files have nowhere to be downloaded from.
+        if p.deleted_at.is_some() || p.original_url.starts_with("file://") {
+            continue;
+        }
+
+        let tasks = download_task::Entity::find()
+            .filter(download_task::Column::PostId.eq(post_id))
+            .order_by_desc(download_task::Column::CreatedAt)
+            .all(&state.db)
+            .await
+            .map_err(db_error)?;
+        if tasks
+            .iter()
+            .any(|t| ACTIVE_TASK_STATUSES.contains(&t.status.as_str()))
+        {
+            continue;
+        }
+        let last = tasks.first();
+
+        media::Entity::delete_many()
+            .filter(media::Column::PostId.eq(post_id))
+            .exec(&state.db)
+            .await
+            .map_err(db_error)?;
+        download_task::ActiveModel {
+            url: Set(p.original_url),
+            post_id: Set(Some(post_id)),
+            status: Set("QUEUED".to_string()),
+            priority: Set(REDOWNLOAD_PRIORITY),
+            progress: Set(0.0),
+            retries: Set(0),
+            max_retries: Set(REDOWNLOAD_MAX_RETRIES),
+            format_selection: Set(last.and_then(|t| t.format_selection.clone())),
+            sections: Set(last.and_then(|t| t.sections.clone())),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&state.db)
+        .await
+        .map_err(db_error)?;
+        queued += 1;
+    }
+
+    if queued > 0 {
+        queue.add_task();
+    }
+    tracing::info!("Queued {} posts with missing media for re-download", queued);
+    Ok(queued)
+}
@@ -0,0 +1,21 @@
+use tauri::State;
+
+use crate::maintenance::{self, MaintenanceReport};
+use crate::queue::DownloadQueue;
+use crate::AppState;
+
+/// Check the database, remove orphaned rows and compact it, reporting what changed.
+/// The queue is held paused so no download starts while it runs.
+#[tauri::command]
+pub async fn run_db_maintenance(
+    state: State<'_, AppState>,
+    queue: State<'_, DownloadQueue>,
+) -> Result<MaintenanceReport, String> {
+    let was_paused = queue.is_paused();
+    queue.pause_queue();
+    let result = maintenance::run(&state.db).await;
+    if !was_paused {
+        queue.resume_queue();
+    }
+    result
+}
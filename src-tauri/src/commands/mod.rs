@@ -1,5 +1,8 @@
 pub mod auth;
+pub mod backup;
 pub mod download;
+pub mod library;
+pub mod maintenance;
 pub mod settings;
 pub mod sidecar;
 pub mod sources;
@@ -28,6 +28,7 @@ pub async fn get_settings(state: State<'_, AppState>) -> Result<HashMap<String,
 
 /// Upsert a setting by key, and—when the key is `concurrent_downloads`—push
 /// the parsed value over the watch channel so the scheduler applies it live.
+/// A new `download_archive_path` gets the archive written to it first.
 #[tauri::command]
 pub async fn update_setting(
     key: String,
@@ -35,6 +36,15 @@ pub async fn update_setting(
     state: State<'_, AppState>,
     concurrency_tx: State<'_, ConcurrencyTx>,
 ) -> Result<(), String> {
+    if key == "filename_template" && !value.trim().is_empty() {
+        crate::download::template::validate(&value)?;
+    }
+
+    // Write the archive right away, so a bad path is reported here.
+    if key == crate::library::archive::ARCHIVE_PATH_KEY && !value.trim().is_empty() {
+        crate::library::archive::export_archive(&state.db, std::path::Path::new(&value)).await?;
+    }
+
     // Upsert
     let existing = Setting::find_by_id(&key)
         .one(&state.db)
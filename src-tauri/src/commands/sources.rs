@@ -30,6 +30,7 @@ pub struct SourceResponse {
     pub post_count: i64,
     pub is_self: bool,
     pub avatar_url: Option<String>,
+    pub filename_template: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -52,6 +53,8 @@ pub struct UpdateSourceRequest {
     pub source_id: i64,
     pub name: Option<String>,
     pub is_active: Option<bool>,
+    /// Empty clears the override.
+    pub filename_template: Option<String>,
 }
 
 #[tauri::command]
@@ -142,6 +145,7 @@ pub async fn get_sources_command(
             post_count: count,
             is_self,
             avatar_url,
+            filename_template: s.filename_template,
         });
     }
 
@@ -188,6 +192,16 @@ pub async fn update_source_command(
         active.is_active = Set(is_active);
     }
 
+    if let Some(template) = request.filename_template {
+        let template = template.trim().to_string();
+        if template.is_empty() {
+            active.filename_template = Set(None);
+        } else {
+            crate::download::template::validate(&template)?;
+            active.filename_template = Set(Some(template));
+        }
+    }
+
     source::Entity::update(active)
         .exec(&state.db)
         .await
@@ -8,10 +8,18 @@ use tauri_plugin_opener::OpenerExt;
 use trash::delete as move_to_trash;
 
 use crate::{
-    entity::{creator, media, post},
+    entity::{chapter, creator, media, post, sponsor_segment},
+    library::Library,
     AppState,
 };
 
+/// Media types stored alongside a download rather than shown as its content.
+const COMPANION_MEDIA_TYPES: &[&str] = &["SUBTITLE", "METADATA"];
+
+fn is_companion_media(m: &media::Model) -> bool {
+    COMPANION_MEDIA_TYPES.contains(&m.media_type.as_str())
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct PostsPage {
     pub posts: Vec<PostResponse>,
@@ -54,6 +62,55 @@ pub struct MediaResponse {
     pub height: Option<i32>,
     pub duration: Option<i32>,
     pub file_size: Option<i32>,
+    pub preview_path: Option<String>,
+    pub sprite_path: Option<String>,
+    pub sprite_vtt_path: Option<String>,
+    pub poster_path: Option<String>,
+    pub language: Option<String>,
+    pub loudness_lufs: Option<f64>,
+    /// AVAILABLE, or MISSING when the library scan couldn't find the file.
+    pub status: String,
+}
+
+impl MediaResponse {
+    /// The row as the frontend sees it, with `file_path` and the asset paths
+    /// resolved to absolute paths.
+    fn new(m: media::Model, library: &Library) -> Self {
+        let file_path = match library.resolve_media_path(&m) {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                m.file_path.clone()
+            }
+        };
+        let asset = |name: &Option<String>| {
+            let path = library.resolve_asset_path(&m, name.as_deref()?).ok()?;
+            Some(path.to_string_lossy().to_string())
+        };
+        let thumbnail_path = asset(&m.thumbnail_path);
+        let preview_path = asset(&m.preview_path);
+        let sprite_path = asset(&m.sprite_path);
+        let sprite_vtt_path = asset(&m.sprite_vtt_path);
+        let poster_path = asset(&m.poster_path);
+        Self {
+            id: m.id,
+            media_type: m.media_type,
+            file_path,
+            thumbnail_path,
+            order_index: m.order_index,
+            width: m.width,
+            height: m.height,
+            duration: m.duration,
+            file_size: m.file_size,
+            preview_path,
+            sprite_path,
+            sprite_vtt_path,
+            poster_path,
+            language: m.language,
+            loudness_lufs: m.loudness_lufs,
+            status: m.status,
+        }
+    }
 }
 
 #[tauri::command]
@@ -84,6 +141,9 @@ pub async fn get_posts(
         .load_many(media::Entity, &state.db)
         .await
         .map_err(|e| format!("Database error loading media: {}", e))?;
+    let library = Library::load(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
 
     let total = post::Entity::find()
         .filter(post::Column::Status.eq("COMPLETED"))
@@ -100,19 +160,11 @@ pub async fn get_posts(
         let creator_opt = creators.get(i).cloned().flatten();
         let medias = media_lists.get(i).cloned().unwrap_or_default();
 
+        // Subtitles and info.json are companions of the main media, not Wall items.
         let mut media_responses: Vec<MediaResponse> = medias
             .into_iter()
-            .map(|m| MediaResponse {
-                id: m.id,
-                media_type: m.media_type,
-                file_path: m.file_path,
-                thumbnail_path: m.thumbnail_path,
-                order_index: m.order_index,
-                width: m.width,
-                height: m.height,
-                duration: m.duration,
-                file_size: m.file_size,
-            })
+            .filter(|m| !is_companion_media(m))
+            .map(|m| MediaResponse::new(m, &library))
             .collect();
 
         // Ensure media is ordered by order_index
@@ -148,6 +200,54 @@ pub async fn get_posts(
     })
 }
 
+#[tauri::command]
+pub async fn get_post_subtitles(
+    state: State<'_, AppState>,
+    post_id: i64,
+) -> Result<Vec<MediaResponse>, String> {
+    let subtitles = media::Entity::find()
+        .filter(media::Column::PostId.eq(post_id))
+        .filter(media::Column::MediaType.eq("SUBTITLE"))
+        .order_by_asc(media::Column::OrderIndex)
+        .all(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading subtitles: {}", e))?;
+    let library = Library::load(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+
+    Ok(subtitles
+        .into_iter()
+        .map(|m| MediaResponse::new(m, &library))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_post_chapters(
+    state: State<'_, AppState>,
+    post_id: i64,
+) -> Result<Vec<chapter::Model>, String> {
+    chapter::Entity::find()
+        .filter(chapter::Column::PostId.eq(post_id))
+        .order_by_asc(chapter::Column::ChapterIndex)
+        .all(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading chapters: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_post_sponsor_segments(
+    state: State<'_, AppState>,
+    post_id: i64,
+) -> Result<Vec<sponsor_segment::Model>, String> {
+    sponsor_segment::Entity::find()
+        .filter(sponsor_segment::Column::PostId.eq(post_id))
+        .order_by_asc(sponsor_segment::Column::StartTime)
+        .all(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading SponsorBlock segments: {}", e))
+}
+
 #[tauri::command]
 pub async fn reveal_in_explorer(
     app_handle: tauri::AppHandle,
@@ -218,6 +318,9 @@ pub async fn get_trash_posts(
         .load_many(media::Entity, &state.db)
         .await
         .map_err(|e| format!("Database error loading media: {}", e))?;
+    let library = Library::load(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
 
     let total = post::Entity::find()
         .filter(post::Column::Status.eq("COMPLETED"))
@@ -234,19 +337,11 @@ pub async fn get_trash_posts(
         let creator_opt = creators.get(i).cloned().flatten();
         let medias = media_lists.get(i).cloned().unwrap_or_default();
 
+        // Subtitles and info.json are companions of the main media, not Wall items.
         let mut media_responses: Vec<MediaResponse> = medias
             .into_iter()
-            .map(|m| MediaResponse {
-                id: m.id,
-                media_type: m.media_type,
-                file_path: m.file_path,
-                thumbnail_path: m.thumbnail_path,
-                order_index: m.order_index,
-                width: m.width,
-                height: m.height,
-                duration: m.duration,
-                file_size: m.file_size,
-            })
+            .filter(|m| !is_companion_media(m))
+            .map(|m| MediaResponse::new(m, &library))
             .collect();
 
         media_responses.sort_by_key(|m| m.order_index);
@@ -294,6 +389,9 @@ pub async fn empty_trash_command(state: State<'_, AppState>) -> Result<usize, St
         return Ok(0);
     }
 
+    let library = Library::load(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
     let mut deleted_count = 0;
 
     for p in trashed_posts {
@@ -306,37 +404,154 @@ pub async fn empty_trash_command(state: State<'_, AppState>) -> Result<usize, St
 
         // Send files to recycling bin
         for m in medias {
-            if Path::new(&m.file_path).exists() {
-                let _ = move_to_trash(&m.file_path);
-            }
-            if let Some(thumb) = &m.thumbnail_path {
-                if Path::new(thumb).exists() {
-                    let _ = move_to_trash(thumb);
+            for path in library.asset_paths(&m) {
+                if path.exists() {
+                    let _ = move_to_trash(path);
                 }
             }
         }
 
-        // Hard delete media records
-        let _ = media::Entity::delete_many()
-            .filter(media::Column::PostId.eq(p.id))
-            .exec(&state.db)
-            .await;
-
-        // Hard delete download_tasks references
-        let _ = crate::entity::download_task::Entity::delete_many()
-            .filter(crate::entity::download_task::Column::PostId.eq(p.id))
-            .exec(&state.db)
-            .await;
-
-        // Hard delete post record
-        if post::Entity::delete_by_id(p.id)
-            .exec(&state.db)
+        // Hard delete the post with its media rows, chapters, segments and tasks
+        match crate::metadata::store::delete_post(&state.db, p.id).await {
+            Ok(()) => deleted_count += 1,
+            Err(e) => tracing::error!("Failed to delete post {}: {}", p.id, e),
+        }
+    }
+
+    Ok(deleted_count)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ThumbnailRepairReport {
+    pub checked: usize,
+    pub regenerated: usize,
+    pub failed: usize,
+}
+
+/// A thumbnail needs rebuilding when it is missing or empty on disk, or when a
+/// platform poster is available that it wasn't built from.
+fn needs_thumbnail(library: &Library, m: &media::Model, file_path: &Path) -> bool {
+    let thumbnail_ok = m
+        .thumbnail_path
+        .as_deref()
+        .and_then(|name| library.resolve_asset_path(m, name).ok())
+        .and_then(|p| std::fs::metadata(p).ok())
+        .is_some_and(|meta| meta.len() > 0);
+    let unused_poster = m.poster_path.is_none()
+        && crate::download::post_process::poster_output_path(file_path).exists();
+    !thumbnail_ok || unused_poster
+}
+
+#[tauri::command]
+pub async fn regenerate_thumbnails(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ThumbnailRepairReport, String> {
+    let ffmpeg =
+        crate::sidecar::get_binary_path(&app_handle, crate::sidecar::types::SidecarBinary::Ffmpeg)
+            .map_err(|e| format!("ffmpeg not available: {}", e))?;
+
+    let medias = media::Entity::find()
+        .all(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading media: {}", e))?;
+    let library = Library::load(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+
+    let mut report = ThumbnailRepairReport {
+        checked: medias.len(),
+        regenerated: 0,
+        failed: 0,
+    };
+
+    for m in medias {
+        if is_companion_media(&m) {
+            continue;
+        }
+        let Ok(file_path) = library.resolve_media_path(&m) else {
+            continue;
+        };
+        if !file_path.exists() || !needs_thumbnail(&library, &m, &file_path) {
+            continue;
+        }
+
+        match crate::queue::DownloadQueue::refresh_thumbnails(&state.db, &ffmpeg, &m, &file_path)
             .await
-            .is_ok()
         {
-            deleted_count += 1;
+            Ok(Some(_)) => report.regenerated += 1,
+            Ok(None) => report.failed += 1,
+            Err(e) => {
+                tracing::error!("Failed to store thumbnail for media {}: {}", m.id, e);
+                report.failed += 1;
+            }
         }
     }
 
-    Ok(deleted_count)
+    tracing::info!(
+        "Thumbnail repair: {} checked, {} regenerated, {} failed",
+        report.checked,
+        report.regenerated,
+        report.failed
+    );
+
+    Ok(report)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LoudnessReport {
+    pub normalized: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Loudness-normalize the audio/video media of a post to the configured target.
+/// Rows whose stored loudness already matches the target are skipped.
+#[tauri::command]
+pub async fn normalize_post_loudness(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    post_id: i64,
+) -> Result<LoudnessReport, String> {
+    let ffmpeg =
+        crate::sidecar::get_binary_path(&app_handle, crate::sidecar::types::SidecarBinary::Ffmpeg)
+            .map_err(|e| format!("ffmpeg not available: {}", e))?;
+    let target = crate::download::loudnorm::configured_target(&state.db).await;
+
+    let medias = media::Entity::find()
+        .filter(media::Column::PostId.eq(post_id))
+        .filter(media::Column::MediaType.is_in(["VIDEO", "AUDIO"]))
+        .all(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading media: {}", e))?;
+    let library = Library::load(&state.db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+
+    let mut report = LoudnessReport {
+        normalized: 0,
+        skipped: 0,
+        failed: 0,
+    };
+
+    for m in medias {
+        let Some(path) = library.resolve_media_path(&m).ok().filter(|p| p.exists()) else {
+            report.failed += 1;
+            continue;
+        };
+        match crate::queue::DownloadQueue::normalize_media_loudness(
+            &state.db, &ffmpeg, &m, &path, target,
+        )
+        .await
+        {
+            Ok(Some(_)) => report.normalized += 1,
+            Ok(None) => report.skipped += 1,
+            Err(e) => {
+                tracing::error!("Loudness normalization failed for media {}: {}", m.id, e);
+                report.failed += 1;
+            }
+        }
+    }
+
+    Ok(report)
 }
@@ -12,3 +12,8 @@ pub mod feed_type {
     pub const REELS: &str = "REELS";
     pub const POSTS: &str = "POSTS";
 }
+
+pub mod platform_id {
+    /// Posts imported from local files that couldn't be matched to a site.
+    pub const LOCAL: &str = "local";
+}
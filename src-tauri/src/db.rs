@@ -1,10 +1,17 @@
-use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbErr, Statement};
+use sea_orm::{
+    sea_query::OnConflict, ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbErr,
+    EntityTrait, Set, Statement,
+};
 use sea_orm_migration::MigratorTrait;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::entity::setting;
 use crate::migration::Migrator;
 
+/// File name of the database inside the app data directory.
+pub const DB_FILE_NAME: &str = "videodownloaderpro.db";
+
 /// Initializes the SQLite database, runs pending migrations, and returns the connection.
 ///
 /// The database file is stored at `<app_data_dir>/videodownloaderpro.db`.
@@ -18,7 +25,11 @@ pub async fn init_db(app_data_dir: PathBuf) -> Result<DatabaseConnection, DbErr>
         ))
     })?;
 
-    let db_path = app_data_dir.join("videodownloaderpro.db");
+    // A restore from backup is staged while the app runs and swapped in here.
+    crate::backup::apply_staged_restore(&app_data_dir)
+        .map_err(|e| DbErr::Custom(format!("Failed to apply restored database: {}", e)))?;
+
+    let db_path = app_data_dir.join(DB_FILE_NAME);
     let db_url = format!("sqlite:{}?mode=rwc", db_path.display());
 
     tracing::info!(path = %db_path.display(), "Connecting to SQLite database");
@@ -62,3 +73,42 @@ pub async fn init_test_db() -> Result<DatabaseConnection, DbErr> {
     Migrator::up(&db, None).await?;
     Ok(db)
 }
+
+/// Reads a single value from the `settings` table.
+///
+/// Missing keys, empty values and DB errors all resolve to `None` so callers can
+/// fall back to their own default.
+pub async fn get_setting(db: &impl ConnectionTrait, key: &str) -> Option<String> {
+    setting::Entity::find_by_id(key)
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+        .map(|s| s.value)
+        .filter(|v| !v.is_empty())
+}
+
+/// Reads a boolean setting stored as `"true"` / `"false"`.
+pub async fn get_bool_setting(db: &impl ConnectionTrait, key: &str, default: bool) -> bool {
+    get_setting(db, key)
+        .await
+        .and_then(|v| v.parse::<bool>().ok())
+        .unwrap_or(default)
+}
+
+/// Writes a value to the `settings` table, replacing any existing one.
+pub async fn set_setting(db: &impl ConnectionTrait, key: &str, value: &str) -> Result<(), DbErr> {
+    setting::Entity::insert(setting::ActiveModel {
+        key: Set(key.to_string()),
+        value: Set(value.to_string()),
+        updated_at: Set(chrono::Utc::now()),
+    })
+    .on_conflict(
+        OnConflict::column(setting::Column::Key)
+            .update_columns([setting::Column::Value, setting::Column::UpdatedAt])
+            .to_owned(),
+    )
+    .exec(db)
+    .await
+    .map(|_| ())
+}
@@ -0,0 +1,226 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use crate::metadata::models::YtDlpVideo;
+
+/// Tags written into an extracted audio file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub track: Option<u32>,
+    /// `YYYY-MM-DD`
+    pub date: Option<String>,
+}
+
+impl AudioTags {
+    /// Tags for a downloaded post. `creator` and `source` are the library's names for
+    /// the uploader and the playlist/source it came from. With `split_artist_title`,
+    /// a title of the form "Artist - Title" is split into both tags.
+    pub fn from_video(
+        video: &YtDlpVideo,
+        creator: Option<&str>,
+        source: Option<&str>,
+        split_artist_title: bool,
+    ) -> Self {
+        let mut title = Some(video.title.trim().to_string()).filter(|t| !t.is_empty());
+        let mut artist = creator
+            .map(String::from)
+            .or_else(|| video.uploader.clone())
+            .or_else(|| video.channel.clone());
+
+        if split_artist_title {
+            if let Some((a, t)) = title.as_deref().and_then(split_artist_title_str) {
+                artist = Some(a);
+                title = Some(t);
+            }
+        }
+
+        Self {
+            title,
+            artist,
+            album: video
+                .playlist_title
+                .clone()
+                .or_else(|| source.map(String::from)),
+            track: video.playlist_index,
+            date: video.upload_date.as_deref().and_then(format_upload_date),
+        }
+    }
+
+    /// `-metadata key=value` pairs for ffmpeg.
+    fn metadata_args(&self) -> Vec<String> {
+        let mut pairs = Vec::new();
+        if let Some(ref v) = self.title {
+            pairs.push(format!("title={}", v));
+        }
+        if let Some(ref v) = self.artist {
+            pairs.push(format!("artist={}", v));
+            pairs.push(format!("album_artist={}", v));
+        }
+        if let Some(ref v) = self.album {
+            pairs.push(format!("album={}", v));
+        }
+        if let Some(v) = self.track {
+            pairs.push(format!("track={}", v));
+        }
+        if let Some(ref v) = self.date {
+            pairs.push(format!("date={}", v));
+        }
+        pairs
+            .into_iter()
+            .flat_map(|p| ["-metadata".to_string(), p])
+            .collect()
+    }
+}
+
+/// "Artist - Title" → (artist, title). Both halves must be non-empty.
+fn split_artist_title_str(title: &str) -> Option<(String, String)> {
+    let (artist, rest) = title.split_once(" - ")?;
+    let (artist, rest) = (artist.trim(), rest.trim());
+    (!artist.is_empty() && !rest.is_empty()).then(|| (artist.to_string(), rest.to_string()))
+}
+
+/// yt-dlp's `YYYYMMDD` → `YYYY-MM-DD`.
+fn format_upload_date(date: &str) -> Option<String> {
+    if date.len() != 8 || !date.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]))
+}
+
+/// Whether ffmpeg can attach a cover picture to this container.
+/// (Ogg/Opus need a `METADATA_BLOCK_PICTURE` comment, which ffmpeg doesn't write.)
+fn supports_cover_art(ext: &str) -> bool {
+    matches!(ext, "mp3" | "m4a" | "mp4" | "flac")
+}
+
+/// Rewrite `audio_file` in place with `tags` and, where the container allows it,
+/// `cover` as embedded front-cover art. Streams are copied, not re-encoded.
+pub async fn write_tags(
+    ffmpeg_path: &Path,
+    audio_file: &Path,
+    tags: &AudioTags,
+    cover: Option<&Path>,
+) -> Result<(), String> {
+    let ext = audio_file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let stem = audio_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("audio");
+    let tagged = audio_file.with_file_name(format!("{}.tagged.{}", stem, ext));
+    let cover = cover.filter(|c| supports_cover_art(&ext) && c.exists());
+
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner").arg("-i").arg(audio_file);
+    if let Some(cover) = cover {
+        cmd.arg("-i").arg(cover);
+    }
+    cmd.args(["-map", "0:a", "-c", "copy", "-map_metadata", "0"]);
+    if cover.is_some() {
+        cmd.args([
+            "-map",
+            "1:v",
+            "-c:v",
+            "mjpeg",
+            "-disposition:v:0",
+            "attached_pic",
+            "-metadata:s:v",
+            "title=Album cover",
+            "-metadata:s:v",
+            "comment=Cover (front)",
+        ]);
+    }
+    if ext == "mp3" {
+        cmd.args(["-id3v2_version", "3"]);
+    }
+    cmd.args(tags.metadata_args()).arg("-y").arg(&tagged);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped());
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
+
+    if !output.status.success() || !tagged.exists() {
+        let _ = tokio::fs::remove_file(&tagged).await;
+        return Err(format!(
+            "ffmpeg could not tag {}: {}",
+            audio_file.display(),
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .last()
+                .unwrap_or_default()
+        ));
+    }
+
+    tokio::fs::rename(&tagged, audio_file)
+        .await
+        .map_err(|e| format!("Failed to replace audio file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(title: &str) -> YtDlpVideo {
+        serde_json::from_value(serde_json::json!({
+            "id": "abc",
+            "title": title,
+            "uploader": "Uploader",
+            "upload_date": "20240131",
+            "playlist_index": 3,
+            "playlist_title": "Best Of"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tags_from_video() {
+        let tags = AudioTags::from_video(&video("Song"), Some("Creator"), Some("Source"), false);
+        assert_eq!(
+            tags,
+            AudioTags {
+                title: Some("Song".to_string()),
+                artist: Some("Creator".to_string()),
+                album: Some("Best Of".to_string()),
+                track: Some(3),
+                date: Some("2024-01-31".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_artist_title_rule() {
+        let tags = AudioTags::from_video(&video("Band - Tune (Live)"), None, None, true);
+        assert_eq!(tags.artist.as_deref(), Some("Band"));
+        assert_eq!(tags.title.as_deref(), Some("Tune (Live)"));
+
+        // Titles without the separator keep the uploader as artist.
+        let tags = AudioTags::from_video(&video("Tune"), None, None, true);
+        assert_eq!(tags.artist.as_deref(), Some("Uploader"));
+        assert_eq!(tags.title.as_deref(), Some("Tune"));
+    }
+
+    #[test]
+    fn test_format_upload_date() {
+        assert_eq!(
+            format_upload_date("20240131").as_deref(),
+            Some("2024-01-31")
+        );
+        assert_eq!(format_upload_date("2024"), None);
+    }
+}
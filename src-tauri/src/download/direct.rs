@@ -0,0 +1,637 @@
+//! Native downloader for URLs that serve a media file directly (a CDN link, a file
+//! on a local server), skipping yt-dlp and its JS runtime entirely.
+
+use reqwest::header::{
+    HeaderMap, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE,
+};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+/// Minimum interval between progress callbacks.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Extensions accepted for `application/octet-stream` responses.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "webm", "mkv", "avi", "mov", "flv", "m4v", "mp3", "m4a", "wav", "aac", "ogg", "opus",
+    "flac", "jpg", "jpeg", "png", "gif", "webp",
+];
+
+/// A URL that serves a media file directly, as found by [`probe`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectMedia {
+    /// MIME type without parameters, e.g. "video/mp4".
+    pub content_type: String,
+    pub total_bytes: Option<u64>,
+    /// Name to save the file under, always with an extension.
+    pub filename: String,
+}
+
+/// A progress update in the same terms yt-dlp reports them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectProgress {
+    /// 0.0 to 100.0, or 0.0 when the size is unknown.
+    pub progress: f64,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub speed: String,
+    pub eta: String,
+}
+
+#[derive(Debug)]
+pub struct DirectDownload {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Hex SHA-256 of the whole file, computed while it was written.
+    pub sha256: String,
+}
+
+#[derive(Debug)]
+pub enum DirectError {
+    /// The `.part` file is left in place: a paused task resumes from it, while the
+    /// queue deletes it when the task was cancelled.
+    Cancelled {
+        downloaded_bytes: u64,
+        total_bytes: Option<u64>,
+    },
+    Failed(String),
+}
+
+pub fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .read_timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+/// Check whether `url` serves a media file. Tries `HEAD` first and falls back to
+/// a one-byte ranged `GET` for servers that don't answer `HEAD`.
+pub async fn probe(client: &reqwest::Client, url: &str) -> Option<DirectMedia> {
+    let head = client.head(url).send().await.ok();
+    let response = match head {
+        Some(r) if r.status().is_success() => r,
+        _ => client
+            .get(url)
+            .header(RANGE, "bytes=0-0")
+            .send()
+            .await
+            .ok()
+            .filter(|r| r.status().is_success())?,
+    };
+
+    let headers = response.headers();
+    let content_type = header_str(headers, CONTENT_TYPE)
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_lowercase())
+        .unwrap_or_default();
+    let total_bytes = if response.status() == StatusCode::PARTIAL_CONTENT {
+        header_str(headers, CONTENT_RANGE).and_then(content_range_total)
+    } else {
+        // `content_length()` describes the body, which a HEAD response doesn't have.
+        header_str(headers, CONTENT_LENGTH).and_then(|v| v.trim().parse().ok())
+    };
+
+    let filename = header_str(headers, CONTENT_DISPOSITION)
+        .and_then(disposition_filename)
+        .or_else(|| url_filename(response.url()))
+        .map(|n| sanitize_filename(&n))
+        .filter(|n| !n.is_empty());
+    let filename = with_extension(filename.as_deref().unwrap_or("download"), &content_type);
+
+    is_media(&content_type, &filename).then_some(DirectMedia {
+        content_type,
+        total_bytes,
+        filename,
+    })
+}
+
+/// Download `url` into `dir/<media.filename>` through a `.part` file, resuming an
+/// existing `.part` with a `Range` request when the server supports it. With a
+/// `rate_limit` (bytes per second) the transfer is paced to stay under it.
+pub async fn download(
+    client: &reqwest::Client,
+    url: &str,
+    dir: &Path,
+    media: &DirectMedia,
+    rate_limit: Option<u64>,
+    cancel_token: &CancellationToken,
+    mut on_progress: impl FnMut(DirectProgress),
+) -> Result<DirectDownload, DirectError> {
+    let target = dir.join(&media.filename);
+    let part = dir.join(format!("{}.part", media.filename));
+    let existing = tokio::fs::metadata(&part)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| DirectError::Failed(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    let resumed = existing > 0 && status == StatusCode::PARTIAL_CONTENT;
+    if existing > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // Nothing left to fetch: the previous attempt got the whole file.
+        if media.total_bytes == Some(existing) {
+            return finish(&part, &target, existing, hash_file(&part).await?).await;
+        }
+        let _ = tokio::fs::remove_file(&part).await;
+        return Err(DirectError::Failed(
+            "Partial file doesn't match the server's copy; restarting".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        return Err(DirectError::Failed(format!("Server answered {}", status)));
+    }
+
+    let (mut file, mut hasher, mut downloaded, total_bytes) = if resumed {
+        tracing::info!("Resuming {} from byte {}", media.filename, existing);
+        let hasher = hash_file(&part).await?;
+        let file = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&part)
+            .await
+            .map_err(|e| DirectError::Failed(format!("Failed to open part file: {}", e)))?;
+        let total = header_str(response.headers(), CONTENT_RANGE)
+            .and_then(content_range_total)
+            .or(media.total_bytes);
+        (file, hasher, existing, total)
+    } else {
+        let file = tokio::fs::File::create(&part)
+            .await
+            .map_err(|e| DirectError::Failed(format!("Failed to create part file: {}", e)))?;
+        let total = response.content_length().or(media.total_bytes);
+        (file, Sha256::new(), 0, total)
+    };
+
+    let started = Instant::now();
+    let resumed_from = downloaded;
+    let mut last_emit: Option<Instant> = None;
+
+    loop {
+        let chunk = tokio::select! {
+            biased;
+            _ = cancel_token.cancelled() => {
+                let _ = file.flush().await;
+                return Err(DirectError::Cancelled { downloaded_bytes: downloaded, total_bytes });
+            }
+            chunk = response.chunk() => chunk
+                .map_err(|e| DirectError::Failed(format!("Download interrupted: {}", e)))?,
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
+
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| DirectError::Failed(format!("Failed to write part file: {}", e)))?;
+        downloaded += chunk.len() as u64;
+
+        if let Some(limit) = rate_limit {
+            let due = Duration::from_secs_f64((downloaded - resumed_from) as f64 / limit as f64);
+            if let Some(ahead) = due.checked_sub(started.elapsed()) {
+                tokio::select! {
+                    _ = cancel_token.cancelled() => {}
+                    _ = tokio::time::sleep(ahead) => {}
+                }
+            }
+        }
+
+        if last_emit.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            last_emit = Some(Instant::now());
+            on_progress(progress_update(
+                downloaded,
+                total_bytes,
+                downloaded - resumed_from,
+                started.elapsed(),
+            ));
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| DirectError::Failed(format!("Failed to write part file: {}", e)))?;
+    drop(file);
+
+    if let Some(total) = total_bytes {
+        if downloaded != total {
+            return Err(DirectError::Failed(format!(
+                "Connection closed after {} of {} bytes",
+                downloaded, total
+            )));
+        }
+    }
+
+    on_progress(DirectProgress {
+        progress: 100.0,
+        downloaded_bytes: downloaded,
+        total_bytes: Some(downloaded),
+        speed: String::new(),
+        eta: String::new(),
+    });
+    finish(&part, &target, downloaded, hasher).await
+}
+
+async fn finish(
+    part: &Path,
+    target: &Path,
+    bytes: u64,
+    hasher: Sha256,
+) -> Result<DirectDownload, DirectError> {
+    tokio::fs::rename(part, target)
+        .await
+        .map_err(|e| DirectError::Failed(format!("Failed to rename part file: {}", e)))?;
+    Ok(DirectDownload {
+        path: target.to_path_buf(),
+        bytes,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+/// Feed an existing partial file into a fresh hasher so a resumed download still
+/// ends up with the checksum of the whole file.
+async fn hash_file(path: &Path) -> Result<Sha256, DirectError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| DirectError::Failed(format!("Failed to read part file: {}", e)))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .await
+            .map_err(|e| DirectError::Failed(format!("Failed to read part file: {}", e)))?;
+        if n == 0 {
+            return Ok(hasher);
+        }
+        hasher.update(&buf[..n]);
+    }
+}
+
+fn progress_update(
+    downloaded: u64,
+    total: Option<u64>,
+    session_bytes: u64,
+    elapsed: Duration,
+) -> DirectProgress {
+    let rate = session_bytes as f64 / elapsed.as_secs_f64().max(0.001);
+    let progress = total
+        .filter(|t| *t > 0)
+        .map(|t| (downloaded as f64 / t as f64 * 100.0).min(100.0))
+        .unwrap_or(0.0);
+    let eta = match total {
+        Some(t) if rate > 0.0 => format_eta((t.saturating_sub(downloaded) as f64 / rate) as u64),
+        _ => String::new(),
+    };
+    DirectProgress {
+        progress,
+        downloaded_bytes: downloaded,
+        total_bytes: total,
+        speed: format_speed(rate),
+        eta,
+    }
+}
+
+/// Transfer rate in yt-dlp's style, e.g. "2.00MiB/s".
+pub fn format_speed(bytes_per_sec: f64) -> String {
+    if bytes_per_sec >= 1024.0 * 1024.0 {
+        format!("{:.2}MiB/s", bytes_per_sec / (1024.0 * 1024.0))
+    } else {
+        format!("{:.2}KiB/s", bytes_per_sec / 1024.0)
+    }
+}
+
+/// Remaining time in yt-dlp's style: "MM:SS", or "HH:MM:SS" past an hour.
+fn format_eta(secs: u64) -> String {
+    if secs >= 3600 {
+        format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        )
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: reqwest::header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// `bytes 0-0/12345` → 12345.
+fn content_range_total(value: &str) -> Option<u64> {
+    value.rsplit('/').next()?.trim().parse().ok()
+}
+
+/// The file name from a `Content-Disposition` header, preferring the RFC 5987
+/// `filename*=UTF-8''…` form.
+fn disposition_filename(value: &str) -> Option<String> {
+    let params: Vec<&str> = value.split(';').map(str::trim).collect();
+    if let Some(encoded) = params.iter().find_map(|p| p.strip_prefix("filename*=")) {
+        let encoded = encoded.split_once("''").map_or(encoded, |(_, v)| v);
+        return Some(percent_decode(encoded.trim_matches('"')));
+    }
+    params
+        .iter()
+        .find_map(|p| p.strip_prefix("filename="))
+        .map(|v| v.trim_matches('"').to_string())
+}
+
+fn url_filename(url: &reqwest::Url) -> Option<String> {
+    let segment = url.path_segments()?.rev().find(|s| !s.is_empty())?;
+    Some(percent_decode(segment))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 3 <= bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Bytes per second for a yt-dlp style rate limit such as "5M", "500K" or "1.5M"
+/// (binary multiples, like yt-dlp's `--limit-rate`).
+pub fn parse_rate_limit(limit: &str) -> Option<u64> {
+    let limit = limit.trim();
+    let (number, multiplier) = match limit.chars().last()?.to_ascii_uppercase() {
+        'K' => (&limit[..limit.len() - 1], 1024.0),
+        'M' => (&limit[..limit.len() - 1], 1024.0 * 1024.0),
+        'G' => (&limit[..limit.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        _ => (limit, 1.0),
+    };
+    let bytes = number.trim().parse::<f64>().ok()? * multiplier;
+    (bytes >= 1.0).then_some(bytes as u64)
+}
+
+/// Strip path separators and characters Windows doesn't allow in file names.
+fn sanitize_filename(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .trim_matches('.')
+        .to_string()
+}
+
+fn extension_for(content_type: &str) -> Option<&'static str> {
+    Some(match content_type {
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
+        "video/x-matroska" => "mkv",
+        "video/quicktime" => "mov",
+        "video/x-msvideo" => "avi",
+        "video/x-flv" => "flv",
+        "audio/mpeg" => "mp3",
+        "audio/mp4" | "audio/x-m4a" => "m4a",
+        "audio/aac" => "aac",
+        "audio/ogg" | "application/ogg" => "ogg",
+        "audio/opus" => "opus",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => return None,
+    })
+}
+
+/// Give `name` an extension from the content type if it has none.
+fn with_extension(name: &str, content_type: &str) -> String {
+    if Path::new(name).extension().is_some() {
+        return name.to_string();
+    }
+    match extension_for(content_type) {
+        Some(ext) => format!("{}.{}", name, ext),
+        None => name.to_string(),
+    }
+}
+
+fn is_media(content_type: &str, filename: &str) -> bool {
+    if content_type.starts_with("video/")
+        || content_type.starts_with("audio/")
+        || content_type.starts_with("image/")
+        || content_type == "application/ogg"
+    {
+        return true;
+    }
+    // Servers often label any download as octet-stream; trust a media extension then.
+    let ext = Path::new(filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    (content_type == "application/octet-stream" || content_type.is_empty())
+        && MEDIA_EXTENSIONS.contains(&ext.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Serve `body` over HTTP/1.1 on localhost, honouring `Range: bytes=N-`.
+    /// Returns the base URL and the ranges requested by `GET`s.
+    async fn serve(
+        body: Vec<u8>,
+        content_type: &'static str,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<Option<u64>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let ranges = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let body = body.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = socket.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    let request = lines.next_line().await.unwrap().unwrap_or_default();
+                    let mut start = None;
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some(v) = line.to_lowercase().strip_prefix("range: bytes=") {
+                            start = v.split('-').next().and_then(|s| s.parse::<u64>().ok());
+                        }
+                    }
+                    let is_head = request.starts_with("HEAD");
+                    if !is_head {
+                        seen.lock().unwrap().push(start);
+                    }
+
+                    let from = start.unwrap_or(0) as usize;
+                    let slice = &body[from.min(body.len())..];
+                    let head = match start {
+                        Some(s) => format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\n",
+                            s,
+                            body.len() - 1,
+                            body.len()
+                        ),
+                        None => "HTTP/1.1 200 OK\r\n".to_string(),
+                    };
+                    let head = format!(
+                        "{}Content-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        head,
+                        content_type,
+                        slice.len()
+                    );
+                    let _ = write.write_all(head.as_bytes()).await;
+                    if !is_head {
+                        let _ = write.write_all(slice).await;
+                    }
+                    let _ = write.shutdown().await;
+                });
+            }
+        });
+
+        (format!("http://{}", addr), ranges)
+    }
+
+    fn sha256_hex(data: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    #[tokio::test]
+    async fn test_probe_and_download_from_local_server() {
+        let body: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let (base, _) = serve(body.clone(), "video/mp4").await;
+        let dir = std::env::temp_dir().join(format!("vdp_direct_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let client = client().unwrap();
+        let url = format!("{}/files/My%20Clip", base);
+        let media = probe(&client, &url).await.expect("media response");
+        assert_eq!(media.content_type, "video/mp4");
+        assert_eq!(media.filename, "My Clip.mp4");
+        assert_eq!(media.total_bytes, Some(body.len() as u64));
+
+        let mut last = None;
+        let result = download(
+            &client,
+            &url,
+            &dir,
+            &media,
+            None,
+            &CancellationToken::new(),
+            |p| last = Some(p),
+        )
+        .await
+        .expect("download");
+
+        assert_eq!(tokio::fs::read(&result.path).await.unwrap(), body);
+        assert_eq!(result.sha256, sha256_hex(&body));
+        assert_eq!(last.map(|p| p.progress), Some(100.0));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_resumes_part_file_with_range() {
+        let body: Vec<u8> = (0..100_000u32).map(|i| (i % 13) as u8).collect();
+        let (base, ranges) = serve(body.clone(), "audio/mpeg").await;
+        let dir = std::env::temp_dir().join(format!("vdp_direct_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let client = client().unwrap();
+        let url = format!("{}/song.mp3", base);
+        let media = probe(&client, &url).await.expect("media response");
+        tokio::fs::write(dir.join("song.mp3.part"), &body[..40_000])
+            .await
+            .unwrap();
+
+        let result = download(
+            &client,
+            &url,
+            &dir,
+            &media,
+            None,
+            &CancellationToken::new(),
+            |_| {},
+        )
+        .await
+        .expect("download");
+
+        assert_eq!(ranges.lock().unwrap().as_slice(), &[Some(40_000)]);
+        assert_eq!(tokio::fs::read(&result.path).await.unwrap(), body);
+        assert_eq!(result.sha256, sha256_hex(&body));
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn test_probe_rejects_html() {
+        let (base, _) = serve(b"<html></html>".to_vec(), "text/html").await;
+        assert!(probe(&client().unwrap(), &format!("{}/page", base))
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_filename_detection() {
+        assert_eq!(
+            disposition_filename("attachment; filename*=UTF-8''caf%C3%A9.mp4").as_deref(),
+            Some("café.mp4")
+        );
+        assert_eq!(
+            disposition_filename("attachment; filename=\"clip.webm\"").as_deref(),
+            Some("clip.webm")
+        );
+        assert_eq!(sanitize_filename("a/b:c?.mp4"), "a_b_c_.mp4");
+        assert_eq!(with_extension("stream", "audio/mpeg"), "stream.mp3");
+        assert!(is_media("application/octet-stream", "x.mkv"));
+        assert!(!is_media("application/octet-stream", "x.zip"));
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("My%20Clip"), "My Clip");
+        assert_eq!(percent_decode("a%20"), "a ");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("a%2"), "a%2");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        assert_eq!(parse_rate_limit("5M"), Some(5 * 1024 * 1024));
+        assert_eq!(parse_rate_limit("500k"), Some(500 * 1024));
+        assert_eq!(parse_rate_limit("1.5M"), Some(1024 * 1024 * 3 / 2));
+        assert_eq!(parse_rate_limit("2048"), Some(2048));
+        assert_eq!(parse_rate_limit(""), None);
+        assert_eq!(parse_rate_limit("fast"), None);
+    }
+}
@@ -0,0 +1,110 @@
+use sea_orm::ConnectionTrait;
+use std::path::PathBuf;
+
+/// aria2c refuses more connections per server than this.
+const ARIA2C_MAX_CONNECTIONS: u32 = 16;
+
+/// How yt-dlp transfers the media: how many DASH/HLS fragments it fetches at
+/// once, and whether it hands the transfer to aria2c.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloaderConfig {
+    pub concurrent_fragments: u32,
+    /// Path of the aria2c sidecar, when enabled and installed.
+    pub aria2c: Option<PathBuf>,
+}
+
+impl DownloaderConfig {
+    /// Resolve the configuration for a URL on `platform` (a `detect_platform`
+    /// id) from the `concurrent_fragments_<platform>` and `use_aria2c` settings.
+    /// URLs without a known platform use `concurrent_fragments_other`.
+    /// `aria2c_path` is the installed sidecar, if any; it is only used when enabled.
+    pub async fn resolve(
+        db: &impl ConnectionTrait,
+        platform: Option<&str>,
+        aria2c_path: Option<PathBuf>,
+    ) -> Self {
+        let key = format!("concurrent_fragments_{}", platform.unwrap_or("other"));
+        let concurrent_fragments = crate::db::get_setting(db, &key)
+            .await
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(1);
+
+        let aria2c = if crate::db::get_bool_setting(db, "use_aria2c", false).await {
+            if aria2c_path.is_none() {
+                tracing::warn!("aria2c is enabled but not installed, using yt-dlp's downloader");
+            }
+            aria2c_path
+        } else {
+            None
+        };
+
+        Self {
+            concurrent_fragments,
+            aria2c,
+        }
+    }
+
+    /// yt-dlp arguments for this configuration. `rate_limit` is repeated for
+    /// aria2c so the limit holds however yt-dlp forwards it. Live recordings
+    /// keep yt-dlp's own downloader, which is what a stop request winds down.
+    pub fn yt_dlp_args(&self, rate_limit: Option<&str>, is_live: bool) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.concurrent_fragments > 1 {
+            args.push("--concurrent-fragments".to_string());
+            args.push(self.concurrent_fragments.to_string());
+        }
+
+        if let Some(ref aria2c) = self.aria2c {
+            if !is_live {
+                let connections = self.concurrent_fragments.clamp(1, ARIA2C_MAX_CONNECTIONS);
+                // Summary lines are what the progress parser reads from aria2c.
+                let mut aria2c_args = format!(
+                    "aria2c:-x {0} -s {0} -k 1M --summary-interval=1 --console-log-level=warn",
+                    connections
+                );
+                if let Some(limit) = rate_limit.map(str::trim).filter(|l| !l.is_empty()) {
+                    aria2c_args.push_str(&format!(" --max-overall-download-limit={}", limit));
+                }
+                args.push("--downloader".to_string());
+                args.push(aria2c.to_string_lossy().to_string());
+                args.push("--downloader-args".to_string());
+                args.push(aria2c_args);
+            }
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[tokio::test]
+    async fn test_resolve_per_platform_and_aria2c() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let aria2c = PathBuf::from("/bin/aria2c");
+
+        let defaults = DownloaderConfig::resolve(&db, Some("youtube"), Some(aria2c.clone())).await;
+        assert!(defaults.yt_dlp_args(None, false).is_empty());
+
+        db::set_setting(&db, "concurrent_fragments_youtube", "8").await.unwrap();
+        db::set_setting(&db, "use_aria2c", "true").await.unwrap();
+
+        let youtube = DownloaderConfig::resolve(&db, Some("youtube"), Some(aria2c.clone())).await;
+        let args = youtube.yt_dlp_args(Some("5M"), false);
+        assert_eq!(
+            &args[..4],
+            ["--concurrent-fragments", "8", "--downloader", "/bin/aria2c"]
+        );
+        assert!(args[5].starts_with("aria2c:-x 8 -s 8 "));
+        assert!(args[5].ends_with("--max-overall-download-limit=5M"));
+
+        // Live recordings keep yt-dlp's downloader.
+        assert_eq!(youtube.yt_dlp_args(None, true).len(), 2);
+
+        let other = DownloaderConfig::resolve(&db, None, None).await;
+        assert!(other.yt_dlp_args(None, false).is_empty());
+    }
+}
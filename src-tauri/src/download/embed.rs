@@ -0,0 +1,87 @@
+use sea_orm::ConnectionTrait;
+
+use crate::metadata::format_processor::DownloadOptions;
+
+/// What a download writes into (or next to) the media file so it stays
+/// self-describing outside the library database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbedConfig {
+    pub embed_metadata: bool,
+    pub embed_thumbnail: bool,
+    pub write_info_json: bool,
+}
+
+impl EmbedConfig {
+    /// Resolve the flags for a task: its own choices first, then the
+    /// `embed_metadata`, `embed_thumbnail` and `write_info_json` settings.
+    pub async fn resolve(db: &impl ConnectionTrait, options: Option<&DownloadOptions>) -> Self {
+        let embed_metadata = match options.and_then(|o| o.embed_metadata) {
+            Some(v) => v,
+            None => crate::db::get_bool_setting(db, "embed_metadata", true).await,
+        };
+        let embed_thumbnail = match options.and_then(|o| o.embed_thumbnail) {
+            Some(v) => v,
+            None => crate::db::get_bool_setting(db, "embed_thumbnail", false).await,
+        };
+        let write_info_json = match options.and_then(|o| o.write_info_json) {
+            Some(v) => v,
+            None => crate::db::get_bool_setting(db, "write_info_json", false).await,
+        };
+
+        Self {
+            embed_metadata,
+            embed_thumbnail,
+            write_info_json,
+        }
+    }
+
+    /// yt-dlp arguments for this configuration.
+    pub fn yt_dlp_args(&self) -> Vec<&'static str> {
+        let mut args = Vec::new();
+        if self.embed_metadata {
+            args.push("--embed-metadata");
+        }
+        if self.embed_thumbnail {
+            args.push("--embed-thumbnail");
+        }
+        if self.write_info_json {
+            args.push("--write-info-json");
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use sea_orm::{EntityTrait, Set};
+
+    #[tokio::test]
+    async fn test_resolve_uses_settings_unless_task_overrides() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+
+        let defaults = EmbedConfig::resolve(&db, None).await;
+        assert_eq!(defaults.yt_dlp_args(), vec!["--embed-metadata"]);
+
+        crate::entity::setting::Entity::insert(crate::entity::setting::ActiveModel {
+            key: Set("write_info_json".to_string()),
+            value: Set("true".to_string()),
+            updated_at: Set(chrono::Utc::now()),
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+
+        let options = DownloadOptions {
+            embed_metadata: Some(false),
+            embed_thumbnail: Some(true),
+            ..Default::default()
+        };
+        let resolved = EmbedConfig::resolve(&db, Some(&options)).await;
+        assert_eq!(
+            resolved.yt_dlp_args(),
+            vec!["--embed-thumbnail", "--write-info-json"]
+        );
+    }
+}
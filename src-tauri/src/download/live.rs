@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// How long yt-dlp gets to wind down after a stop request before it is killed.
+pub const STOP_GRACE: Duration = Duration::from_secs(15);
+
+/// `HH:MM:SS` of recorded time.
+pub fn format_elapsed(secs: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+/// Average recording rate in yt-dlp's style, e.g. "1.25MiB/s".
+pub fn format_rate(bytes: u64, secs: u64) -> Option<String> {
+    if secs == 0 {
+        return None;
+    }
+    Some(super::direct::format_speed(bytes as f64 / secs as f64))
+}
+
+/// Files of the recording named `stem` in `dir` that weren't there before it
+/// started. Other downloads write to the same folder at the same time.
+async fn new_files(dir: &Path, stem: &str, pre_download_files: &HashSet<OsString>) -> Vec<PathBuf> {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    while let Ok(Some(e)) = entries.next_entry().await {
+        let name = e.file_name();
+        if is_recording_file(&name.to_string_lossy(), stem) && !pre_download_files.contains(&name) {
+            files.push(e.path());
+        }
+    }
+    files
+}
+
+/// `Title [id].mp4.part` belongs to the recording `Title [id]`; `Title [id2].mp4` doesn't.
+fn is_recording_file(name: &str, stem: &str) -> bool {
+    name.strip_prefix(stem)
+        .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Bytes written so far by the recording named `stem`, partial files included.
+pub async fn recording_bytes(
+    dir: &Path,
+    stem: &str,
+    pre_download_files: &HashSet<OsString>,
+) -> u64 {
+    let mut total = 0;
+    for path in new_files(dir, stem, pre_download_files).await {
+        if let Ok(m) = tokio::fs::metadata(&path).await {
+            total += m.len();
+        }
+    }
+    total
+}
+
+/// yt-dlp's errors for a premiere or stream that hasn't started yet.
+pub fn is_not_started_error(message: &str) -> bool {
+    const MARKERS: &[&str] = &[
+        "Premieres in",
+        "Premiere will begin",
+        "This live event will begin",
+        "is_upcoming",
+    ];
+    MARKERS.iter().any(|m| message.contains(m))
+}
+
+/// `Title.mp4.part` → `Title.mp4`. Fragment leftovers (`.part-Frag12`) aren't media.
+fn finished_name(part_name: &str) -> Option<&str> {
+    let name = part_name.strip_suffix(".part")?;
+    Path::new(name).extension().is_some().then_some(name)
+}
+
+/// yt-dlp bookkeeping left behind by an interrupted download.
+fn is_leftover(name: &str) -> bool {
+    name.ends_with(".ytdl") || name.contains(".part-Frag")
+}
+
+/// Turn the partial files of a stopped recording named `stem` into finished ones.
+///
+/// A stopped stream leaves its data in `.part` files, usually MPEG-TS whatever
+/// their extension says. Each is remuxed into its named container (streams are
+/// copied), or just renamed if ffmpeg is unavailable or fails.
+pub async fn finalize_recording(
+    ffmpeg_path: Option<&Path>,
+    dir: &Path,
+    stem: &str,
+    pre_download_files: &HashSet<OsString>,
+) -> Vec<PathBuf> {
+    let mut finished = Vec::new();
+
+    for path in new_files(dir, stem, pre_download_files).await {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if is_leftover(name) {
+            let _ = tokio::fs::remove_file(&path).await;
+            continue;
+        }
+        let Some(target) = finished_name(name).map(|n| dir.join(n)) else {
+            continue;
+        };
+
+        let remuxed = match ffmpeg_path {
+            Some(ffmpeg) => remux(ffmpeg, &path, &target).await,
+            None => false,
+        };
+        if remuxed {
+            let _ = tokio::fs::remove_file(&path).await;
+        } else if let Err(e) = tokio::fs::rename(&path, &target).await {
+            tracing::warn!("Failed to finalize {}: {}", path.display(), e);
+            continue;
+        }
+
+        tracing::info!("Finalized recording: {}", target.display());
+        finished.push(target);
+    }
+
+    finished
+}
+
+async fn remux(ffmpeg_path: &Path, input: &Path, output: &Path) -> bool {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner")
+        .arg("-i")
+        .arg(input)
+        .args(["-map", "0", "-c", "copy", "-y"])
+        .arg(output);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+
+    match cmd.status().await {
+        Ok(status) if status.success() && output.exists() => true,
+        _ => {
+            tracing::warn!("ffmpeg could not remux {}", input.display());
+            let _ = tokio::fs::remove_file(output).await;
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finished_name() {
+        assert_eq!(finished_name("Live [abc].mp4.part"), Some("Live [abc].mp4"));
+        assert_eq!(finished_name("Live.mp4"), None);
+        assert_eq!(finished_name("Live.mp4.part-Frag12"), None);
+        assert!(is_leftover("Live.mp4.part-Frag12.part"));
+        assert!(is_leftover("Live.mp4.ytdl"));
+    }
+
+    #[test]
+    fn test_is_recording_file() {
+        assert!(is_recording_file("Live [abc].mp4.part", "Live [abc]"));
+        assert!(is_recording_file(
+            "Live [abc].f96.mp4.part-Frag3",
+            "Live [abc]"
+        ));
+        assert!(!is_recording_file("Live [abc2].mp4.part", "Live [abc]"));
+        assert!(!is_recording_file("Other.mp4.part", "Live [abc]"));
+    }
+
+    #[test]
+    fn test_not_started_error() {
+        assert!(is_not_started_error(
+            "ERROR: [youtube] abc: This live event will begin in 3 hours."
+        ));
+        assert!(is_not_started_error(
+            "ERROR: [youtube] abc: Premieres in 20 minutes"
+        ));
+        assert!(!is_not_started_error(
+            "ERROR: [youtube] abc: Video unavailable"
+        ));
+    }
+
+    #[test]
+    fn test_format_elapsed_and_rate() {
+        assert_eq!(format_elapsed(3725), "01:02:05");
+        assert_eq!(
+            format_rate(3 * 1024 * 1024, 2).as_deref(),
+            Some("1.50MiB/s")
+        );
+        assert_eq!(format_rate(1024, 0), None);
+    }
+}
@@ -0,0 +1,292 @@
+use sea_orm::ConnectionTrait;
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::path::Path;
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use crate::metadata::format_processor::DownloadOptions;
+
+/// Default integrated loudness target (LUFS), the common podcast/streaming level.
+pub const DEFAULT_TARGET_LUFS: f64 = -16.0;
+
+/// True-peak ceiling (dBTP) and loudness range (LU) passed to `loudnorm`.
+const TRUE_PEAK: f64 = -1.5;
+const LOUDNESS_RANGE: f64 = 11.0;
+
+/// Files measured within this many LU of the target are left alone.
+const TOLERANCE_LU: f64 = 1.0;
+
+/// The loudness target for a task, if normalization is enabled for it: its own
+/// `loudnorm` choice, else the `loudnorm_enabled` setting; the target comes from
+/// the `loudnorm_target` setting.
+pub async fn resolve_target(
+    db: &impl ConnectionTrait,
+    options: Option<&DownloadOptions>,
+) -> Option<f64> {
+    let enabled = match options.and_then(|o| o.loudnorm) {
+        Some(v) => v,
+        None => crate::db::get_bool_setting(db, "loudnorm_enabled", false).await,
+    };
+    if !enabled {
+        return None;
+    }
+    Some(configured_target(db).await)
+}
+
+/// The `loudnorm_target` setting, clamped to the range `loudnorm` accepts.
+pub async fn configured_target(db: &impl ConnectionTrait) -> f64 {
+    crate::db::get_setting(db, "loudnorm_target")
+        .await
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .unwrap_or(DEFAULT_TARGET_LUFS)
+        .clamp(-70.0, -5.0)
+}
+
+/// Whether a stored measurement already sits at the target.
+pub fn is_normalized(loudness_lufs: Option<f64>, target: f64) -> bool {
+    loudness_lufs.is_some_and(|l| (l - target).abs() <= TOLERANCE_LU)
+}
+
+/// First-pass analysis printed by `loudnorm=print_format=json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoudnessMeasurement {
+    pub input_i: f64,
+    pub input_tp: f64,
+    pub input_lra: f64,
+    pub input_thresh: f64,
+    pub target_offset: f64,
+}
+
+/// `loudnorm` reports every value as a JSON string.
+#[derive(Deserialize)]
+struct LoudnormReport {
+    input_i: String,
+    input_tp: String,
+    input_lra: String,
+    input_thresh: String,
+    #[serde(default)]
+    output_i: Option<String>,
+    target_offset: String,
+}
+
+/// The JSON block `loudnorm` prints at the end of ffmpeg's stderr.
+fn parse_report(stderr: &str) -> Option<LoudnormReport> {
+    let start = stderr.rfind('{')?;
+    let end = start + stderr[start..].find('}')?;
+    serde_json::from_str(&stderr[start..=end]).ok()
+}
+
+fn parse_measurement(stderr: &str) -> Option<LoudnessMeasurement> {
+    let report = parse_report(stderr)?;
+    Some(LoudnessMeasurement {
+        input_i: report.input_i.trim().parse().ok()?,
+        input_tp: report.input_tp.trim().parse().ok()?,
+        input_lra: report.input_lra.trim().parse().ok()?,
+        input_thresh: report.input_thresh.trim().parse().ok()?,
+        target_offset: report.target_offset.trim().parse().ok()?,
+    })
+}
+
+fn loudnorm_filter(target: f64, measured: Option<&LoudnessMeasurement>) -> String {
+    let mut filter = format!(
+        "loudnorm=I={}:TP={}:LRA={}",
+        target, TRUE_PEAK, LOUDNESS_RANGE
+    );
+    if let Some(m) = measured {
+        filter.push_str(&format!(
+            ":measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+            m.input_i, m.input_tp, m.input_lra, m.input_thresh, m.target_offset
+        ));
+    }
+    filter.push_str(":print_format=json");
+    filter
+}
+
+/// Audio encoder arguments for re-encoding the normalized track in the same container.
+fn audio_codec_args(ext: &str) -> &'static [&'static str] {
+    match ext {
+        "mp3" => &["-c:a", "libmp3lame", "-q:a", "2"],
+        "opus" | "ogg" | "webm" => &["-c:a", "libopus", "-b:a", "160k"],
+        "flac" => &["-c:a", "flac"],
+        "wav" => &["-c:a", "pcm_s16le"],
+        _ => &["-c:a", "aac", "-b:a", "192k"],
+    }
+}
+
+async fn run_ffmpeg(ffmpeg_path: &Path, args: &[OsString]) -> Result<(bool, String), String> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner").arg("-nostats").args(args);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
+    Ok((
+        output.status.success(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    ))
+}
+
+/// First pass: measure the integrated loudness of `file`.
+pub async fn measure(
+    ffmpeg_path: &Path,
+    file: &Path,
+    target: f64,
+) -> Result<LoudnessMeasurement, String> {
+    let filter = loudnorm_filter(target, None);
+    let (_, stderr) = run_ffmpeg(
+        ffmpeg_path,
+        &[
+            "-i".into(),
+            file.into(),
+            "-vn".into(),
+            "-af".into(),
+            filter.into(),
+            "-f".into(),
+            "null".into(),
+            "-".into(),
+        ],
+    )
+    .await?;
+    parse_measurement(&stderr)
+        .ok_or_else(|| format!("No loudness measurement for {}", file.display()))
+}
+
+/// Normalize `file` in place to `target` LUFS using two `loudnorm` passes.
+/// Video streams are copied; only the audio is re-encoded. Returns the resulting
+/// integrated loudness, which is the first-pass measurement if the file was
+/// already within tolerance and left untouched. Cancelling the token stops
+/// ffmpeg and leaves the file untouched.
+pub async fn normalize(
+    ffmpeg_path: &Path,
+    file: &Path,
+    target: f64,
+    cancel_token: &CancellationToken,
+) -> Result<f64, String> {
+    let cancelled = || "Loudness normalization cancelled".to_string();
+    let measured = tokio::select! {
+        _ = cancel_token.cancelled() => return Err(cancelled()),
+        measured = measure(ffmpeg_path, file, target) => measured?,
+    };
+    if is_normalized(Some(measured.input_i), target) {
+        tracing::info!(
+            "{} already at {:.1} LUFS, skipping normalization",
+            file.display(),
+            measured.input_i
+        );
+        return Ok(measured.input_i);
+    }
+
+    let ext = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or("media");
+    let output = file.with_file_name(format!("{}.loudnorm.{}", stem, ext));
+
+    let filter = loudnorm_filter(target, Some(&measured));
+    let mut args: Vec<OsString> = vec![
+        "-i".into(),
+        file.into(),
+        "-map".into(),
+        "0".into(),
+        "-map_metadata".into(),
+        "0".into(),
+        "-c".into(),
+        "copy".into(),
+        "-af".into(),
+        filter.into(),
+    ];
+    args.extend(audio_codec_args(&ext).iter().map(OsString::from));
+    // loudnorm resamples to 192 kHz internally; bring it back to a normal rate.
+    args.extend(["-ar".into(), "48000".into(), "-y".into()]);
+    args.push(output.clone().into());
+
+    let (success, stderr) = tokio::select! {
+        _ = cancel_token.cancelled() => {
+            let _ = tokio::fs::remove_file(&output).await;
+            return Err(cancelled());
+        }
+        result = run_ffmpeg(ffmpeg_path, &args) => result?,
+    };
+    if !success || !output.exists() {
+        let _ = tokio::fs::remove_file(&output).await;
+        return Err(format!(
+            "ffmpeg could not normalize {}: {}",
+            file.display(),
+            stderr.lines().last().unwrap_or_default()
+        ));
+    }
+
+    tokio::fs::rename(&output, file)
+        .await
+        .map_err(|e| format!("Failed to replace media file: {}", e))?;
+
+    let result = parse_report(&stderr)
+        .and_then(|r| r.output_i)
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .unwrap_or(target);
+    tracing::info!(
+        "Normalized {} from {:.1} to {:.1} LUFS",
+        file.display(),
+        measured.input_i,
+        result
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST_PASS: &str = r#"[Parsed_loudnorm_0 @ 0x5581]
+{
+	"input_i" : "-27.61",
+	"input_tp" : "-4.47",
+	"input_lra" : "18.06",
+	"input_thresh" : "-39.20",
+	"output_i" : "-16.58",
+	"output_tp" : "-1.50",
+	"output_lra" : "14.78",
+	"output_thresh" : "-27.71",
+	"normalization_type" : "dynamic",
+	"target_offset" : "0.58"
+}"#;
+
+    #[test]
+    fn test_parse_measurement() {
+        let m = parse_measurement(FIRST_PASS).expect("measurement");
+        assert_eq!(m.input_i, -27.61);
+        assert_eq!(m.input_thresh, -39.20);
+        assert_eq!(m.target_offset, 0.58);
+        assert!(parse_measurement("no json here").is_none());
+    }
+
+    #[test]
+    fn test_second_pass_filter_uses_measurement() {
+        let m = parse_measurement(FIRST_PASS).unwrap();
+        assert_eq!(
+            loudnorm_filter(-16.0, Some(&m)),
+            "loudnorm=I=-16:TP=-1.5:LRA=11:measured_I=-27.61:measured_TP=-4.47:measured_LRA=18.06:measured_thresh=-39.2:offset=0.58:linear=true:print_format=json"
+        );
+    }
+
+    #[test]
+    fn test_is_normalized_within_tolerance() {
+        assert!(is_normalized(Some(-16.4), -16.0));
+        assert!(!is_normalized(Some(-23.0), -16.0));
+        assert!(!is_normalized(None, -16.0));
+    }
+}
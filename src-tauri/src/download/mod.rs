@@ -1,5 +1,15 @@
+pub mod audio_tags;
+pub mod direct;
+pub mod downloader;
+pub mod embed;
+pub mod live;
+pub mod loudnorm;
 pub mod parser;
 pub mod post_process;
+pub mod preview;
+pub mod sponsorblock;
+pub mod template;
+pub mod transcode;
 pub mod worker;
 
 #[cfg(test)]
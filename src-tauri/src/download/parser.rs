@@ -20,6 +20,7 @@ pub enum ParseResult {
 pub struct Parser {
     progress_regex: Regex,
     completion_regex: Regex,
+    aria2c_regex: Regex,
 }
 
 impl Default for Parser {
@@ -40,14 +41,27 @@ impl Parser {
         let completion_re =
             Regex::new(r"\[download\]\s+100(?:\.0)?%\s+of\s+(?:~)?(\S+)\s+in\s+(\S+)").unwrap();
 
+        // aria2c (as yt-dlp's external downloader) summary/readout line:
+        // [#2089b0 400KiB/33MiB(1%) CN:8 DL:2.0MiB ETA:16s]
+        let aria2c_re = Regex::new(
+            r"\[#\w+\s+(\S+?)/(\S+?)\((\d+)%\)(?:\s+CN:\d+)?(?:\s+SD:\d+)?\s+DL:(\S+?)(?:\s+ETA:(\S+?))?\]",
+        )
+        .unwrap();
+
         Self {
             progress_regex: re,
             completion_regex: completion_re,
+            aria2c_regex: aria2c_re,
         }
     }
 
     pub fn parse_line(&self, line: &str) -> ParseResult {
-        let trimmed = line.trim();
+        // aria2c redraws its readout with carriage returns; only the latest counts.
+        let trimmed = line
+            .rsplit('\r')
+            .map(str::trim)
+            .find(|s| !s.is_empty())
+            .unwrap_or("");
         if trimmed.is_empty() {
             return ParseResult::Ignore;
         }
@@ -83,12 +97,55 @@ impl Parser {
                 speed: None,
                 eta: None,
             })
+        } else if let Some(caps) = self.aria2c_regex.captures(trimmed) {
+            let downloaded_bytes = caps.get(1).and_then(|m| parse_size(m.as_str()));
+            let total_bytes = caps.get(2).and_then(|m| parse_size(m.as_str()));
+            let progress = caps
+                .get(3)
+                .and_then(|m| m.as_str().parse::<f64>().ok())
+                .unwrap_or(0.0);
+
+            ParseResult::Progress(ProgressUpdate {
+                progress,
+                downloaded_bytes,
+                total_bytes,
+                speed: caps.get(4).map(|m| format!("{}/s", m.as_str())),
+                eta: caps.get(5).and_then(|m| aria2c_eta(m.as_str())),
+            })
         } else {
             ParseResult::Ignore
         }
     }
 }
 
+/// aria2c's `1h2m3s` / `4m51s` / `16s` → yt-dlp's `01:02:03` / `04:51` / `00:16`.
+fn aria2c_eta(eta: &str) -> Option<String> {
+    let mut secs = 0u64;
+    let mut value = String::new();
+    for c in eta.chars() {
+        if c.is_ascii_digit() {
+            value.push(c);
+            continue;
+        }
+        let n: u64 = value.parse().ok()?;
+        value.clear();
+        secs += match c {
+            'h' => n * 3600,
+            'm' => n * 60,
+            's' => n,
+            _ => return None,
+        };
+    }
+    if !value.is_empty() {
+        return None;
+    }
+    Some(if secs >= 3600 {
+        format!("{:02}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    })
+}
+
 /// Lazily compiled regex for parsing human-readable size strings.
 static SIZE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+(?:\.\d+)?)([KMGT]i?B)").unwrap());
 
@@ -147,6 +204,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_aria2c_progress() {
+        let parser = Parser::new();
+        let line = "\r[#2089b0 400KiB/33MiB(1%) CN:1 DL:115KiB ETA:4m51s]\r[#2089b0 16MiB/32MiB(50%) CN:8 DL:2.0MiB ETA:8s]";
+        let result = parser.parse_line(line);
+
+        if let ParseResult::Progress(update) = result {
+            assert_eq!(update.progress, 50.0);
+            assert_eq!(update.downloaded_bytes, Some(16 * 1024 * 1024));
+            assert_eq!(update.total_bytes, Some(32 * 1024 * 1024));
+            assert_eq!(update.speed, Some("2.0MiB/s".to_string()));
+            assert_eq!(update.eta, Some("00:08".to_string()));
+        } else {
+            panic!("Expected Progress, got {:?}", result);
+        }
+
+        assert_eq!(aria2c_eta("1h2m3s").as_deref(), Some("01:02:03"));
+        assert_eq!(
+            parser.parse_line("*** Download Progress Summary as of Mon Jan  1 00:00:00 2024 ***"),
+            ParseResult::Ignore
+        );
+    }
+
     #[test]
     fn test_parse_non_matching_line() {
         let parser = Parser::new();
@@ -1,6 +1,18 @@
-use std::path::PathBuf;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::process::Command;
 
+/// Width in pixels of every Wall gallery thumbnail.
+const THUMBNAIL_WIDTH: u32 = 300;
+
+/// Height of the waveform image rendered for audio without any artwork (16:9 at 300px).
+const WAVEFORM_HEIGHT: u32 = 169;
+
+/// How long fetching a platform thumbnail URL may take before it is given up on.
+const REMOTE_THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(15);
+
 /// Result of post-processing a downloaded media file.
 pub struct ThumbnailResult {
     /// Path to the 300px thumbnail for the Wall gallery.
@@ -8,86 +20,267 @@ pub struct ThumbnailResult {
 }
 
 /// Process thumbnails after a download completes:
-/// - Extract a frame from the video and scale to 300px for the Wall gallery
+/// - VIDEO: scale the platform poster to 300px, falling back to a frame from the video
+/// - IMAGE: scale the image itself to 300px
+/// - AUDIO: use the embedded cover art, then the platform poster or thumbnail URL
+///   (`remote_thumbnail`, fetched to a temporary file), and finally render a waveform image
 pub async fn process_thumbnails(
-    ffmpeg_path: &std::path::Path,
-    media_file: &std::path::Path,
+    ffmpeg_path: &Path,
+    media_file: &Path,
     media_type: &str,
+    poster: Option<&Path>,
+    remote_thumbnail: Option<&str>,
 ) -> ThumbnailResult {
-    if media_type == "VIDEO" {
-        tracing::info!(
-            "Extracting frame from video for thumbnail: {}",
-            media_file.display()
-        );
-        let extracted = extract_frame_thumbnail(ffmpeg_path, media_file).await;
-        match extracted {
-            Ok(path) => ThumbnailResult {
-                thumbnail_path: Some(path.to_string_lossy().to_string()),
-            },
-            Err(e) => {
-                tracing::warn!("Frame extraction failed: {}", e);
-                ThumbnailResult {
-                    thumbnail_path: None,
+    let extracted = match media_type {
+        "VIDEO" => match poster {
+            Some(poster) => {
+                tracing::info!(
+                    "Scaling platform poster for thumbnail: {}",
+                    poster.display()
+                );
+                match scale_image_thumbnail(ffmpeg_path, media_file, &poster.to_string_lossy())
+                    .await
+                {
+                    Ok(path) => Ok(path),
+                    Err(e) => {
+                        tracing::warn!("Poster unusable, grabbing a frame instead: {}", e);
+                        extract_frame_thumbnail(ffmpeg_path, media_file).await
+                    }
                 }
             }
+            None => {
+                tracing::info!(
+                    "Extracting frame from video for thumbnail: {}",
+                    media_file.display()
+                );
+                extract_frame_thumbnail(ffmpeg_path, media_file).await
+            }
+        },
+        "IMAGE" => {
+            tracing::info!("Scaling image for thumbnail: {}", media_file.display());
+            scale_image_thumbnail(ffmpeg_path, media_file, &media_file.to_string_lossy()).await
         }
-    } else {
-        ThumbnailResult {
-            thumbnail_path: None,
+        "AUDIO" => {
+            tracing::info!("Building thumbnail for audio: {}", media_file.display());
+            audio_thumbnail(ffmpeg_path, media_file, poster, remote_thumbnail).await
+        }
+        _ => Err(format!(
+            "No thumbnail strategy for media type '{}'",
+            media_type
+        )),
+    };
+
+    match extracted {
+        Ok(path) => ThumbnailResult {
+            thumbnail_path: Some(path.to_string_lossy().to_string()),
+        },
+        Err(e) => {
+            tracing::warn!("Thumbnail generation failed: {}", e);
+            ThumbnailResult {
+                thumbnail_path: None,
+            }
         }
     }
 }
 
-/// Extract a frame from the video at ~3 seconds and save as a 300px thumbnail.
-async fn extract_frame_thumbnail(
-    ffmpeg_path: &std::path::Path,
-    video_file: &std::path::Path,
-) -> Result<PathBuf, String> {
-    let stem = video_file
+/// `<dir>/<stem>.thumb_sm.jpg` — the shared naming for every Wall thumbnail.
+pub fn thumbnail_output_path(media_file: &Path) -> PathBuf {
+    let stem = media_file
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("thumb");
-    let output_path = video_file
+    media_file
+        .parent()
+        .unwrap_or(media_file)
+        .join(format!("{}.thumb_sm.jpg", stem))
+}
+
+/// `<dir>/<stem>.poster.jpg` — where yt-dlp writes the platform's full-size
+/// thumbnail (see the `thumbnail:` output template in the worker).
+pub fn poster_output_path(media_file: &Path) -> PathBuf {
+    let stem = media_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("poster");
+    media_file
+        .parent()
+        .unwrap_or(media_file)
+        .join(format!("{}.poster.jpg", stem))
+}
+
+/// `<dir>/<stem>.info.json` — where `--write-info-json` leaves yt-dlp's metadata dump.
+pub fn info_json_path(media_file: &Path) -> PathBuf {
+    let stem = media_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("info");
+    media_file
         .parent()
-        .unwrap_or(video_file)
-        .join(format!("{}.thumb_sm.jpg", stem));
+        .unwrap_or(media_file)
+        .join(format!("{}.info.json", stem))
+}
+
+/// Subtitle formats yt-dlp may leave next to a download (after `--convert-subs`).
+const SUBTITLE_EXTENSIONS: &[&str] = &["vtt", "srt", "ass", "lrc"];
+
+/// Subtitle files yt-dlp wrote next to `media_file` (`<stem>.<lang>.<ext>`),
+/// paired with their language code and sorted by language.
+pub async fn find_subtitle_files(media_file: &Path) -> Vec<(PathBuf, String)> {
+    let Some(stem) = media_file.file_stem().and_then(|s| s.to_str()) else {
+        return Vec::new();
+    };
+    let Some(dir) = media_file.parent() else {
+        return Vec::new();
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+
+    let mut subtitles = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        if let Some(lang) = name.to_str().and_then(|n| subtitle_language(stem, n)) {
+            subtitles.push((entry.path(), lang));
+        }
+    }
+    subtitles.sort_by(|a, b| a.1.cmp(&b.1));
+    subtitles
+}
+
+/// Language code of `file_name` if it is a subtitle for the media with `media_stem`.
+fn subtitle_language(media_stem: &str, file_name: &str) -> Option<String> {
+    let rest = file_name.strip_prefix(media_stem)?.strip_prefix('.')?;
+    let (lang, ext) = rest.rsplit_once('.')?;
+    if lang.is_empty() || lang.contains('.') {
+        return None;
+    }
+    SUBTITLE_EXTENSIONS
+        .contains(&ext.to_lowercase().as_str())
+        .then(|| lang.to_string())
+}
+
+/// Per-chapter pieces yt-dlp's `--split-chapters` wrote next to `media_file`
+/// (`<stem> - 001 <chapter title>.<ext>`), in chapter order.
+pub async fn find_chapter_files(media_file: &Path) -> Vec<PathBuf> {
+    let (Some(stem), Some(ext)) = (
+        media_file.file_stem().and_then(|s| s.to_str()),
+        media_file.extension().and_then(|s| s.to_str()),
+    ) else {
+        return Vec::new();
+    };
+    let Some(dir) = media_file.parent() else {
+        return Vec::new();
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+
+    let mut pieces = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        if let Some(number) = name.to_str().and_then(|n| chapter_number(stem, ext, n)) {
+            pieces.push((number, entry.path()));
+        }
+    }
+    pieces.sort();
+    pieces.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Start second of a clip named by a sectioned download
+/// (`<stem> [<start>-<end>].<ext>`, see `worker::CLIP_OUTPUT_SUFFIX`).
+pub fn clip_start(file_name: &str) -> Option<u64> {
+    let (name, _) = file_name.rsplit_once('.')?;
+    let (_, range) = name.strip_suffix(']')?.rsplit_once(" [")?;
+    let (start, end) = range.split_once('-')?;
+    end.parse::<u64>().ok()?;
+    start.parse().ok()
+}
+
+/// Chapter number of `file_name` if it is a split-chapter piece of the media
+/// with `media_stem` and extension `media_ext`.
+fn chapter_number(media_stem: &str, media_ext: &str, file_name: &str) -> Option<u32> {
+    let (name, ext) = file_name.rsplit_once('.')?;
+    if !ext.eq_ignore_ascii_case(media_ext) {
+        return None;
+    }
+    let rest = name.strip_prefix(media_stem)?.strip_prefix(" - ")?;
+    let digits = rest.split(' ').next()?;
+    if digits.len() < 3 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Run ffmpeg with the given arguments (output path last) and report whether
+/// it produced `output_path`.
+async fn run_ffmpeg(ffmpeg_path: &Path, args: &[&str], output_path: &Path) -> Result<bool, String> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.args(args);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped());
+
+    let result = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
+
+    Ok(result.status.success() && output_path.exists())
+}
+
+/// Matches the `Duration: 01:02:03.45` line ffmpeg prints for each input.
+static DURATION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"Duration:\s*(\d+):(\d{2}):(\d{2}(?:\.\d+)?)").unwrap());
+
+/// Media duration in seconds, read from ffmpeg's input summary.
+pub async fn probe_duration(ffmpeg_path: &Path, media_file: &Path) -> Option<f64> {
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner").arg("-i").arg(media_file);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped());
+
+    // ffmpeg exits non-zero without an output file; the summary is still on stderr.
+    let output = cmd.output().await.ok()?;
+    parse_ffmpeg_duration(&String::from_utf8_lossy(&output.stderr))
+}
+
+fn parse_ffmpeg_duration(stderr: &str) -> Option<f64> {
+    let caps = DURATION_REGEX.captures(stderr)?;
+    let hours = caps.get(1)?.as_str().parse::<f64>().ok()?;
+    let minutes = caps.get(2)?.as_str().parse::<f64>().ok()?;
+    let seconds = caps.get(3)?.as_str().parse::<f64>().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Extract a frame from the video at ~3 seconds and save as a 300px thumbnail.
+async fn extract_frame_thumbnail(ffmpeg_path: &Path, video_file: &Path) -> Result<PathBuf, String> {
+    let output_path = thumbnail_output_path(video_file);
+    let scale = format!("scale={}:-1", THUMBNAIL_WIDTH);
 
     // Try at 3 seconds, fallback to 0 seconds for very short videos
     for timestamp in ["00:00:03", "00:00:00"] {
         let input = video_file.to_string_lossy();
         let output = output_path.to_string_lossy();
 
-        let mut cmd = Command::new(ffmpeg_path);
-        cmd.args([
-            "-ss",
-            timestamp,
-            "-i",
-            &input,
-            "-vframes",
-            "1",
-            "-vf",
-            "scale=300:-1",
-            "-q:v",
-            "3",
-            "-y",
+        let args = [
+            "-ss", timestamp, "-i", &input, "-vframes", "1", "-vf", &scale, "-q:v", "3", "-y",
             &output,
-        ]);
-
-        #[cfg(windows)]
-        {
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
-
-        cmd.stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::piped());
+        ];
 
-        let result = cmd
-            .output()
-            .await
-            .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
-
-        if result.status.success() && output_path.exists() {
+        if run_ffmpeg(ffmpeg_path, &args, &output_path).await? {
             tracing::info!(
                 "Frame thumbnail extracted at {}: {}",
                 timestamp,
@@ -99,3 +292,215 @@ async fn extract_frame_thumbnail(
 
     Err("Failed to extract frame at any timestamp".to_string())
 }
+
+/// Scale a single local image to a 300px thumbnail stored next to `media_file`.
+async fn scale_image_thumbnail(
+    ffmpeg_path: &Path,
+    media_file: &Path,
+    input: &str,
+) -> Result<PathBuf, String> {
+    let output_path = thumbnail_output_path(media_file);
+    let output = output_path.to_string_lossy();
+    let scale = format!("scale={}:-1", THUMBNAIL_WIDTH);
+
+    let args = [
+        "-i", input, "-vframes", "1", "-vf", &scale, "-q:v", "3", "-y", &output,
+    ];
+
+    if run_ffmpeg(ffmpeg_path, &args, &output_path).await? {
+        tracing::info!("Image thumbnail created: {}", output_path.display());
+        Ok(output_path)
+    } else {
+        Err(format!("Failed to scale image {}", input))
+    }
+}
+
+/// Audio thumbnails, in order of preference: embedded cover art, the platform
+/// thumbnail (the poster file, else the URL), and a rendered waveform as the last resort.
+async fn audio_thumbnail(
+    ffmpeg_path: &Path,
+    audio_file: &Path,
+    poster: Option<&Path>,
+    remote_thumbnail: Option<&str>,
+) -> Result<PathBuf, String> {
+    // Embedded cover art is exposed by ffmpeg as an attached-picture video stream.
+    let input = audio_file.to_string_lossy();
+    match scale_image_thumbnail(ffmpeg_path, audio_file, &input).await {
+        Ok(path) => return Ok(path),
+        Err(_) => tracing::info!("No embedded cover art in {}", audio_file.display()),
+    }
+
+    let fetched = match (poster, remote_thumbnail) {
+        (Some(_), _) | (None, None) => None,
+        (None, Some(url)) => match fetch_remote_thumbnail(url).await {
+            Ok(path) => Some(path),
+            Err(e) => {
+                tracing::warn!("Platform thumbnail unavailable: {}", e);
+                None
+            }
+        },
+    };
+    if let Some(input) = poster.or(fetched.as_deref()) {
+        let scaled = scale_image_thumbnail(ffmpeg_path, audio_file, &input.to_string_lossy()).await;
+        if let Some(fetched) = &fetched {
+            let _ = tokio::fs::remove_file(fetched).await;
+        }
+        match scaled {
+            Ok(path) => return Ok(path),
+            Err(e) => tracing::warn!("Platform thumbnail unusable: {}", e),
+        }
+    }
+
+    render_waveform_thumbnail(ffmpeg_path, audio_file).await
+}
+
+/// Download a platform thumbnail to a temporary file, so ffmpeg only reads local
+/// files and a slow server can't hold it up. The caller removes the file.
+async fn fetch_remote_thumbnail(url: &str) -> Result<PathBuf, String> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("Not an HTTP URL: {}", url));
+    }
+    let client = reqwest::Client::builder()
+        .timeout(REMOTE_THUMBNAIL_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let bytes = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+
+    let path = std::env::temp_dir().join(format!("vdp-thumbnail-{}", uuid::Uuid::new_v4()));
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Render the audio waveform (`showwavespic`) as a 300px-wide image.
+async fn render_waveform_thumbnail(
+    ffmpeg_path: &Path,
+    audio_file: &Path,
+) -> Result<PathBuf, String> {
+    let output_path = thumbnail_output_path(audio_file);
+    let input = audio_file.to_string_lossy();
+    let output = output_path.to_string_lossy();
+    let filter = format!(
+        "showwavespic=s={}x{}:split_channels=0:colors=0x8B5CF6",
+        THUMBNAIL_WIDTH, WAVEFORM_HEIGHT
+    );
+
+    let args = [
+        "-i",
+        &input,
+        "-filter_complex",
+        &filter,
+        "-frames:v",
+        "1",
+        "-q:v",
+        "3",
+        "-y",
+        &output,
+    ];
+
+    if run_ffmpeg(ffmpeg_path, &args, &output_path).await? {
+        tracing::info!("Waveform thumbnail rendered: {}", output_path.display());
+        Ok(output_path)
+    } else {
+        Err("Failed to render waveform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_output_path_uses_shared_suffix() {
+        let path = thumbnail_output_path(Path::new("/downloads/My Song.mp3"));
+        assert_eq!(path, PathBuf::from("/downloads/My Song.thumb_sm.jpg"));
+    }
+
+    #[test]
+    fn test_thumbnail_output_path_for_images() {
+        let path = thumbnail_output_path(Path::new("/downloads/photo.webp"));
+        assert_eq!(path, PathBuf::from("/downloads/photo.thumb_sm.jpg"));
+    }
+
+    #[test]
+    fn test_poster_output_path_matches_media_stem() {
+        let path = poster_output_path(Path::new("/downloads/My Video.mkv"));
+        assert_eq!(path, PathBuf::from("/downloads/My Video.poster.jpg"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_remote_thumbnail_only_fetches_http() {
+        assert!(fetch_remote_thumbnail("file:///etc/passwd").await.is_err());
+        assert!(fetch_remote_thumbnail("/downloads/cover.jpg").await.is_err());
+    }
+
+    #[test]
+    fn test_info_json_path_matches_media_stem() {
+        let path = info_json_path(Path::new("/downloads/My Video.mkv"));
+        assert_eq!(path, PathBuf::from("/downloads/My Video.info.json"));
+    }
+
+    #[test]
+    fn test_parse_ffmpeg_duration() {
+        let stderr = "Input #0, mov,mp4,m4a,3gp,3g2,mj2, from 'video.mp4':\n  Duration: 01:02:03.50, start: 0.000000, bitrate: 1200 kb/s";
+        assert_eq!(parse_ffmpeg_duration(stderr), Some(3723.5));
+        assert_eq!(parse_ffmpeg_duration("Duration: N/A, bitrate: N/A"), None);
+    }
+
+    #[test]
+    fn test_subtitle_language() {
+        assert_eq!(
+            subtitle_language("My Video", "My Video.en.vtt"),
+            Some("en".to_string())
+        );
+        assert_eq!(
+            subtitle_language("My Video", "My Video.pt-BR.SRT"),
+            Some("pt-BR".to_string())
+        );
+        // Other assets and other videos' subtitles are not ours.
+        assert_eq!(subtitle_language("My Video", "My Video.poster.jpg"), None);
+        assert_eq!(subtitle_language("My Video", "My Video.mp4"), None);
+        assert_eq!(subtitle_language("My Video", "My Video 2.en.vtt"), None);
+        assert_eq!(subtitle_language("My", "My.Video.en.vtt"), None);
+    }
+
+    #[test]
+    fn test_clip_start() {
+        assert_eq!(clip_start("Talk [90-120].mp4"), Some(90));
+        assert_eq!(clip_start("Talk [live] [5-20].webm"), Some(5));
+        assert_eq!(clip_start("Talk.mp4"), None);
+        assert_eq!(clip_start("Talk [abc].mp4"), None);
+    }
+
+    #[test]
+    fn test_chapter_number() {
+        assert_eq!(
+            chapter_number("Talk", "mp4", "Talk - 001 Intro.mp4"),
+            Some(1)
+        );
+        assert_eq!(
+            chapter_number("Talk", "mp4", "Talk - 012 Part 2. Details.mp4"),
+            Some(12)
+        );
+        assert_eq!(chapter_number("Talk", "mp4", "Talk - 003 .mp4"), Some(3));
+        // The full download, other assets and other videos don't match.
+        assert_eq!(chapter_number("Talk", "mp4", "Talk.mp4"), None);
+        assert_eq!(
+            chapter_number("Talk", "mp4", "Talk - 001 Intro.poster.jpg"),
+            None
+        );
+        assert_eq!(
+            chapter_number("Talk", "mp4", "Talk - Live - 001 Intro.mp4"),
+            None
+        );
+    }
+}
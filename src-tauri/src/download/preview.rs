@@ -0,0 +1,390 @@
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+use super::post_process::probe_duration;
+
+/// Width in pixels of the animated hover preview (matches the Wall thumbnails).
+const PREVIEW_WIDTH: u32 = 300;
+
+/// Number of one-second clips sampled across the video for the hover preview.
+const PREVIEW_SEGMENTS: u32 = 6;
+
+/// Frame rate of the animated hover preview.
+const PREVIEW_FPS: u32 = 10;
+
+/// Size of a single storyboard tile.
+const SPRITE_TILE_WIDTH: u32 = 160;
+const SPRITE_TILE_HEIGHT: u32 = 90;
+
+/// Storyboard tiles per row in the sprite sheet.
+const SPRITE_COLUMNS: u32 = 10;
+
+/// Upper bound on storyboard tiles, and the minimum spacing between them.
+const SPRITE_MAX_TILES: u32 = 100;
+const SPRITE_MIN_INTERVAL_SECS: f64 = 2.0;
+
+/// Preview generation is background work: run one job at a time so it never
+/// competes with active downloads for CPU.
+static PREVIEW_SLOTS: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(1));
+
+/// Hover/scrub assets produced for a downloaded video.
+#[derive(Debug, Default)]
+pub struct PreviewAssets {
+    /// Short animated WebP sampled across the duration.
+    pub preview_path: Option<String>,
+    /// Storyboard sprite sheet (JPEG grid of evenly spaced frames).
+    pub sprite_path: Option<String>,
+    /// WebVTT index mapping time ranges to tiles in the sprite sheet.
+    pub sprite_vtt_path: Option<String>,
+}
+
+/// Nice value for preview ffmpeg processes on unix: well below downloads and playback.
+#[cfg(unix)]
+const PREVIEW_NICENESS: &str = "10";
+
+/// Generate the animated preview and storyboard sprite for a video.
+/// Failures are logged and leave the corresponding field empty; cancelling
+/// `cancel` kills the running ffmpeg and skips what is left.
+pub async fn generate_preview_assets(
+    ffmpeg_path: &Path,
+    video_file: &Path,
+    cancel: &CancellationToken,
+) -> PreviewAssets {
+    let _slot = tokio::select! {
+        slot = PREVIEW_SLOTS.acquire() => match slot {
+            Ok(slot) => slot,
+            Err(_) => return PreviewAssets::default(),
+        },
+        _ = cancel.cancelled() => return PreviewAssets::default(),
+    };
+
+    let Some(duration) = probe_duration(ffmpeg_path, video_file).await else {
+        tracing::warn!(
+            "Could not determine duration of {}, skipping previews",
+            video_file.display()
+        );
+        return PreviewAssets::default();
+    };
+
+    let mut assets = PreviewAssets::default();
+
+    match render_animated_preview(ffmpeg_path, video_file, duration, cancel).await {
+        Ok(path) => assets.preview_path = Some(path.to_string_lossy().to_string()),
+        Err(e) => tracing::warn!("Hover preview generation failed: {}", e),
+    }
+    if cancel.is_cancelled() {
+        return assets;
+    }
+
+    match render_sprite_sheet(ffmpeg_path, video_file, duration, cancel).await {
+        Ok((sprite, vtt)) => {
+            assets.sprite_path = Some(sprite.to_string_lossy().to_string());
+            assets.sprite_vtt_path = Some(vtt.to_string_lossy().to_string());
+        }
+        Err(e) => tracing::warn!("Sprite sheet generation failed: {}", e),
+    }
+
+    assets
+}
+
+/// `<dir>/<stem><suffix>` next to the video.
+fn asset_output_path(video_file: &Path, suffix: &str) -> PathBuf {
+    let stem = video_file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("preview");
+    video_file
+        .parent()
+        .unwrap_or(video_file)
+        .join(format!("{}{}", stem, suffix))
+}
+
+/// Run ffmpeg single-threaded at reduced priority and report whether it produced
+/// `output_path`. Cancelling `cancel` kills it.
+async fn run_background_ffmpeg(
+    ffmpeg_path: &Path,
+    args: &[String],
+    output_path: &Path,
+    cancel: &CancellationToken,
+) -> Result<bool, String> {
+    #[cfg(unix)]
+    let mut cmd = {
+        let mut cmd = Command::new("nice");
+        cmd.arg("-n").arg(PREVIEW_NICENESS).arg(ffmpeg_path);
+        cmd
+    };
+    #[cfg(not(unix))]
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner").arg("-threads").arg("1").args(args);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x00004000;
+        cmd.creation_flags(CREATE_NO_WINDOW | BELOW_NORMAL_PRIORITY_CLASS);
+    }
+
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+
+    let result = tokio::select! {
+        result = cmd.output() => result.map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?,
+        _ = cancel.cancelled() => {
+            let _ = tokio::fs::remove_file(output_path).await;
+            return Err("Preview generation cancelled".to_string());
+        }
+    };
+
+    Ok(result.status.success() && output_path.exists())
+}
+
+/// Start offsets of the clips stitched into the hover preview, evenly spread
+/// over the duration. Short videos get a single clip from the start.
+fn preview_segment_starts(duration: f64) -> Vec<f64> {
+    if duration < f64::from(PREVIEW_SEGMENTS) * 2.0 {
+        return vec![0.0];
+    }
+    let step = duration / f64::from(PREVIEW_SEGMENTS + 1);
+    (1..=PREVIEW_SEGMENTS)
+        .map(|i| step * f64::from(i))
+        .collect()
+}
+
+/// Stitch short clips from across the video into a looping animated WebP.
+async fn render_animated_preview(
+    ffmpeg_path: &Path,
+    video_file: &Path,
+    duration: f64,
+    cancel: &CancellationToken,
+) -> Result<PathBuf, String> {
+    let output_path = asset_output_path(video_file, ".preview.webp");
+    let input = video_file.to_string_lossy().to_string();
+    let starts = preview_segment_starts(duration);
+    let clip_length = if starts.len() == 1 {
+        duration.min(3.0)
+    } else {
+        1.0
+    };
+
+    let mut args = Vec::new();
+    let mut filter = String::new();
+    for (i, start) in starts.iter().enumerate() {
+        args.extend([
+            "-ss".to_string(),
+            format!("{:.3}", start),
+            "-t".to_string(),
+            format!("{:.3}", clip_length),
+            "-i".to_string(),
+            input.clone(),
+        ]);
+        filter.push_str(&format!(
+            "[{i}:v]scale={}:-2,fps={},setsar=1[v{i}];",
+            PREVIEW_WIDTH, PREVIEW_FPS
+        ));
+    }
+    for i in 0..starts.len() {
+        filter.push_str(&format!("[v{}]", i));
+    }
+    filter.push_str(&format!("concat=n={}:v=1:a=0[out]", starts.len()));
+
+    args.extend(
+        [
+            "-filter_complex",
+            filter.as_str(),
+            "-map",
+            "[out]",
+            "-an",
+            "-c:v",
+            "libwebp",
+            "-q:v",
+            "60",
+            "-loop",
+            "0",
+            "-y",
+        ]
+        .map(String::from),
+    );
+    args.push(output_path.to_string_lossy().to_string());
+
+    if run_background_ffmpeg(ffmpeg_path, &args, &output_path, cancel).await? {
+        tracing::info!("Hover preview created: {}", output_path.display());
+        Ok(output_path)
+    } else {
+        Err(format!(
+            "ffmpeg produced no preview for {}",
+            video_file.display()
+        ))
+    }
+}
+
+/// Storyboard layout for a video: (seconds between tiles, number of tiles).
+fn sprite_layout(duration: f64) -> (f64, u32) {
+    let interval = (duration / f64::from(SPRITE_MAX_TILES)).max(SPRITE_MIN_INTERVAL_SECS);
+    let count = ((duration / interval).ceil() as u32).clamp(1, SPRITE_MAX_TILES);
+    (interval, count)
+}
+
+/// Render the storyboard sprite sheet and its WebVTT index.
+async fn render_sprite_sheet(
+    ffmpeg_path: &Path,
+    video_file: &Path,
+    duration: f64,
+    cancel: &CancellationToken,
+) -> Result<(PathBuf, PathBuf), String> {
+    let sprite_path = asset_output_path(video_file, ".sprite.jpg");
+    let vtt_path = asset_output_path(video_file, ".sprite.vtt");
+    let (interval, count) = sprite_layout(duration);
+    let rows = count.div_ceil(SPRITE_COLUMNS);
+
+    let filter = format!(
+        "fps=1/{interval:.3},scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,tile={cols}x{rows}",
+        interval = interval,
+        w = SPRITE_TILE_WIDTH,
+        h = SPRITE_TILE_HEIGHT,
+        cols = SPRITE_COLUMNS,
+        rows = rows,
+    );
+
+    let input = video_file.to_string_lossy();
+    let output = sprite_path.to_string_lossy();
+    let args = [
+        "-skip_frame",
+        "nokey",
+        "-i",
+        &*input,
+        "-vf",
+        filter.as_str(),
+        "-frames:v",
+        "1",
+        "-q:v",
+        "5",
+        "-y",
+        &*output,
+    ]
+    .map(String::from);
+
+    if !run_background_ffmpeg(ffmpeg_path, &args, &sprite_path, cancel).await? {
+        return Err(format!(
+            "ffmpeg produced no sprite sheet for {}",
+            video_file.display()
+        ));
+    }
+
+    let sprite_name = sprite_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let vtt = build_sprite_vtt(&sprite_name, count, interval, duration);
+    tokio::fs::write(&vtt_path, vtt)
+        .await
+        .map_err(|e| format!("Failed to write sprite index: {}", e))?;
+
+    tracing::info!("Sprite sheet created: {}", sprite_path.display());
+    Ok((sprite_path, vtt_path))
+}
+
+/// WebVTT storyboard index: one cue per tile, pointing into the sprite with a
+/// `#xywh=` media fragment. The sprite is referenced relative to the VTT file.
+fn build_sprite_vtt(sprite_name: &str, count: u32, interval: f64, duration: f64) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for i in 0..count {
+        let start = f64::from(i) * interval;
+        let end = (start + interval).min(duration);
+        let x = (i % SPRITE_COLUMNS) * SPRITE_TILE_WIDTH;
+        let y = (i / SPRITE_COLUMNS) * SPRITE_TILE_HEIGHT;
+        vtt.push_str(&format!(
+            "\n{} --> {}\n{}#xywh={},{},{},{}\n",
+            format_vtt_timestamp(start),
+            format_vtt_timestamp(end),
+            sprite_name,
+            x,
+            y,
+            SPRITE_TILE_WIDTH,
+            SPRITE_TILE_HEIGHT
+        ));
+    }
+    vtt
+}
+
+/// `HH:MM:SS.mmm`
+fn format_vtt_timestamp(seconds: f64) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        total_ms / 3_600_000,
+        (total_ms / 60_000) % 60,
+        (total_ms / 1000) % 60,
+        total_ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancel_kills_background_ffmpeg() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stands in for an ffmpeg run that takes a while.
+        let dir = std::env::temp_dir().join(format!("vdp-preview-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let slow = dir.join("ffmpeg");
+        tokio::fs::write(&slow, "#!/bin/sh\nsleep 30\n").await.unwrap();
+        tokio::fs::set_permissions(&slow, std::fs::Permissions::from_mode(0o755))
+            .await
+            .unwrap();
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            canceller.cancel();
+        });
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            run_background_ffmpeg(&slow, &[], &dir.join("out.webp"), &cancel),
+        )
+        .await
+        .expect("cancelling should stop ffmpeg");
+        assert!(result.is_err());
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[test]
+    fn test_format_vtt_timestamp() {
+        assert_eq!(format_vtt_timestamp(0.0), "00:00:00.000");
+        assert_eq!(format_vtt_timestamp(3723.5), "01:02:03.500");
+    }
+
+    #[test]
+    fn test_sprite_layout_caps_tiles() {
+        // Short videos: one tile every 2 seconds.
+        assert_eq!(sprite_layout(30.0), (2.0, 15));
+        // Long videos: spacing grows so the sheet stays at 100 tiles.
+        assert_eq!(sprite_layout(1000.0), (10.0, 100));
+    }
+
+    #[test]
+    fn test_build_sprite_vtt_wraps_rows() {
+        let vtt = build_sprite_vtt("clip.sprite.jpg", 11, 2.0, 21.0);
+        assert!(vtt.starts_with("WEBVTT\n"));
+        assert!(vtt.contains("00:00:00.000 --> 00:00:02.000\nclip.sprite.jpg#xywh=0,0,160,90\n"));
+        // The 11th tile starts the second row; its cue ends at the video's end.
+        assert!(vtt.contains("00:00:20.000 --> 00:00:21.000\nclip.sprite.jpg#xywh=0,90,160,90\n"));
+    }
+
+    #[test]
+    fn test_preview_segment_starts() {
+        assert_eq!(preview_segment_starts(5.0), vec![0.0]);
+        let starts = preview_segment_starts(70.0);
+        assert_eq!(starts.len(), PREVIEW_SEGMENTS as usize);
+        assert_eq!(starts[0], 10.0);
+        assert_eq!(starts[5], 60.0);
+    }
+}
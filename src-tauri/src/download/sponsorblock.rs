@@ -0,0 +1,285 @@
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set};
+use serde::Deserialize;
+
+use crate::entity::sponsor_segment;
+use crate::metadata::format_processor::{DownloadOptions, SponsorBlockMode};
+
+/// Public SponsorBlock server, also yt-dlp's default for `--sponsorblock-api`.
+pub const DEFAULT_API_URL: &str = "https://sponsor.ajay.app";
+
+/// Categories used when neither the task nor the settings name any.
+pub const DEFAULT_CATEGORIES: &[&str] = &["sponsor", "selfpromo"];
+
+/// SponsorBlock settings for one download: the task's own choices, with the
+/// user's defaults from settings filling in whatever it left unset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SponsorBlockConfig {
+    pub mode: SponsorBlockMode,
+    pub categories: Vec<String>,
+    pub api_url: String,
+}
+
+impl SponsorBlockConfig {
+    /// Resolve the configuration for a task from its options and the settings table
+    /// (`sponsorblock_mode`, `sponsorblock_categories`, `sponsorblock_api`).
+    pub async fn resolve(db: &impl ConnectionTrait, options: Option<&DownloadOptions>) -> Self {
+        let mode = match options.and_then(|o| o.sponsorblock_mode) {
+            Some(mode) => mode,
+            None => crate::db::get_setting(db, "sponsorblock_mode")
+                .await
+                .and_then(|v| serde_json::from_value(serde_json::Value::String(v)).ok())
+                .unwrap_or_default(),
+        };
+
+        let mut categories = options
+            .map(|o| o.sponsorblock_categories.clone())
+            .unwrap_or_default();
+        if categories.is_empty() {
+            categories = match crate::db::get_setting(db, "sponsorblock_categories").await {
+                Some(v) => parse_categories(&v),
+                None => DEFAULT_CATEGORIES.iter().map(|c| c.to_string()).collect(),
+            };
+        }
+
+        let api_url = crate::db::get_setting(db, "sponsorblock_api")
+            .await
+            .unwrap_or_else(|| DEFAULT_API_URL.to_string());
+
+        Self {
+            mode,
+            categories,
+            api_url,
+        }
+    }
+
+    /// Whether this download marks or removes anything.
+    pub fn is_active(&self) -> bool {
+        self.mode != SponsorBlockMode::Off && !self.categories.is_empty()
+    }
+
+    /// yt-dlp arguments for this configuration.
+    pub fn yt_dlp_args(&self) -> Vec<String> {
+        let flag = match self.mode {
+            _ if self.categories.is_empty() => return Vec::new(),
+            SponsorBlockMode::Off => return Vec::new(),
+            SponsorBlockMode::Mark => "--sponsorblock-mark",
+            SponsorBlockMode::Remove => "--sponsorblock-remove",
+        };
+        let mut args = vec![flag.to_string(), self.categories.join(",")];
+        if self.api_url != DEFAULT_API_URL {
+            args.push("--sponsorblock-api".to_string());
+            args.push(self.api_url.clone());
+        }
+        args
+    }
+
+    fn action(&self) -> &'static str {
+        match self.mode {
+            SponsorBlockMode::Remove => "remove",
+            _ => "mark",
+        }
+    }
+}
+
+/// Split a comma-separated category list from settings.
+fn parse_categories(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// A segment as returned by the SponsorBlock `skipSegments` endpoint.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Segment {
+    /// `[start, end]` in seconds.
+    pub segment: [f64; 2],
+    pub category: String,
+}
+
+/// Look up the segments of a YouTube video in the configured categories.
+/// A video without any submitted segments yields an empty list.
+pub async fn fetch_segments(
+    api_url: &str,
+    video_id: &str,
+    categories: &[String],
+) -> Result<Vec<Segment>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+    let categories_json =
+        serde_json::to_string(categories).map_err(|e| format!("Invalid categories: {}", e))?;
+
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/api/skipSegments", api_url.trim_end_matches('/')),
+        &[("videoID", video_id), ("categories", &categories_json)],
+    )
+    .map_err(|e| format!("Invalid SponsorBlock API URL: {}", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("SponsorBlock request failed: {}", e))?;
+
+    // The API answers 404 when a video has no segments.
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!("SponsorBlock API returned {}", response.status()));
+    }
+
+    response
+        .json::<Vec<Segment>>()
+        .await
+        .map_err(|e| format!("Invalid SponsorBlock response: {}", e))
+}
+
+/// Replace the stored segments of a post with the ones applied by this download.
+pub async fn store_segments(
+    db: &impl ConnectionTrait,
+    post_id: i64,
+    config: &SponsorBlockConfig,
+    segments: &[Segment],
+) -> Result<(), DbErr> {
+    sponsor_segment::Entity::delete_many()
+        .filter(sponsor_segment::Column::PostId.eq(post_id))
+        .exec(db)
+        .await?;
+
+    if segments.is_empty() {
+        return Ok(());
+    }
+
+    let rows = segments.iter().map(|s| sponsor_segment::ActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        post_id: Set(post_id),
+        category: Set(s.category.clone()),
+        start_time: Set(s.segment[0]),
+        end_time: Set(s.segment[1]),
+        action: Set(config.action().to_string()),
+    });
+    sponsor_segment::Entity::insert_many(rows).exec(db).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn config(mode: SponsorBlockMode, api_url: &str) -> SponsorBlockConfig {
+        SponsorBlockConfig {
+            mode,
+            categories: vec!["sponsor".to_string(), "intro".to_string()],
+            api_url: api_url.to_string(),
+        }
+    }
+
+    /// Serve a single HTTP response on a local port, standing in for the SponsorBlock API.
+    /// Returns the base URL and a handle yielding the raw request line.
+    async fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            request.lines().next().unwrap_or_default().to_string()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_yt_dlp_args_per_mode() {
+        assert!(config(SponsorBlockMode::Off, DEFAULT_API_URL)
+            .yt_dlp_args()
+            .is_empty());
+        assert_eq!(
+            config(SponsorBlockMode::Mark, DEFAULT_API_URL).yt_dlp_args(),
+            vec!["--sponsorblock-mark", "sponsor,intro"]
+        );
+        assert_eq!(
+            config(SponsorBlockMode::Remove, "http://127.0.0.1:9000").yt_dlp_args(),
+            vec![
+                "--sponsorblock-remove",
+                "sponsor,intro",
+                "--sponsorblock-api",
+                "http://127.0.0.1:9000"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_prefers_task_options_over_settings() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        crate::entity::setting::Entity::insert(crate::entity::setting::ActiveModel {
+            key: Set("sponsorblock_mode".to_string()),
+            value: Set("remove".to_string()),
+            updated_at: Set(chrono::Utc::now()),
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+
+        // Nothing on the task: settings mode, default categories.
+        let resolved = SponsorBlockConfig::resolve(&db, None).await;
+        assert_eq!(resolved.mode, SponsorBlockMode::Remove);
+        assert_eq!(resolved.categories, vec!["sponsor", "selfpromo"]);
+
+        let options = DownloadOptions {
+            sponsorblock_mode: Some(SponsorBlockMode::Off),
+            sponsorblock_categories: vec!["intro".to_string()],
+            ..Default::default()
+        };
+        let resolved = SponsorBlockConfig::resolve(&db, Some(&options)).await;
+        assert_eq!(resolved.mode, SponsorBlockMode::Off);
+        assert_eq!(resolved.categories, vec!["intro"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_segments_from_local_api() {
+        let (url, request) = serve_once(
+            "200 OK",
+            r#"[{"segment":[12.5,40.0],"UUID":"a","category":"sponsor","actionType":"skip"}]"#,
+        )
+        .await;
+
+        let segments = fetch_segments(&url, "abc123", &["sponsor".to_string()])
+            .await
+            .expect("Segments should parse");
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].segment, [12.5, 40.0]);
+        assert_eq!(segments[0].category, "sponsor");
+
+        let request_line = request.await.unwrap();
+        assert!(request_line.starts_with("GET /api/skipSegments?videoID=abc123&categories="));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_segments_not_found_is_empty() {
+        let (url, _request) = serve_once("404 Not Found", "Not Found").await;
+        let segments = fetch_segments(&url, "abc123", &["sponsor".to_string()])
+            .await
+            .expect("404 means no segments");
+        assert!(segments.is_empty());
+    }
+}
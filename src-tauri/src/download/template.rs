@@ -0,0 +1,301 @@
+//! Output path templates. A template such as `{platform}/{creator}/{upload_date} {title}`
+//! is rendered from the library's own records into a path under the download
+//! folder; its last component is the file name without extension.
+
+use sea_orm::{ConnectionTrait, EntityTrait};
+use std::path::{Component, Path, PathBuf};
+
+use crate::entity::{creator, post, source};
+use crate::metadata::models::YtDlpVideo;
+
+/// Used when neither the source nor the `filename_template` setting has one.
+pub const DEFAULT_TEMPLATE: &str = "{title}";
+
+/// Stand-in for a token whose value is unknown for this download.
+const MISSING_VALUE: &str = "NA";
+
+/// Longest component written, in bytes; leaves room for extensions and suffixes
+/// within the common 255-byte file name limit.
+const MAX_COMPONENT_BYTES: usize = 180;
+
+/// Names Windows reserves for devices, with or without an extension.
+const WINDOWS_RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Token values for one download.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateContext {
+    pub platform: Option<String>,
+    pub creator: Option<String>,
+    pub handle: Option<String>,
+    pub source: Option<String>,
+    pub feed_type: Option<String>,
+    /// `YYYY-MM-DD`.
+    pub upload_date: Option<String>,
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub playlist_index: Option<u32>,
+}
+
+/// Where a download goes: a directory relative to the download folder, and
+/// the file name without extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputPath {
+    pub dir: PathBuf,
+    pub stem: String,
+}
+
+impl OutputPath {
+    /// `name` (a file written into `dir`) relative to the download folder.
+    pub fn relative(&self, name: &str) -> String {
+        self.dir.join(name).to_string_lossy().to_string()
+    }
+}
+
+impl TemplateContext {
+    /// Gather the token values of a post from its creator, source and metadata.
+    pub async fn for_post(db: &impl ConnectionTrait, post_id: i64) -> Option<Self> {
+        let post = post::Entity::find_by_id(post_id).one(db).await.ok()??;
+        let creator = creator::Entity::find_by_id(post.creator_id)
+            .one(db)
+            .await
+            .ok()
+            .flatten();
+        let source = match post.source_id {
+            Some(id) => source::Entity::find_by_id(id).one(db).await.ok().flatten(),
+            None => None,
+        };
+        let video = post
+            .raw_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<YtDlpVideo>(json).ok());
+
+        Some(Self {
+            platform: creator.as_ref().map(|c| c.platform_id.clone()),
+            creator: creator.as_ref().map(|c| c.name.clone()),
+            handle: creator.as_ref().and_then(|c| c.handle.clone()),
+            source: source.as_ref().map(|s| s.name.clone()),
+            feed_type: source.as_ref().and_then(|s| s.feed_type.clone()),
+            upload_date: post.posted_at.map(|d| d.format("%Y-%m-%d").to_string()),
+            id: Some(post.external_id),
+            title: post
+                .title
+                .or_else(|| video.as_ref().map(|v| v.title.clone())),
+            playlist_index: video.and_then(|v| v.playlist_index),
+        })
+    }
+
+    fn value(&self, token: &str) -> Result<Option<String>, String> {
+        Ok(match token {
+            "platform" => self.platform.clone(),
+            "creator" => self.creator.clone(),
+            "handle" => self.handle.clone(),
+            "source" => self.source.clone(),
+            "feed_type" => self.feed_type.clone(),
+            "upload_date" => self.upload_date.clone(),
+            "year" => self
+                .upload_date
+                .as_ref()
+                .map(|d| d[..4.min(d.len())].to_string()),
+            "id" => self.id.clone(),
+            "title" => self.title.clone(),
+            "playlist_index" => self.playlist_index.map(|i| format!("{:03}", i)),
+            other => return Err(format!("Unknown template token: {{{}}}", other)),
+        })
+    }
+}
+
+/// The template for a post's downloads: its source's override, else the
+/// `filename_template` setting, else [`DEFAULT_TEMPLATE`].
+pub async fn template_for_post(db: &impl ConnectionTrait, post_id: i64) -> String {
+    let source_template = match post::Entity::find_by_id(post_id).one(db).await {
+        Ok(Some(post)) => match post.source_id {
+            Some(id) => source::Entity::find_by_id(id)
+                .one(db)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|s| s.filename_template),
+            None => None,
+        },
+        _ => None,
+    };
+    match source_template.filter(|t| !t.trim().is_empty()) {
+        Some(t) => t,
+        None => crate::db::get_setting(db, "filename_template")
+            .await
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+    }
+}
+
+/// Render `template` for `ctx`. Each `/`-separated component is sanitized on its
+/// own, so token values can never add directories or climb out of the folder.
+pub fn render(template: &str, ctx: &TemplateContext) -> Result<OutputPath, String> {
+    let mut components = Vec::new();
+    for part in template.split(['/', '\\']) {
+        let rendered = sanitize_component(&substitute(part, ctx)?);
+        if !rendered.is_empty() {
+            components.push(rendered);
+        }
+    }
+    let stem = components
+        .pop()
+        .ok_or_else(|| "Template produces an empty file name".to_string())?;
+    Ok(OutputPath {
+        dir: components.iter().collect(),
+        stem,
+    })
+}
+
+/// Validate a template without a download, e.g. before saving it.
+pub fn validate(template: &str) -> Result<(), String> {
+    render(template, &TemplateContext::default()).map(|_| ())
+}
+
+fn substitute(part: &str, ctx: &TemplateContext) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = part;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|e| start + e)
+            .ok_or_else(|| format!("Unclosed '{{' in template: {}", part))?;
+        let value = ctx.value(&rest[start + 1..end])?;
+        // Separators inside a value are data, not directories.
+        let value = value.unwrap_or_else(|| MISSING_VALUE.to_string());
+        out.push_str(&value.replace(['/', '\\'], "_"));
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Make one path component safe on every platform: no reserved or control
+/// characters, no `.`/`..`, no trailing dots or spaces, no Windows device names,
+/// and no longer than [`MAX_COMPONENT_BYTES`].
+pub fn sanitize_component(value: &str) -> String {
+    let mut name: String = value
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    if name.len() > MAX_COMPONENT_BYTES {
+        let mut end = MAX_COMPONENT_BYTES;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+
+    let name = name.trim();
+    if name.chars().all(|c| c == '.') {
+        return name.replace('.', "_");
+    }
+    let name = name.trim_end_matches('.').trim_end().to_string();
+
+    let base = name.split('.').next().unwrap_or("").trim_end();
+    if WINDOWS_RESERVED
+        .iter()
+        .any(|r| r.eq_ignore_ascii_case(base))
+    {
+        return format!("_{}", name);
+    }
+    name
+}
+
+/// Join `relative` onto `base`, refusing anything that would resolve outside it.
+pub fn guard_path(base: &Path, relative: &Path) -> Result<PathBuf, String> {
+    for component in relative.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            _ => {
+                return Err(format!(
+                    "Output path escapes the download folder: {}",
+                    relative.display()
+                ))
+            }
+        }
+    }
+    let joined = base.join(relative);
+    if !joined.starts_with(base) {
+        return Err(format!(
+            "Output path escapes the download folder: {}",
+            joined.display()
+        ));
+    }
+    Ok(joined)
+}
+
+/// Escape literal text for use inside a yt-dlp output template.
+pub fn escape_for_yt_dlp(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> TemplateContext {
+        TemplateContext {
+            platform: Some("youtube".to_string()),
+            creator: Some("AC/DC".to_string()),
+            handle: Some("@acdc".to_string()),
+            upload_date: Some("2024-03-09".to_string()),
+            id: Some("abc123".to_string()),
+            title: Some("Live: Back in Black?".to_string()),
+            playlist_index: Some(7),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let out = render(
+            "{platform}/{creator}/{year}/{playlist_index} - {title} [{id}]",
+            &context(),
+        )
+        .unwrap();
+        assert_eq!(out.dir, PathBuf::from("youtube").join("AC_DC").join("2024"));
+        assert_eq!(out.stem, "007 - Live_ Back in Black_ [abc123]");
+
+        let out = render("{source}/{title}", &context()).unwrap();
+        assert_eq!(out.dir, PathBuf::from("NA"));
+        assert!(render("{nope}", &context()).is_err());
+        assert!(render("{title", &context()).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_component() {
+        assert_eq!(sanitize_component(".."), "__");
+        assert_eq!(sanitize_component("con"), "_con");
+        assert_eq!(sanitize_component("LPT1.txt"), "_LPT1.txt");
+        assert_eq!(sanitize_component("console"), "console");
+        assert_eq!(sanitize_component("name. "), "name");
+        assert_eq!(sanitize_component(&"é".repeat(200)).len(), 180);
+    }
+
+    #[test]
+    fn test_dot_segments_cannot_escape() {
+        let ctx = TemplateContext {
+            creator: Some("..".to_string()),
+            title: Some("../../etc/passwd".to_string()),
+            ..Default::default()
+        };
+        let out = render("{creator}/../{title}", &ctx).unwrap();
+        assert_eq!(out.dir, PathBuf::from("__").join("__"));
+        assert_eq!(out.stem, ".._.._etc_passwd");
+
+        let base = Path::new("/downloads");
+        assert!(guard_path(base, &out.dir).is_ok());
+        assert!(guard_path(base, Path::new("../outside")).is_err());
+        assert!(guard_path(base, Path::new("/etc")).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use sea_orm::ConnectionTrait;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use super::post_process::probe_duration;
+use crate::metadata::format_processor::DownloadOptions;
+
+/// A named ffmpeg recipe applied to a finished download.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TranscodeProfile {
+    /// Stable identifier stored in `DownloadOptions::transcode_profile` and settings.
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Extension of the produced file.
+    pub extension: &'static str,
+    /// Drops the video stream; the only kind of profile that applies to audio downloads.
+    pub audio_only: bool,
+    /// Codec and filter arguments placed between the input and the output.
+    #[serde(skip)]
+    pub args: &'static [&'static str],
+}
+
+pub const PROFILES: &[TranscodeProfile] = &[
+    TranscodeProfile {
+        id: "h264_1080p",
+        name: "H.264 1080p MP4 (compatibility)",
+        extension: "mp4",
+        audio_only: false,
+        args: &[
+            "-vf",
+            "scale=-2:'min(1080,ih)'",
+            "-c:v",
+            "libx264",
+            "-preset",
+            "medium",
+            "-crf",
+            "20",
+            "-pix_fmt",
+            "yuv420p",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "192k",
+            "-movflags",
+            "+faststart",
+        ],
+    },
+    TranscodeProfile {
+        id: "h264_720p_small",
+        name: "H.264 720p MP4 (small)",
+        extension: "mp4",
+        audio_only: false,
+        args: &[
+            "-vf",
+            "scale=-2:'min(720,ih)'",
+            "-c:v",
+            "libx264",
+            "-preset",
+            "faster",
+            "-crf",
+            "26",
+            "-pix_fmt",
+            "yuv420p",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "128k",
+            "-movflags",
+            "+faststart",
+        ],
+    },
+    TranscodeProfile {
+        id: "aac_128k",
+        name: "Audio AAC 128k (M4A)",
+        extension: "m4a",
+        audio_only: true,
+        args: &[
+            "-vn",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "128k",
+            "-movflags",
+            "+faststart",
+        ],
+    },
+];
+
+pub fn find_profile(id: &str) -> Option<&'static TranscodeProfile> {
+    PROFILES.iter().find(|p| p.id == id)
+}
+
+/// The profile to apply to a task: its own choice, else the `transcode_profile`
+/// setting. An empty or unknown id means no transcoding.
+pub async fn resolve_profile(
+    db: &impl ConnectionTrait,
+    options: Option<&DownloadOptions>,
+) -> Option<&'static TranscodeProfile> {
+    let id = match options.and_then(|o| o.transcode_profile.clone()) {
+        Some(id) => id,
+        None => crate::db::get_setting(db, "transcode_profile").await?,
+    };
+    let profile = find_profile(id.trim());
+    if profile.is_none() && !id.trim().is_empty() && id.trim() != "none" {
+        tracing::warn!("Unknown transcode profile '{}', skipping", id);
+    }
+    profile
+}
+
+/// Transcoding progress, reported roughly once per second by ffmpeg.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscodeProgress {
+    /// 0.0 to 100.0
+    pub progress: f64,
+    /// Processing speed relative to playback, e.g. "2.5x".
+    pub speed: Option<String>,
+    /// Estimated seconds remaining.
+    pub eta_secs: Option<u64>,
+}
+
+/// `<dir>/<stem>.<profile id>.<ext>` next to the input.
+fn output_path(input: &Path, profile: &TranscodeProfile) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("transcoded");
+    input
+        .parent()
+        .unwrap_or(input)
+        .join(format!("{}.{}.{}", stem, profile.id, profile.extension))
+}
+
+/// Transcode `input` with `profile`, calling `on_progress` as ffmpeg reports it.
+///
+/// With `keep_original` the result is written next to the input under a
+/// profile-tagged name; otherwise the input is replaced by `<stem>.<ext>`.
+/// Cancelling the token stops ffmpeg and leaves the input untouched.
+pub async fn transcode_file(
+    ffmpeg_path: &Path,
+    input: &Path,
+    profile: &TranscodeProfile,
+    keep_original: bool,
+    cancel_token: &CancellationToken,
+    mut on_progress: impl FnMut(TranscodeProgress),
+) -> Result<PathBuf, String> {
+    let duration = probe_duration(ffmpeg_path, input).await;
+    let output = output_path(input, profile);
+
+    let mut cmd = Command::new(ffmpeg_path);
+    cmd.arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-i")
+        .arg(input)
+        .args(profile.args)
+        .arg("-y")
+        .arg(&output);
+
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to spawn ffmpeg: {}", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "Failed to open ffmpeg stdout".to_string())?;
+    let mut lines = BufReader::new(stdout).lines();
+
+    let mut speed = None;
+    let cancelled = loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => break true,
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    if let Some(s) = line.strip_prefix("speed=") {
+                        speed = Some(s.trim().to_string());
+                    } else if let Some(p) = parse_progress_line(&line, duration, speed.as_deref()) {
+                        on_progress(p);
+                    }
+                }
+                _ => break false,
+            }
+        }
+    };
+
+    if cancelled {
+        let _ = child.kill().await;
+        let _ = tokio::fs::remove_file(&output).await;
+        return Err("Transcoding cancelled".to_string());
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("ffmpeg error: {}", e))?;
+    if !status.success() || !output.exists() {
+        let _ = tokio::fs::remove_file(&output).await;
+        return Err(format!(
+            "ffmpeg could not transcode {} with profile {}",
+            input.display(),
+            profile.id
+        ));
+    }
+
+    if keep_original {
+        return Ok(output);
+    }
+
+    let replaced = input.with_extension(profile.extension);
+    tokio::fs::remove_file(input)
+        .await
+        .map_err(|e| format!("Failed to remove original: {}", e))?;
+    tokio::fs::rename(&output, &replaced)
+        .await
+        .map_err(|e| format!("Failed to rename transcoded file: {}", e))?;
+    Ok(replaced)
+}
+
+/// Turn an `out_time_us=` line from `-progress` output into a progress update.
+/// (`out_time_ms` is also microseconds, despite its name.)
+fn parse_progress_line(
+    line: &str,
+    duration: Option<f64>,
+    speed: Option<&str>,
+) -> Option<TranscodeProgress> {
+    let value = line
+        .strip_prefix("out_time_us=")
+        .or_else(|| line.strip_prefix("out_time_ms="))?;
+    let position = value.trim().parse::<f64>().ok()? / 1_000_000.0;
+    let duration = duration.filter(|d| *d > 0.0)?;
+
+    let progress = (position / duration * 100.0).clamp(0.0, 100.0);
+    let eta_secs = speed
+        .and_then(|s| s.trim_end_matches('x').parse::<f64>().ok())
+        .filter(|s| *s > 0.0)
+        .map(|s| ((duration - position).max(0.0) / s).round() as u64);
+
+    Some(TranscodeProgress {
+        progress,
+        speed: speed.map(String::from),
+        eta_secs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress_line() {
+        let update = parse_progress_line("out_time_us=30000000", Some(120.0), Some("2.0x"))
+            .expect("progress line");
+        assert_eq!(update.progress, 25.0);
+        assert_eq!(update.eta_secs, Some(45));
+        assert_eq!(update.speed.as_deref(), Some("2.0x"));
+
+        assert!(parse_progress_line("frame=120", Some(120.0), None).is_none());
+        assert!(parse_progress_line("out_time_us=1000000", None, None).is_none());
+    }
+
+    #[test]
+    fn test_output_path_is_tagged_with_profile() {
+        let profile = find_profile("aac_128k").unwrap();
+        assert_eq!(
+            output_path(Path::new("/downloads/Song.webm"), profile),
+            PathBuf::from("/downloads/Song.aac_128k.m4a")
+        );
+    }
+}
@@ -1,11 +1,20 @@
 use super::parser::Parser;
+use super::direct::{self, DirectError, DirectMedia};
+use super::downloader::DownloaderConfig;
+use super::embed::EmbedConfig;
+use super::live;
+use super::post_process;
+use super::sponsorblock::{self, Segment, SponsorBlockConfig};
+use super::template::{self, OutputPath, TemplateContext};
 use crate::auth::cookie_manager::CookieManager;
-use crate::entity::download_task;
+use crate::entity::{download_task, post};
 use crate::metadata::format_processor::DownloadOptions;
+use crate::metadata::models::{YtDlpOutput, YtDlpVideo};
 use crate::metadata::{fetcher, store};
 use crate::sidecar::{get_binary_path, types::SidecarBinary};
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
 use std::ffi::OsString;
 use std::path::PathBuf;
@@ -53,13 +62,47 @@ async fn kill_process_tree(child: &mut tokio::process::Child) {
     }
 }
 
+/// yt-dlp leads its own process group (see `build_yt_dlp_command`), so killing
+/// the group also stops the ffmpeg or aria2c processes it started.
 #[cfg(not(windows))]
 async fn kill_process_tree(child: &mut tokio::process::Child) {
+    if let Some(pid) = child.id() {
+        let _ = tokio::process::Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", pid)])
+            .status()
+            .await;
+    }
     if let Err(e) = child.kill().await {
         tracing::error!("Failed to kill process tree: {}", e);
     }
 }
 
+/// Ask the process to stop on its own, the way Ctrl+C would, so yt-dlp can wind
+/// down a live recording. Returns false where that isn't possible.
+#[cfg(unix)]
+async fn interrupt_process(child: &tokio::process::Child) -> bool {
+    let Some(pid) = child.id() else {
+        return false;
+    };
+    match tokio::process::Command::new("kill")
+        .args(["-INT", &pid.to_string()])
+        .status()
+        .await
+    {
+        Ok(status) => status.success(),
+        Err(e) => {
+            tracing::error!("Failed to interrupt PID {}: {}", pid, e);
+            false
+        }
+    }
+}
+
+/// yt-dlp runs without a console on Windows, so there is no Ctrl+C to send.
+#[cfg(not(unix))]
+async fn interrupt_process(_child: &tokio::process::Child) -> bool {
+    false
+}
+
 #[derive(Clone, Serialize, Debug)]
 pub struct DownloadProgressPayload {
     pub task_id: i64,
@@ -68,6 +111,19 @@ pub struct DownloadProgressPayload {
     pub eta: String,
     pub downloaded_bytes: u64,
     pub total_bytes: Option<u64>,
+    /// Seconds recorded so far. Set for live streams, which have no percent progress.
+    pub elapsed_secs: Option<u64>,
+}
+
+/// A live stream being recorded. Its progress is the elapsed time and the bytes
+/// on disk, since yt-dlp can't report a percentage for an open-ended stream.
+struct LiveRecording<'a> {
+    stop_token: &'a CancellationToken,
+    output_dir: &'a PathBuf,
+    /// Stem of the recording's files; other downloads share the folder.
+    file_stem: &'a str,
+    pre_download_files: &'a HashSet<OsString>,
+    started: Instant,
 }
 
 pub struct DownloadWorker {
@@ -79,6 +135,14 @@ pub struct DownloadResult {
     pub total_bytes: Option<u64>,
     pub downloaded_bytes: u64,
     pub filename: Option<String>,
+    /// Further files registered after `filename`: the other clips of a sectioned
+    /// download, transcoded copies kept alongside their originals.
+    pub extra_files: Vec<String>,
+    /// Hex SHA-256 of `filename`, when the downloader computed one while writing it.
+    pub checksum: Option<String>,
+    /// SponsorBlock segments looked up for the download's marks or cuts; None when
+    /// SponsorBlock wasn't used.
+    pub sponsor_segments: Option<Vec<Segment>>,
 }
 
 #[derive(Debug)]
@@ -94,8 +158,21 @@ pub enum DownloadError {
         downloaded_bytes: u64,
         filename: Option<String>,
     },
+    /// A scheduled premiere or stream that hasn't started yet.
+    Upcoming {
+        /// Unix timestamp of the scheduled start, if the platform announced one.
+        starts_at: Option<i64>,
+    },
 }
 
+/// Output template for `--split-chapters` pieces after the file stem, kept next
+/// to the full file. `post_process::find_chapter_files` relies on this naming.
+const CHAPTER_OUTPUT_SUFFIX: &str = " - %(section_number)03d %(section_title)s.%(ext)s";
+
+/// Output template after the file stem when only sections are downloaded, so
+/// each clip gets its own file. `post_process::clip_start` relies on this naming.
+const CLIP_OUTPUT_SUFFIX: &str = " [%(section_start)d-%(section_end)d].%(ext)s";
+
 /// Media file extensions used to identify downloaded content (vs thumbnails, .part files, etc.).
 const MEDIA_EXTENSIONS: &[&str] = &[
     "mp4", "webm", "mkv", "avi", "mov", "flv", "mp3", "m4a", "wav", "aac", "ogg", "opus",
@@ -112,7 +189,50 @@ impl DownloadWorker {
         dir: &PathBuf,
         pre_download_files: &HashSet<OsString>,
     ) -> Option<String> {
-        let mut entries = tokio::fs::read_dir(dir).await.ok()?;
+        let new_media = Self::new_media_entries(dir, pre_download_files).await;
+
+        let mut max_len = 0;
+        let mut max_file = None;
+
+        for e in new_media {
+            if let Ok(metadata) = tokio::fs::metadata(e.path()).await {
+                if metadata.len() > max_len {
+                    max_len = metadata.len();
+                    max_file = Some(e.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+
+        max_file
+    }
+
+    /// All media files a sectioned download produced, ordered by the start time
+    /// each clip's name carries.
+    async fn find_new_clip_files(
+        dir: &PathBuf,
+        pre_download_files: &HashSet<OsString>,
+    ) -> Vec<String> {
+        let entries = Self::new_media_entries(dir, pre_download_files).await;
+        let mut clips: Vec<(Option<u64>, String)> = entries
+            .into_iter()
+            .map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                (post_process::clip_start(&name), name)
+            })
+            .collect();
+        // Names without a start time (unexpected) go last.
+        clips.sort_by_key(|(start, name)| (start.is_none(), *start, name.clone()));
+        clips.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// Media files in `dir` that weren't there before the download started.
+    async fn new_media_entries(
+        dir: &PathBuf,
+        pre_download_files: &HashSet<OsString>,
+    ) -> Vec<tokio::fs::DirEntry> {
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+            return Vec::new();
+        };
         let mut new_media = Vec::new();
 
         while let Ok(Some(e)) = entries.next_entry().await {
@@ -130,19 +250,7 @@ impl DownloadWorker {
             }
         }
 
-        let mut max_len = 0;
-        let mut max_file = None;
-
-        for e in new_media {
-            if let Ok(metadata) = tokio::fs::metadata(e.path()).await {
-                if metadata.len() > max_len {
-                    max_len = metadata.len();
-                    max_file = Some(e.file_name().to_string_lossy().to_string());
-                }
-            }
-        }
-
-        max_file
+        new_media
     }
 
     async fn get_pre_download_files(dir: &PathBuf) -> HashSet<OsString> {
@@ -267,6 +375,7 @@ impl DownloadWorker {
         Ok(temp_cookie_path)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn build_yt_dlp_command(
         &self,
         url: &str,
@@ -274,7 +383,11 @@ impl DownloadWorker {
         format_selection: Option<&String>,
         rate_limit: Option<&String>,
         temp_cookie_path: Option<&PathBuf>,
+        settings_args: &[String],
+        is_live: bool,
+        file_stem: &str,
     ) -> Result<Command, DownloadError> {
+        let stem = template::escape_for_yt_dlp(file_stem);
         let binary_path = get_binary_path(&self.app, SidecarBinary::YtDlp).map_err(|e| {
             DownloadError::Failed {
                 message: e.to_string(),
@@ -303,7 +416,12 @@ impl DownloadWorker {
             .arg("-P")
             .arg(output_dir)
             .arg("--output")
-            .arg("%(title)s.%(ext)s");
+            .arg(format!("{}.%(ext)s", stem))
+            .arg("--output")
+            .arg(format!("thumbnail:{}.poster.%(ext)s", stem))
+            .arg("--write-thumbnail")
+            .arg("--convert-thumbnails")
+            .arg("jpg");
 
         if let Some(limit) = rate_limit {
             if !limit.trim().is_empty() {
@@ -328,15 +446,33 @@ impl DownloadWorker {
                 } else {
                     let video_part = opts.format_id.as_deref().unwrap_or("bestvideo");
                     let audio_part = opts.audio_format_id.as_deref().unwrap_or("bestaudio");
-                    let format_string = format!("{}+{}/best", video_part, audio_part);
+                    // Live recordings prefer a pre-muxed format so that stopping
+                    // leaves a single playable file rather than separate streams.
+                    let format_string = if is_live {
+                        format!("best/{}+{}", video_part, audio_part)
+                    } else {
+                        format!("{}+{}/best", video_part, audio_part)
+                    };
                     cmd.arg("-f").arg(&format_string);
                 }
 
+                if is_live {
+                    if opts.live_from_start {
+                        cmd.arg("--live-from-start");
+                    } else {
+                        cmd.arg("--no-live-from-start");
+                    }
+                }
+
                 if !opts.subtitle_langs.is_empty() {
                     cmd.arg("--write-subs");
                     cmd.arg("--sub-langs").arg(opts.subtitle_langs.join(","));
                     cmd.arg("--sub-format").arg("vtt");
 
+                    if opts.subs_as_srt {
+                        cmd.arg("--convert-subs").arg("srt");
+                    }
+
                     if opts.embed_subs {
                         cmd.arg("--embed-subs");
                     }
@@ -345,11 +481,35 @@ impl DownloadWorker {
                 if let Some(ref container) = opts.container {
                     cmd.arg("--merge-output-format").arg(container);
                 }
+
+                if opts.embed_chapters {
+                    cmd.arg("--embed-chapters");
+                }
+
+                if opts.split_chapters {
+                    cmd.arg("--split-chapters")
+                        .arg("--output")
+                        .arg(format!("chapter:{}{}", stem, CHAPTER_OUTPUT_SUFFIX));
+                }
+
+                let sections = opts.download_section_args();
+                if !sections.is_empty() {
+                    for section in &sections {
+                        cmd.arg("--download-sections").arg(section);
+                    }
+                    cmd.arg("--output").arg(format!("{}{}", stem, CLIP_OUTPUT_SUFFIX));
+
+                    if opts.force_keyframes_at_cuts {
+                        cmd.arg("--force-keyframes-at-cuts");
+                    }
+                }
             } else {
                 cmd.arg("-f").arg(fmt_str);
             }
         }
 
+        cmd.args(settings_args);
+
         cmd.arg(url);
 
         #[cfg(windows)]
@@ -357,6 +517,8 @@ impl DownloadWorker {
             const CREATE_NO_WINDOW: u32 = 0x08000000;
             cmd.creation_flags(CREATE_NO_WINDOW);
         }
+        #[cfg(unix)]
+        cmd.process_group(0);
 
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -369,8 +531,9 @@ impl DownloadWorker {
         task_id: i64,
         mut child: tokio::process::Child,
         cancel_token: CancellationToken,
+        live: Option<LiveRecording<'_>>,
         db: DatabaseConnection,
-    ) -> Result<(tokio::process::Child, Option<u64>, u64, bool), DownloadError> {
+    ) -> Result<(tokio::process::Child, Option<u64>, u64, bool, bool), DownloadError> {
         let stdout = child.stdout.take().ok_or_else(|| DownloadError::Failed {
             message: "Failed to open stdout".to_string(),
             total_bytes: None,
@@ -387,6 +550,12 @@ impl DownloadWorker {
         let mut final_downloaded_bytes = 0;
         let mut was_killed = false;
 
+        // Stopping only applies to live recordings; other tasks never trigger it.
+        let stop_token = live.as_ref().map(|l| l.stop_token.clone()).unwrap_or_default();
+        let mut was_stopped = false;
+        let mut stop_deadline: Option<Instant> = None;
+        let mut live_ticker = tokio::time::interval(PROGRESS_THROTTLE);
+
         let read_result: Result<(), DownloadError> = loop {
             if cancel_token.is_cancelled() {
                 tracing::info!("Download cancelled for task: {}", task_id);
@@ -417,6 +586,25 @@ impl DownloadWorker {
                         filename: None,
                     });
                 }
+                _ = stop_token.cancelled(), if !was_stopped => {
+                    tracing::info!("Stopping recording for task: {}", task_id);
+                    was_stopped = true;
+                    if interrupt_process(&child).await {
+                        stop_deadline = Some(Instant::now() + live::STOP_GRACE);
+                    } else {
+                        kill_process_tree(&mut child).await;
+                    }
+                }
+                _ = live_ticker.tick(), if live.is_some() => {
+                    if stop_deadline.is_some_and(|d| Instant::now() >= d) {
+                        tracing::warn!("yt-dlp did not stop in time for task {}, killing", task_id);
+                        stop_deadline = None;
+                        kill_process_tree(&mut child).await;
+                    }
+                    if let Some(ref live) = live {
+                        final_downloaded_bytes = self.report_live_progress(task_id, live, &db).await;
+                    }
+                }
                 result = reader.read_until(b'\n', &mut buf) => {
                     match result {
                         Ok(0) => break Ok(()),
@@ -446,6 +634,7 @@ impl DownloadWorker {
                                             eta: progress.eta.clone().unwrap_or_default(),
                                             downloaded_bytes: final_downloaded_bytes,
                                             total_bytes: final_total_bytes,
+                                            elapsed_secs: None,
                                         };
 
                                         if let Err(e) = self.app.emit("download-progress", &payload) {
@@ -505,7 +694,353 @@ impl DownloadWorker {
         };
 
         read_result?;
-        Ok((child, final_total_bytes, final_downloaded_bytes, was_killed))
+        Ok((
+            child,
+            final_total_bytes,
+            final_downloaded_bytes,
+            was_killed,
+            was_stopped,
+        ))
+    }
+
+    /// Emit and store the elapsed time and size of a live recording.
+    /// Returns the bytes recorded so far.
+    async fn report_live_progress(
+        &self,
+        task_id: i64,
+        live: &LiveRecording<'_>,
+        db: &DatabaseConnection,
+    ) -> u64 {
+        let bytes =
+            live::recording_bytes(live.output_dir, live.file_stem, live.pre_download_files).await;
+        let elapsed = live.started.elapsed().as_secs();
+        let speed = live::format_rate(bytes, elapsed);
+        let eta = live::format_elapsed(elapsed);
+
+        let payload = DownloadProgressPayload {
+            task_id,
+            progress: 0.0,
+            speed: speed.clone().unwrap_or_default(),
+            eta: eta.clone(),
+            downloaded_bytes: bytes,
+            total_bytes: None,
+            elapsed_secs: Some(elapsed),
+        };
+        if let Err(e) = self.app.emit("download-progress", &payload) {
+            tracing::error!("Failed to emit download progress: {}", e);
+        }
+
+        if let Err(e) = download_task::Entity::update_many()
+            .col_expr(
+                download_task::Column::DownloadedBytes,
+                sea_orm::sea_query::Expr::value(bytes as i64),
+            )
+            .col_expr(download_task::Column::Speed, sea_orm::sea_query::Expr::value(speed))
+            .col_expr(download_task::Column::Eta, sea_orm::sea_query::Expr::value(eta))
+            .filter(download_task::Column::Id.eq(task_id))
+            .exec(db)
+            .await
+        {
+            tracing::error!("Failed to update DB progress: {}", e);
+        }
+
+        bytes
+    }
+
+    /// The stored metadata of the task's post.
+    async fn task_video(db: &DatabaseConnection, task_id: i64) -> Option<(i64, YtDlpVideo)> {
+        let task = download_task::Entity::find_by_id(task_id).one(db).await.ok()??;
+        let post_id = task.post_id?;
+        let post = post::Entity::find_by_id(post_id).one(db).await.ok()??;
+        let video = serde_json::from_str(post.raw_json.as_deref()?).ok()?;
+        Some((post_id, video))
+    }
+
+    /// Look up the SponsorBlock segments yt-dlp is about to mark or cut, so the
+    /// post records those once the download is done. SponsorBlock only covers YouTube.
+    async fn lookup_sponsor_segments(
+        config: &SponsorBlockConfig,
+        url: &str,
+        post_id: i64,
+        video: &YtDlpVideo,
+    ) -> Option<Vec<Segment>> {
+        if !config.is_active() || crate::platform::detect_platform(url) != Some("youtube") {
+            return None;
+        }
+        match sponsorblock::fetch_segments(&config.api_url, &video.id, &config.categories).await {
+            Ok(segments) => Some(segments),
+            Err(e) => {
+                tracing::warn!("SponsorBlock lookup failed for post {}: {}", post_id, e);
+                None
+            }
+        }
+    }
+
+    /// Whether the task's post is a live stream, i.e. the task records it and
+    /// can be stopped.
+    pub(crate) async fn is_live_task(db: &DatabaseConnection, task_id: i64) -> bool {
+        Self::task_video(db, task_id)
+            .await
+            .is_some_and(|(_, v)| v.is_live_now())
+    }
+
+    /// Re-fetch a post's metadata and store it, e.g. to see whether a scheduled
+    /// event has started. Only the stored JSON changes; the post keeps its links.
+    async fn refresh_post_metadata(
+        &self,
+        db: &DatabaseConnection,
+        post_id: i64,
+        url: &str,
+        temp_cookie_path: Option<&PathBuf>,
+    ) -> Option<YtDlpVideo> {
+        let video = match fetcher::fetch_metadata(&self.app, url, temp_cookie_path, None).await {
+            Ok(YtDlpOutput::Video(v)) | Ok(YtDlpOutput::VideoFallback(v)) => v,
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to refresh metadata for post {}: {}", post_id, e);
+                return None;
+            }
+        };
+
+        if let Err(e) = post::Entity::update(post::ActiveModel {
+            id: Set(post_id),
+            raw_json: Set(serde_json::to_string(&video).ok()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        {
+            tracing::error!("Failed to store refreshed metadata for post {}: {}", post_id, e);
+        }
+        Some(video)
+    }
+
+    async fn cleanup_temp_cookie(&self, temp_cookie_path: Option<PathBuf>) {
+        if let Some(path) = temp_cookie_path {
+            let cookie_manager = self.app.state::<std::sync::Arc<CookieManager>>();
+            if let Err(e) = cookie_manager.cleanup_temp_file(&path).await {
+                tracing::warn!("Failed to cleanup temp cookie file: {}", e);
+            }
+        }
+    }
+
+    /// Link the task to a post describing a direct file, which has no yt-dlp metadata.
+    async fn ensure_direct_post(
+        db: &DatabaseConnection,
+        task_id: i64,
+        url: &str,
+        media: &DirectMedia,
+    ) -> Result<(), DownloadError> {
+        let db_error = |e: sea_orm::DbErr| DownloadError::Failed {
+            message: format!("DB error: {}", e),
+            total_bytes: None,
+            downloaded_bytes: 0,
+            filename: None,
+        };
+        let task = download_task::Entity::find_by_id(task_id)
+            .one(db)
+            .await
+            .map_err(db_error)?;
+        if task.is_none_or(|t| t.post_id.is_some()) {
+            return Ok(());
+        }
+
+        let title = std::path::Path::new(&media.filename)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(&media.filename);
+        let url_hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+        let video: YtDlpVideo = serde_json::from_value(serde_json::json!({
+            "id": format!("direct_{}", &url_hash[..16]),
+            "title": title,
+            "webpage_url": url,
+            "original_url": url,
+        }))
+        .map_err(|e| DownloadError::Failed {
+            message: format!("Metadata save error: {}", e),
+            total_bytes: None,
+            downloaded_bytes: 0,
+            filename: None,
+        })?;
+
+        let post_id =
+            store::save_metadata(db, YtDlpOutput::Video(video), None, None, None, Some(url))
+                .await
+                .map_err(db_error)?;
+        download_task::Entity::update(download_task::ActiveModel {
+            id: Set(task_id),
+            post_id: Set(Some(post_id)),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Where the task's files go: the directory its filename template resolves to
+    /// under `output_dir` (created if needed, and recorded as the task's folder), and
+    /// the template's result.
+    async fn resolve_output_path(
+        db: &DatabaseConnection,
+        task_id: i64,
+        output_dir: &std::path::Path,
+    ) -> Result<(OutputPath, PathBuf), DownloadError> {
+        let failed = |message: String| DownloadError::Failed {
+            message,
+            total_bytes: None,
+            downloaded_bytes: 0,
+            filename: None,
+        };
+        let post_id = download_task::Entity::find_by_id(task_id)
+            .one(db)
+            .await
+            .map_err(|e| failed(format!("DB error: {}", e)))?
+            .and_then(|t| t.post_id);
+
+        let (template, ctx) = match post_id {
+            Some(id) => (
+                template::template_for_post(db, id).await,
+                TemplateContext::for_post(db, id).await.unwrap_or_default(),
+            ),
+            None => (template::DEFAULT_TEMPLATE.to_string(), TemplateContext::default()),
+        };
+        let output = template::render(&template, &ctx)
+            .or_else(|e| {
+                tracing::warn!("Invalid filename template '{}': {}, using default", template, e);
+                template::render(template::DEFAULT_TEMPLATE, &ctx)
+            })
+            .map_err(failed)?;
+
+        let dir = template::guard_path(output_dir, &output.dir).map_err(failed)?;
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| failed(format!("Failed to create {}: {}", dir.display(), e)))?;
+        // A symlinked folder could still lead elsewhere.
+        crate::library::folders::ensure_within(output_dir, &dir).map_err(failed)?;
+
+        let folder_id = crate::library::folders::ensure_folder(db, &output.dir)
+            .await
+            .map_err(|e| failed(format!("DB error: {}", e)))?;
+        download_task::Entity::update(download_task::ActiveModel {
+            id: Set(task_id),
+            folder_id: Set(folder_id),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(|e| failed(format!("DB error: {}", e)))?;
+        Ok((output, dir))
+    }
+
+    /// Download a direct media URL with the native HTTP client, within the
+    /// task's rate limit. Reports progress like the yt-dlp path. Pausing keeps
+    /// the `.part` file so the resumed task continues with a `Range` request;
+    /// cancelling names it so the queue deletes it.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_direct_download(
+        &self,
+        task_id: i64,
+        url: &str,
+        output_dir: &std::path::Path,
+        client: &reqwest::Client,
+        media: DirectMedia,
+        rate_limit: Option<&str>,
+        cancel_token: CancellationToken,
+        db: DatabaseConnection,
+    ) -> Result<DownloadResult, DownloadError> {
+        Self::ensure_direct_post(&db, task_id, url, &media).await?;
+
+        let (output_path, output_dir) = Self::resolve_output_path(&db, task_id, output_dir).await?;
+        let mut media = media;
+        media.filename = match std::path::Path::new(&media.filename).extension() {
+            Some(ext) => format!("{}.{}", output_path.stem, ext.to_string_lossy()),
+            None => output_path.stem.clone(),
+        };
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let rate_limit = rate_limit.and_then(direct::parse_rate_limit);
+        let download = direct::download(
+            client,
+            url,
+            &output_dir,
+            &media,
+            rate_limit,
+            &cancel_token,
+            move |p| {
+                let _ = tx.send(p);
+            },
+        );
+        let report = async {
+            while let Some(p) = rx.recv().await {
+                let payload = DownloadProgressPayload {
+                    task_id,
+                    progress: p.progress,
+                    speed: p.speed.clone(),
+                    eta: p.eta.clone(),
+                    downloaded_bytes: p.downloaded_bytes,
+                    total_bytes: p.total_bytes,
+                    elapsed_secs: None,
+                };
+                if let Err(e) = self.app.emit("download-progress", &payload) {
+                    tracing::error!("Failed to emit download progress: {}", e);
+                }
+
+                if let Err(e) = download_task::Entity::update_many()
+                    .col_expr(
+                        download_task::Column::Progress,
+                        sea_orm::sea_query::Expr::value(p.progress as f32),
+                    )
+                    .col_expr(
+                        download_task::Column::Speed,
+                        sea_orm::sea_query::Expr::value(p.speed),
+                    )
+                    .col_expr(download_task::Column::Eta, sea_orm::sea_query::Expr::value(p.eta))
+                    .col_expr(
+                        download_task::Column::DownloadedBytes,
+                        sea_orm::sea_query::Expr::value(p.downloaded_bytes as i64),
+                    )
+                    .col_expr(
+                        download_task::Column::TotalBytes,
+                        sea_orm::sea_query::Expr::value(p.total_bytes.map(|b| b as i64)),
+                    )
+                    .filter(download_task::Column::Id.eq(task_id))
+                    .exec(&db)
+                    .await
+                {
+                    tracing::error!("Failed to update DB progress: {}", e);
+                }
+            }
+        };
+        let (result, ()) = tokio::join!(download, report);
+
+        match result {
+            Ok(file) => {
+                tracing::info!("Downloaded file (direct): {}", file.path.display());
+                Ok(DownloadResult {
+                    total_bytes: Some(file.bytes),
+                    downloaded_bytes: file.bytes,
+                    filename: Some(output_path.relative(&media.filename)),
+                    extra_files: Vec::new(),
+                    checksum: Some(file.sha256),
+                    sponsor_segments: None,
+                })
+            }
+            Err(DirectError::Cancelled {
+                downloaded_bytes,
+                total_bytes,
+            }) => Err(DownloadError::Cancelled {
+                total_bytes,
+                downloaded_bytes,
+                filename: Some(output_path.relative(&media.filename)),
+            }),
+            Err(DirectError::Failed(message)) => Err(DownloadError::Failed {
+                message,
+                total_bytes: media.total_bytes,
+                downloaded_bytes: 0,
+                filename: None,
+            }),
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -517,16 +1052,103 @@ impl DownloadWorker {
         format_selection: Option<String>,
         rate_limit: Option<String>,
         cancel_token: CancellationToken,
+        stop_token: CancellationToken,
         db: DatabaseConnection,
     ) -> Result<DownloadResult, DownloadError> {
+        // Plain media links from unknown hosts don't need yt-dlp at all.
+        if crate::platform::detect_platform(&url).is_none() {
+            let client = direct::client().map_err(|message| DownloadError::Failed {
+                message,
+                total_bytes: None,
+                downloaded_bytes: 0,
+                filename: None,
+            })?;
+            if let Some(media) = direct::probe(&client, &url).await {
+                tracing::info!("Task {} is a direct {} file", task_id, media.content_type);
+                return self
+                    .execute_direct_download(
+                        task_id,
+                        &url,
+                        &output_dir,
+                        &client,
+                        media,
+                        rate_limit.as_deref(),
+                        cancel_token,
+                        db,
+                    )
+                    .await;
+            }
+        }
+
         let temp_cookie_path = self.prepare_auth_and_metadata(task_id, &url, &db).await?;
 
+        let mut video = Self::task_video(&db, task_id).await;
+        if let Some((post_id, ref mut stored)) = video {
+            if stored.is_upcoming() {
+                // The stored metadata may predate a wait; check whether the event has started.
+                if let Some(fresh) = self
+                    .refresh_post_metadata(&db, post_id, &url, temp_cookie_path.as_ref())
+                    .await
+                {
+                    *stored = fresh;
+                }
+                if stored.is_upcoming() {
+                    let starts_at = stored.release_timestamp;
+                    self.cleanup_temp_cookie(temp_cookie_path).await;
+                    return Err(DownloadError::Upcoming { starts_at });
+                }
+            }
+        }
+
+        let is_live = video.as_ref().is_some_and(|(_, v)| v.is_live_now());
+        if is_live {
+            tracing::info!("Task {} is a live stream, recording", task_id);
+        }
+
+        let (output_path, output_dir) =
+            match Self::resolve_output_path(&db, task_id, &output_dir).await {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    self.cleanup_temp_cookie(temp_cookie_path).await;
+                    return Err(e);
+                }
+            };
+
+        let options = format_selection
+            .as_deref()
+            .and_then(|s| serde_json::from_str::<DownloadOptions>(s).ok());
+        // Options whose defaults live in settings, resolved against the task's choices.
+        let sponsorblock = SponsorBlockConfig::resolve(&db, options.as_ref()).await;
+        let mut settings_args = sponsorblock.yt_dlp_args();
+        let sponsor_segments = match video {
+            Some((post_id, ref v)) => {
+                Self::lookup_sponsor_segments(&sponsorblock, &url, post_id, v).await
+            }
+            None => None,
+        };
+        settings_args.extend(
+            EmbedConfig::resolve(&db, options.as_ref())
+                .await
+                .yt_dlp_args()
+                .into_iter()
+                .map(String::from),
+        );
+        let aria2c_path = get_binary_path(&self.app, SidecarBinary::Aria2c).ok();
+        settings_args.extend(
+            DownloaderConfig::resolve(&db, crate::platform::detect_platform(&url), aria2c_path)
+                .await
+                .yt_dlp_args(rate_limit.as_deref(), is_live),
+        );
+
         let mut cmd = self.build_yt_dlp_command(
             &url,
             &output_dir,
             format_selection.as_ref(),
             rate_limit.as_ref(),
             temp_cookie_path.as_ref(),
+            &settings_args,
+            is_live,
+            &output_path.stem,
         )?;
 
         let pre_download_files = Self::get_pre_download_files(&output_dir).await;
@@ -564,15 +1186,22 @@ impl DownloadWorker {
             }
         });
 
+        let live = is_live.then(|| LiveRecording {
+            stop_token: &stop_token,
+            output_dir: &output_dir,
+            file_stem: &output_path.stem,
+            pre_download_files: &pre_download_files,
+            started: Instant::now(),
+        });
         let progress_result = self
-            .handle_progress_updates(task_id, child, cancel_token, db)
+            .handle_progress_updates(task_id, child, cancel_token, live, db)
             .await;
 
         if let Err(e) = stderr_handle.await {
             tracing::warn!("Stderr task failed: {}", e);
         }
 
-        let (mut final_child, final_total_bytes, final_downloaded_bytes, was_killed) =
+        let (mut final_child, final_total_bytes, final_downloaded_bytes, was_killed, was_stopped) =
             progress_result?;
 
         if was_killed {
@@ -593,8 +1222,35 @@ impl DownloadWorker {
                 filename: None,
             })?;
 
-        let result = if status.success() {
-            let result_filename = Self::find_new_media_file(&output_dir, &pre_download_files).await;
+        // A stopped recording exits with an error status but is kept: its partial
+        // files become the download.
+        if was_stopped {
+            let ffmpeg_path = get_binary_path(&self.app, SidecarBinary::Ffmpeg).ok();
+            live::finalize_recording(
+                ffmpeg_path.as_deref(),
+                &output_dir,
+                &output_path.stem,
+                &pre_download_files,
+            )
+            .await;
+        }
+
+        let result = if status.success() || was_stopped {
+            let is_sectioned = options
+                .as_ref()
+                .is_some_and(|o| !o.download_section_args().is_empty());
+            let mut extra_files = Vec::new();
+            let result_filename = if is_sectioned {
+                let mut clips = Self::find_new_clip_files(&output_dir, &pre_download_files).await;
+                if clips.is_empty() {
+                    None
+                } else {
+                    extra_files = clips.split_off(1);
+                    clips.pop()
+                }
+            } else {
+                Self::find_new_media_file(&output_dir, &pre_download_files).await
+            };
 
             if result_filename.is_none() {
                 tracing::warn!("Could not identify downloaded file via filesystem scan");
@@ -622,7 +1278,10 @@ impl DownloadWorker {
             Ok(DownloadResult {
                 total_bytes: actual_file_size,
                 downloaded_bytes: total,
-                filename: result_filename,
+                filename: result_filename.map(|name| output_path.relative(&name)),
+                extra_files: extra_files.iter().map(|name| output_path.relative(name)).collect(),
+                checksum: None,
+                sponsor_segments,
             })
         } else {
             let stderr_output = stderr_lines.lock().await;
@@ -640,20 +1299,19 @@ impl DownloadWorker {
                     .collect();
                 tail.join("\n")
             };
-            Err(DownloadError::Failed {
-                message: error_detail,
-                total_bytes: final_total_bytes,
-                downloaded_bytes: final_downloaded_bytes,
-                filename: None,
-            })
+            if live::is_not_started_error(&error_detail) {
+                Err(DownloadError::Upcoming { starts_at: None })
+            } else {
+                Err(DownloadError::Failed {
+                    message: error_detail,
+                    total_bytes: final_total_bytes,
+                    downloaded_bytes: final_downloaded_bytes,
+                    filename: None,
+                })
+            }
         };
 
-        if let Some(path) = temp_cookie_path {
-            let cookie_manager = self.app.state::<std::sync::Arc<CookieManager>>();
-            if let Err(e) = cookie_manager.cleanup_temp_file(&path).await {
-                tracing::warn!("Failed to cleanup temp cookie file: {}", e);
-            }
-        }
+        self.cleanup_temp_cookie(temp_cookie_path).await;
 
         result
     }
@@ -22,6 +22,15 @@ pub struct Model {
     pub completed_at: Option<DateTimeUtc>,
     pub downloaded_bytes: Option<i64>,
     pub total_bytes: Option<i64>,
+    /// Requested `--download-sections` ranges, `; `-separated. None = whole video.
+    pub sections: Option<String>,
+    /// When a WAITING task is released back to the queue: the event's start time,
+    /// or the next check if the start time is unknown.
+    pub scheduled_at: Option<DateTimeUtc>,
+    /// Give up on a WAITING task that still hasn't started by this time.
+    pub wait_deadline: Option<DateTimeUtc>,
+    /// Folder the task's output was written to; None for the download folder itself.
+    pub folder_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
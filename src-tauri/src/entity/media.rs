@@ -1,7 +1,7 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "media")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = true)]
@@ -9,7 +9,12 @@ pub struct Model {
     pub post_id: i64,
     #[sea_orm(column_name = "type")]
     pub media_type: String,
+    /// File name inside the folder; absolute only for files kept outside the download folder.
     pub file_path: String,
+    /// Folder below the download folder holding the file; None for the folder itself.
+    pub folder_id: Option<i64>,
+    /// Scaled thumbnail. Like every asset path below, a file name next to the media
+    /// file, absolute only when `file_path` is.
     pub thumbnail_path: Option<String>,
     pub order_index: i32,
     pub width: Option<i32>,
@@ -19,6 +24,20 @@ pub struct Model {
     pub format_id: Option<String>,
     pub checksum: Option<String>,
     pub deleted_at: Option<DateTimeUtc>,
+    /// Short animated WebP sampled across the video, shown on hover.
+    pub preview_path: Option<String>,
+    /// Storyboard sprite sheet used for scrubbing.
+    pub sprite_path: Option<String>,
+    /// WebVTT index mapping time ranges to tiles in `sprite_path`.
+    pub sprite_vtt_path: Option<String>,
+    /// Full-size platform thumbnail written by yt-dlp; `thumbnail_path` is scaled from it.
+    pub poster_path: Option<String>,
+    /// Language code of a SUBTITLE row (e.g. "en", "pt-BR").
+    pub language: Option<String>,
+    /// Integrated loudness (LUFS) measured by the loudness-normalization stage.
+    pub loudness_lufs: Option<f64>,
+    /// AVAILABLE, or MISSING when the library scan can't find the file.
+    pub status: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -29,6 +48,12 @@ pub enum Relation {
         to = "super::post::Column::Id"
     )]
     Post,
+    #[sea_orm(
+        belongs_to = "super::folder::Entity",
+        from = "Column::FolderId",
+        to = "super::folder::Column::Id"
+    )]
+    Folder,
 }
 
 impl Related<super::post::Entity> for Entity {
@@ -37,4 +62,10 @@ impl Related<super::post::Entity> for Entity {
     }
 }
 
+impl Related<super::folder::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Folder.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
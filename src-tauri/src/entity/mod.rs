@@ -1,8 +1,12 @@
+pub mod chapter;
 pub mod creator;
+pub mod download_archive;
 pub mod download_task;
+pub mod folder;
 pub mod media;
 pub mod platform;
 pub mod platform_session;
 pub mod post;
 pub mod setting;
 pub mod source;
+pub mod sponsor_segment;
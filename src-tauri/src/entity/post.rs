@@ -37,6 +37,10 @@ pub enum Relation {
     Source,
     #[sea_orm(has_many = "super::media::Entity")]
     Media,
+    #[sea_orm(has_many = "super::chapter::Entity")]
+    Chapter,
+    #[sea_orm(has_many = "super::sponsor_segment::Entity")]
+    SponsorSegment,
     #[sea_orm(has_one = "super::download_task::Entity")]
     DownloadTask,
 }
@@ -59,6 +63,18 @@ impl Related<super::media::Entity> for Entity {
     }
 }
 
+impl Related<super::chapter::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Chapter.def()
+    }
+}
+
+impl Related<super::sponsor_segment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SponsorSegment.def()
+    }
+}
+
 impl Related<super::download_task::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::DownloadTask.def()
@@ -20,6 +20,8 @@ pub struct Model {
     pub max_items: Option<i32>,
     pub last_checked: Option<DateTimeUtc>,
     pub is_active: bool,
+    /// Overrides the `filename_template` setting for this source's downloads.
+    pub filename_template: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,9 +1,12 @@
 pub mod auth;
+pub mod backup;
 pub mod constants;
 pub mod background;
 mod commands;
 pub mod db;
 pub mod download;
+pub mod library;
+pub mod maintenance;
 mod entity;
 pub mod metadata;
 pub mod migration;
@@ -15,7 +18,7 @@ use sea_orm::{DatabaseConnection, EntityTrait};
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
-    Manager, WindowEvent,
+    Manager, RunEvent, WindowEvent,
 };
 use tracing_subscriber::{fmt, EnvFilter};
 
@@ -82,6 +85,32 @@ pub fn run() {
                 std::sync::Arc::new(db.clone()),
             );
 
+            // Scheduled database checks and orphan cleanup
+            background::db_maintenance::start_db_maintenance(
+                app.handle(),
+                std::sync::Arc::new(db.clone()),
+            );
+
+            // Scheduled, rotating database backups
+            background::backup_scheduler::start_backup_scheduler(
+                app.handle(),
+                std::sync::Arc::new(db.clone()),
+                app_data_dir.clone(),
+            );
+
+            // Check the library against the disk once the app is up
+            background::library_scan::start_library_scan(
+                app.handle(),
+                std::sync::Arc::new(db.clone()),
+            );
+
+            // Follow changes made to the download folder outside the app
+            let library_watcher = background::library_watcher::start_library_watcher(
+                app.handle(),
+                std::sync::Arc::new(db.clone()),
+            );
+            app.manage(library_watcher);
+
             // Initialize sidecars (copy from bundle to app_data if needed)
             let handle = app.handle().clone();
             tauri::async_runtime::block_on(async move {
@@ -187,6 +216,7 @@ pub fn run() {
             commands::sidecar::update_ytdlp,
             commands::download::create_download_task,
             commands::download::cancel_download_task,
+            commands::download::stop_recording,
             commands::download::retry_download_task,
             commands::download::get_queue_status,
             commands::download::pause_download_task,
@@ -196,6 +226,7 @@ pub fn run() {
             commands::download::fetch_metadata_command,
             commands::download::clear_download_history,
             commands::download::retry_all_failed,
+            commands::download::get_transcode_profiles,
             commands::auth::get_auth_status,
             commands::auth::update_session,
             commands::auth::delete_session,
@@ -206,17 +237,43 @@ pub fn run() {
             commands::settings::get_settings,
             commands::settings::update_setting,
             commands::settings::select_download_path,
+            commands::backup::create_backup,
+            commands::backup::restore_backup,
+            commands::backup::restart_app,
+            commands::maintenance::run_db_maintenance,
             commands::wall::get_posts,
+            commands::wall::get_post_subtitles,
+            commands::wall::get_post_chapters,
+            commands::wall::get_post_sponsor_segments,
             commands::wall::delete_post,
             commands::wall::reveal_in_explorer,
             commands::wall::restore_post,
             commands::wall::get_trash_posts,
             commands::wall::empty_trash_command,
+            commands::wall::regenerate_thumbnails,
+            commands::wall::normalize_post_loudness,
+            commands::library::get_pending_relocation,
+            commands::library::relocate_library,
+            commands::library::reconcile_library,
+            commands::library::redownload_missing,
+            commands::library::import_local_files,
+            commands::library::import_download_archive,
+            commands::library::export_download_archive,
+            commands::library::export_catalog,
+            commands::library::import_catalog,
             commands::sources::get_sources_command,
             commands::sources::delete_source_command,
             commands::sources::add_source_command,
             commands::sources::update_source_command,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                // Don't leave a preview ffmpeg running after the app is gone.
+                if let Some(queue) = app.try_state::<queue::DownloadQueue>() {
+                    queue.stop_previews();
+                }
+            }
+        });
 }
@@ -0,0 +1,295 @@
+//! Interoperability with yt-dlp's `--download-archive` files, which list one
+//! `<extractor> <id>` line per downloaded video. Imported entries are kept so
+//! syncs skip those videos, and the archive file named by the
+//! `download_archive_path` setting is rewritten whenever a post completes, so
+//! scripts using it and the app agree on what is done.
+
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, QueryFilter,
+    QuerySelect, RelationTrait, Set,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::entity::{creator, download_archive, post};
+
+/// Settings key of the archive file kept in step with the library.
+pub const ARCHIVE_PATH_KEY: &str = "download_archive_path";
+
+/// Status of a post another tool already downloaded; syncs only queue PENDING posts.
+pub const POST_ARCHIVED: &str = "ARCHIVED";
+
+/// yt-dlp extractor keys (lowercased) and the platforms they belong to.
+const EXTRACTORS: &[(&str, &str)] = &[
+    ("youtube", "youtube"),
+    ("tiktok", "tiktok"),
+    ("instagram", "instagram"),
+    ("twitter", "x"),
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ArchiveImportReport {
+    /// Entries read from the file.
+    pub entries: usize,
+    /// Entries for sites the library has no platform for; kept for export.
+    pub unsupported: usize,
+    /// Pending posts marked as already downloaded.
+    pub posts_marked: usize,
+}
+
+/// `youtube dQw4w9WgXcQ` → (`youtube`, `dQw4w9WgXcQ`).
+pub fn parse_line(line: &str) -> Option<(String, String)> {
+    let mut parts = line.split_whitespace();
+    let extractor = parts.next()?.to_lowercase();
+    let id = parts.next()?.to_string();
+    parts.next().is_none().then_some((extractor, id))
+}
+
+fn platform_of(extractor: &str) -> Option<&'static str> {
+    EXTRACTORS
+        .iter()
+        .find(|(e, _)| *e == extractor)
+        .map(|(_, p)| *p)
+}
+
+fn extractor_of(platform: &str) -> Option<&'static str> {
+    EXTRACTORS
+        .iter()
+        .find(|(_, p)| *p == platform)
+        .map(|(e, _)| *e)
+}
+
+/// Whether an imported archive lists the video `external_id` on `platform`.
+pub async fn is_archived(db: &impl ConnectionTrait, platform: &str, external_id: &str) -> bool {
+    let Some(extractor) = extractor_of(platform) else {
+        return false;
+    };
+    download_archive::Entity::find()
+        .filter(download_archive::Column::Extractor.eq(extractor))
+        .filter(download_archive::Column::ExternalId.eq(external_id))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+/// Record every entry of the archive at `path` and mark the matching pending
+/// posts as already downloaded.
+pub async fn import_archive(
+    db: &impl ConnectionTrait,
+    path: &Path,
+) -> Result<ArchiveImportReport, String> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let entries: BTreeSet<(String, String)> = text.lines().filter_map(parse_line).collect();
+
+    let mut report = ArchiveImportReport {
+        entries: entries.len(),
+        ..Default::default()
+    };
+    let db_error = |e: sea_orm::DbErr| format!("Database error importing archive: {}", e);
+    for (extractor, id) in &entries {
+        download_archive::Entity::insert(download_archive::ActiveModel {
+            extractor: Set(extractor.clone()),
+            external_id: Set(id.clone()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::columns([
+                download_archive::Column::Extractor,
+                download_archive::Column::ExternalId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await
+        .map_err(db_error)?;
+
+        let Some(platform) = platform_of(extractor) else {
+            report.unsupported += 1;
+            continue;
+        };
+        let pending = post::Entity::find()
+            .join(JoinType::InnerJoin, post::Relation::Creator.def())
+            .filter(creator::Column::PlatformId.eq(platform))
+            .filter(post::Column::ExternalId.eq(id.as_str()))
+            .filter(post::Column::Status.eq("PENDING"))
+            .all(db)
+            .await
+            .map_err(db_error)?;
+        for p in pending {
+            post::Entity::update(post::ActiveModel {
+                id: Set(p.id),
+                status: Set(POST_ARCHIVED.to_string()),
+                ..Default::default()
+            })
+            .exec(db)
+            .await
+            .map_err(db_error)?;
+            report.posts_marked += 1;
+        }
+    }
+
+    tracing::info!("Imported download archive {}: {:?}", path.display(), report);
+    Ok(report)
+}
+
+/// Write an archive of every imported entry and every COMPLETED post to `path`,
+/// replacing it in one step. Returns the number of lines written.
+pub async fn export_archive(db: &impl ConnectionTrait, path: &Path) -> Result<usize, String> {
+    let db_error = |e: sea_orm::DbErr| format!("Database error exporting archive: {}", e);
+    let mut lines: BTreeSet<String> = download_archive::Entity::find()
+        .all(db)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|e| format!("{} {}", e.extractor, e.external_id))
+        .collect();
+
+    let completed: Vec<(String, String)> = post::Entity::find()
+        .select_only()
+        .column(creator::Column::PlatformId)
+        .column(post::Column::ExternalId)
+        .join(JoinType::InnerJoin, post::Relation::Creator.def())
+        .filter(post::Column::Status.eq("COMPLETED"))
+        .filter(post::Column::DeletedAt.is_null())
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(db_error)?;
+    for (platform, id) in completed {
+        if let Some(extractor) = extractor_of(&platform) {
+            lines.insert(format!("{} {}", extractor, id));
+        }
+    }
+
+    let text: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".tmp");
+    tokio::fs::write(&partial, text)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    tokio::fs::rename(&partial, path)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(lines.len())
+}
+
+/// Rewrite the archive file named by the `download_archive_path` setting, if any.
+pub async fn export_configured(db: &impl ConnectionTrait) {
+    let Some(path) = crate::db::get_setting(db, ARCHIVE_PATH_KEY)
+        .await
+        .filter(|p| !p.trim().is_empty())
+    else {
+        return;
+    };
+    if let Err(e) = export_archive(db, Path::new(&path)).await {
+        tracing::warn!("Failed to export download archive: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::metadata::models::{YtDlpOutput, YtDlpVideo};
+    use crate::metadata::store::save_metadata;
+
+    fn video(id: &str) -> YtDlpOutput {
+        let json = serde_json::json!({
+            "_type": "video",
+            "id": id,
+            "title": id,
+            "uploader": "Channel",
+            "uploader_id": "channel",
+            "webpage_url": format!("https://www.youtube.com/watch?v={}", id),
+        });
+        YtDlpOutput::Video(serde_json::from_value::<YtDlpVideo>(json).unwrap())
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("youtube dQw4w9WgXcQ"),
+            Some(("youtube".to_string(), "dQw4w9WgXcQ".to_string()))
+        );
+        assert_eq!(
+            parse_line("Twitter 123\r"),
+            Some(("twitter".to_string(), "123".to_string()))
+        );
+        assert_eq!(parse_line(""), None);
+        assert_eq!(parse_line("youtube"), None);
+        assert_eq!(parse_line("youtube a b"), None);
+    }
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let dir = std::env::temp_dir().join(format!("vdp-archive-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let known = save_metadata(&db, video("known000001"), None, None, None, None)
+            .await
+            .unwrap();
+        let done = save_metadata(&db, video("done0000001"), None, None, None, None)
+            .await
+            .unwrap();
+        post::Entity::update(post::ActiveModel {
+            id: Set(done),
+            status: Set("COMPLETED".to_string()),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+
+        let input = dir.join("archive.txt");
+        tokio::fs::write(
+            &input,
+            "youtube known000001\nyoutube later000001\nvimeo 42\n\nyoutube known000001\n",
+        )
+        .await
+        .unwrap();
+        let report = import_archive(&db, &input).await.unwrap();
+        assert_eq!(
+            report,
+            ArchiveImportReport {
+                entries: 3,
+                unsupported: 1,
+                posts_marked: 1,
+            }
+        );
+
+        let status = |id| {
+            let db = &db;
+            async move {
+                post::Entity::find_by_id(id)
+                    .one(db)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .status
+            }
+        };
+        assert_eq!(status(known).await, POST_ARCHIVED);
+        // A sync finding an archived video later doesn't queue it either.
+        let later = save_metadata(&db, video("later000001"), None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(status(later).await, POST_ARCHIVED);
+
+        let output = dir.join("out.txt");
+        assert_eq!(export_archive(&db, &output).await.unwrap(), 4);
+        assert_eq!(
+            tokio::fs::read_to_string(&output).await.unwrap(),
+            "vimeo 42\nyoutube done0000001\nyoutube known000001\nyoutube later000001\n"
+        );
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+}
@@ -0,0 +1,824 @@
+//! Exporting the catalog (platforms, creators, sources, posts and media) as JSON
+//! or CSV, and merging such an export into another library. Rows refer to each
+//! other by platform and external IDs rather than database IDs, and media by
+//! their path below the download folder, so an export can be audited as is or
+//! imported on another machine without creating duplicates.
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, JoinType, QueryFilter,
+    QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use super::folders::{download_root, ensure_folder, location_filter};
+use super::reconcile::{MEDIA_AVAILABLE, MEDIA_MISSING};
+use crate::entity::{creator, folder, media, platform, post, source};
+use crate::metadata::models::YtDlpVideo;
+
+/// Version written to JSON exports; newer files are refused.
+const CATALOG_VERSION: u32 = 1;
+
+/// File names of a CSV export, one per table, inside the export directory.
+const PLATFORMS_CSV: &str = "platforms.csv";
+const CREATORS_CSV: &str = "creators.csv";
+const SOURCES_CSV: &str = "sources.csv";
+const POSTS_CSV: &str = "posts.csv";
+const MEDIA_CSV: &str = "media.csv";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogFormat {
+    /// One document holding every table.
+    Json,
+    /// A directory with one file per table.
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlatformRecord {
+    pub id: String,
+    pub name: String,
+    pub base_url: String,
+}
+
+/// A creator is identified by its platform and external ID, or its name when it has none.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatorRecord {
+    pub platform_id: String,
+    pub external_id: Option<String>,
+    pub name: String,
+    pub handle: Option<String>,
+    pub url: String,
+    pub is_self: bool,
+}
+
+/// A source is identified by its platform, URL and feed type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub platform_id: String,
+    pub url: String,
+    pub feed_type: Option<String>,
+    pub creator_external_id: Option<String>,
+    pub creator_name: Option<String>,
+    pub external_id: Option<String>,
+    pub source_type: String,
+    pub name: String,
+    pub sync_mode: String,
+    pub max_items: Option<i32>,
+    pub is_active: bool,
+    pub filename_template: Option<String>,
+}
+
+/// A post is identified by its platform and external ID. `duration`, `view_count`
+/// and `like_count` come from the stored yt-dlp metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostRecord {
+    pub platform_id: String,
+    pub external_id: String,
+    pub creator_external_id: Option<String>,
+    pub creator_name: String,
+    pub source_url: Option<String>,
+    pub source_feed_type: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub original_url: String,
+    pub status: String,
+    pub posted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub downloaded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub duration: Option<f64>,
+    pub view_count: Option<u64>,
+    pub like_count: Option<u64>,
+}
+
+/// A media file of a post. `path` is `/`-separated below the download folder,
+/// or absolute for files kept outside it. Imports only accept paths that stay
+/// within the download folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaRecord {
+    pub platform_id: String,
+    pub post_external_id: String,
+    pub media_type: String,
+    pub path: String,
+    pub order_index: i32,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub duration: Option<i32>,
+    pub file_size: Option<i32>,
+    pub checksum: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Catalog {
+    pub version: u32,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub platforms: Vec<PlatformRecord>,
+    pub creators: Vec<CreatorRecord>,
+    pub sources: Vec<SourceRecord>,
+    pub posts: Vec<PostRecord>,
+    pub media: Vec<MediaRecord>,
+}
+
+/// Rows per table.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CatalogCounts {
+    pub platforms: usize,
+    pub creators: usize,
+    pub sources: usize,
+    pub posts: usize,
+    pub media: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CatalogImportReport {
+    /// Rows created in this library.
+    pub added: CatalogCounts,
+    /// Rows the library already had; they are left unchanged.
+    pub existing: CatalogCounts,
+}
+
+impl Catalog {
+    pub fn counts(&self) -> CatalogCounts {
+        CatalogCounts {
+            platforms: self.platforms.len(),
+            creators: self.creators.len(),
+            sources: self.sources.len(),
+            posts: self.posts.len(),
+            media: self.media.len(),
+        }
+    }
+}
+
+/// Read the whole catalog. Deleted media rows are left out.
+pub async fn collect(db: &impl ConnectionTrait) -> Result<Catalog, String> {
+    let db_error = |e: DbErr| format!("Database error exporting catalog: {}", e);
+
+    let platforms = platform::Entity::find()
+        .all(db)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|p| PlatformRecord {
+            id: p.id,
+            name: p.name,
+            base_url: p.base_url,
+        })
+        .collect();
+
+    let creators: HashMap<i64, creator::Model> = creator::Entity::find()
+        .all(db)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|c| (c.id, c))
+        .collect();
+    let sources: HashMap<i64, source::Model> = source::Entity::find()
+        .all(db)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|s| (s.id, s))
+        .collect();
+    let folders: HashMap<i64, String> = folder::Entity::find()
+        .all(db)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|f| (f.id, f.relative_path))
+        .collect();
+
+    let mut creator_records: Vec<CreatorRecord> = creators
+        .values()
+        .map(|c| CreatorRecord {
+            platform_id: c.platform_id.clone(),
+            external_id: c.external_id.clone(),
+            name: c.name.clone(),
+            handle: c.handle.clone(),
+            url: c.url.clone(),
+            is_self: c.is_self,
+        })
+        .collect();
+    creator_records.sort_by(|a, b| (&a.platform_id, &a.name).cmp(&(&b.platform_id, &b.name)));
+
+    let mut source_records: Vec<SourceRecord> = sources
+        .values()
+        .map(|s| {
+            let owner = s.creator_id.and_then(|id| creators.get(&id));
+            SourceRecord {
+                platform_id: s.platform_id.clone(),
+                url: s.url.clone(),
+                feed_type: s.feed_type.clone(),
+                creator_external_id: owner.and_then(|c| c.external_id.clone()),
+                creator_name: owner.map(|c| c.name.clone()),
+                external_id: s.external_id.clone(),
+                source_type: s.source_type.clone(),
+                name: s.name.clone(),
+                sync_mode: s.sync_mode.clone(),
+                max_items: s.max_items,
+                is_active: s.is_active,
+                filename_template: s.filename_template.clone(),
+            }
+        })
+        .collect();
+    source_records.sort_by(|a, b| (&a.platform_id, &a.url).cmp(&(&b.platform_id, &b.url)));
+
+    let mut posts = Vec::new();
+    let mut post_keys: HashMap<i64, (String, String)> = HashMap::new();
+    for p in post::Entity::find().all(db).await.map_err(db_error)? {
+        let Some(owner) = creators.get(&p.creator_id) else {
+            continue;
+        };
+        let video: Option<YtDlpVideo> = p
+            .raw_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok());
+        let origin = p.source_id.and_then(|id| sources.get(&id));
+        post_keys.insert(p.id, (owner.platform_id.clone(), p.external_id.clone()));
+        posts.push(PostRecord {
+            platform_id: owner.platform_id.clone(),
+            external_id: p.external_id,
+            creator_external_id: owner.external_id.clone(),
+            creator_name: owner.name.clone(),
+            source_url: origin.map(|s| s.url.clone()),
+            source_feed_type: origin.and_then(|s| s.feed_type.clone()),
+            title: p.title,
+            description: p.description,
+            original_url: p.original_url,
+            status: p.status,
+            posted_at: p.posted_at,
+            downloaded_at: p.downloaded_at,
+            deleted_at: p.deleted_at,
+            duration: video.as_ref().and_then(|v| v.duration),
+            view_count: video.as_ref().and_then(|v| v.view_count),
+            like_count: video.as_ref().and_then(|v| v.like_count),
+        });
+    }
+    posts.sort_by(|a, b| (&a.platform_id, &a.external_id).cmp(&(&b.platform_id, &b.external_id)));
+
+    let mut media_records = Vec::new();
+    let rows = media::Entity::find()
+        .filter(media::Column::DeletedAt.is_null())
+        .all(db)
+        .await
+        .map_err(db_error)?;
+    for m in rows {
+        let Some((platform_id, post_external_id)) = post_keys.get(&m.post_id).cloned() else {
+            continue;
+        };
+        let path = match m.folder_id.and_then(|id| folders.get(&id)) {
+            Some(dir) => format!("{}/{}", dir, m.file_path),
+            None => m.file_path,
+        };
+        media_records.push(MediaRecord {
+            platform_id,
+            post_external_id,
+            media_type: m.media_type,
+            path,
+            order_index: m.order_index,
+            width: m.width,
+            height: m.height,
+            duration: m.duration,
+            file_size: m.file_size,
+            checksum: m.checksum,
+            language: m.language,
+        });
+    }
+    media_records.sort_by(|a, b| {
+        (&a.platform_id, &a.post_external_id, a.order_index, &a.path).cmp(&(
+            &b.platform_id,
+            &b.post_external_id,
+            b.order_index,
+            &b.path,
+        ))
+    });
+
+    Ok(Catalog {
+        version: CATALOG_VERSION,
+        exported_at: chrono::Utc::now(),
+        platforms,
+        creators: creator_records,
+        sources: source_records,
+        posts,
+        media: media_records,
+    })
+}
+
+/// Export the catalog to `path`: a JSON file, or a directory of CSV files.
+pub async fn export_catalog(
+    db: &impl ConnectionTrait,
+    path: &Path,
+    format: CatalogFormat,
+) -> Result<CatalogCounts, String> {
+    let catalog = collect(db).await?;
+    match format {
+        CatalogFormat::Json => {
+            let json = serde_json::to_vec_pretty(&catalog)
+                .map_err(|e| format!("Failed to serialize catalog: {}", e))?;
+            write_file(path, json).await?;
+        }
+        CatalogFormat::Csv => {
+            tokio::fs::create_dir_all(path)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            write_file(&path.join(PLATFORMS_CSV), to_csv(&catalog.platforms)?).await?;
+            write_file(&path.join(CREATORS_CSV), to_csv(&catalog.creators)?).await?;
+            write_file(&path.join(SOURCES_CSV), to_csv(&catalog.sources)?).await?;
+            write_file(&path.join(POSTS_CSV), to_csv(&catalog.posts)?).await?;
+            write_file(&path.join(MEDIA_CSV), to_csv(&catalog.media)?).await?;
+        }
+    }
+    let counts = catalog.counts();
+    tracing::info!("Exported catalog to {}: {:?}", path.display(), counts);
+    Ok(counts)
+}
+
+/// Read an export written by [`export_catalog`]. A CSV directory may leave out
+/// tables it has no rows for.
+pub async fn read_catalog(path: &Path, format: CatalogFormat) -> Result<Catalog, String> {
+    match format {
+        CatalogFormat::Json => {
+            let bytes = tokio::fs::read(path)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let catalog: Catalog = serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Invalid catalog {}: {}", path.display(), e))?;
+            if catalog.version > CATALOG_VERSION {
+                return Err(format!(
+                    "{} was exported by a newer version (catalog version {})",
+                    path.display(),
+                    catalog.version
+                ));
+            }
+            Ok(catalog)
+        }
+        CatalogFormat::Csv => Ok(Catalog {
+            version: CATALOG_VERSION,
+            exported_at: chrono::Utc::now(),
+            platforms: from_csv(&path.join(PLATFORMS_CSV)).await?,
+            creators: from_csv(&path.join(CREATORS_CSV)).await?,
+            sources: from_csv(&path.join(SOURCES_CSV)).await?,
+            posts: from_csv(&path.join(POSTS_CSV)).await?,
+            media: from_csv(&path.join(MEDIA_CSV)).await?,
+        }),
+    }
+}
+
+/// Merge the export at `path` into the library in one transaction. Rows are
+/// matched on their platform and external IDs (media on post and path); rows
+/// the library already has are left as they are.
+pub async fn import_catalog(
+    db: &DatabaseConnection,
+    path: &Path,
+    format: CatalogFormat,
+) -> Result<CatalogImportReport, String> {
+    let catalog = read_catalog(path, format).await?;
+    let root = download_root(db).await;
+    let db_error = |e: DbErr| format!("Database error importing catalog: {}", e);
+    let txn = db.begin().await.map_err(db_error)?;
+    let report = merge(&txn, &catalog, &root).await?;
+    txn.commit().await.map_err(db_error)?;
+    tracing::info!("Imported catalog {}: {:?}", path.display(), report);
+    Ok(report)
+}
+
+async fn merge(
+    db: &impl ConnectionTrait,
+    catalog: &Catalog,
+    root: &Path,
+) -> Result<CatalogImportReport, String> {
+    let db_error = |e: DbErr| format!("Database error importing catalog: {}", e);
+    let mut report = CatalogImportReport::default();
+
+    for p in &catalog.platforms {
+        if platform::Entity::find_by_id(p.id.as_str())
+            .one(db)
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            report.existing.platforms += 1;
+            continue;
+        }
+        platform::Entity::insert(platform::ActiveModel {
+            id: Set(p.id.clone()),
+            name: Set(p.name.clone()),
+            base_url: Set(p.base_url.clone()),
+            icon_path: Set(None),
+        })
+        .exec_without_returning(db)
+        .await
+        .map_err(db_error)?;
+        report.added.platforms += 1;
+    }
+
+    for c in &catalog.creators {
+        if find_creator(db, &c.platform_id, c.external_id.as_deref(), &c.name)
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            report.existing.creators += 1;
+            continue;
+        }
+        creator::Entity::insert(creator::ActiveModel {
+            platform_id: Set(c.platform_id.clone()),
+            external_id: Set(c.external_id.clone()),
+            is_self: Set(c.is_self),
+            name: Set(c.name.clone()),
+            handle: Set(c.handle.clone()),
+            url: Set(c.url.clone()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+        report.added.creators += 1;
+    }
+
+    for s in &catalog.sources {
+        if find_source(db, &s.platform_id, &s.url, s.feed_type.as_deref())
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            report.existing.sources += 1;
+            continue;
+        }
+        let creator_id = match &s.creator_name {
+            Some(name) => find_creator(db, &s.platform_id, s.creator_external_id.as_deref(), name)
+                .await
+                .map_err(db_error)?,
+            None => None,
+        };
+        source::Entity::insert(source::ActiveModel {
+            platform_id: Set(s.platform_id.clone()),
+            creator_id: Set(creator_id),
+            external_id: Set(s.external_id.clone()),
+            source_type: Set(s.source_type.clone()),
+            feed_type: Set(s.feed_type.clone()),
+            name: Set(s.name.clone()),
+            url: Set(s.url.clone()),
+            sync_mode: Set(s.sync_mode.clone()),
+            max_items: Set(s.max_items),
+            is_active: Set(s.is_active),
+            filename_template: Set(s.filename_template.clone()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+        report.added.sources += 1;
+    }
+
+    for p in &catalog.posts {
+        if find_post(db, &p.platform_id, &p.external_id)
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            report.existing.posts += 1;
+            continue;
+        }
+        let creator_id = find_creator(
+            db,
+            &p.platform_id,
+            p.creator_external_id.as_deref(),
+            &p.creator_name,
+        )
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            format!(
+                "Post {} {} belongs to creator {}, which is not in the catalog",
+                p.platform_id, p.external_id, p.creator_name
+            )
+        })?;
+        let source_id = match &p.source_url {
+            Some(url) => find_source(db, &p.platform_id, url, p.source_feed_type.as_deref())
+                .await
+                .map_err(db_error)?,
+            None => None,
+        };
+        post::Entity::insert(post::ActiveModel {
+            creator_id: Set(creator_id),
+            source_id: Set(source_id),
+            external_id: Set(p.external_id.clone()),
+            title: Set(p.title.clone()),
+            description: Set(p.description.clone()),
+            original_url: Set(p.original_url.clone()),
+            status: Set(p.status.clone()),
+            posted_at: Set(p.posted_at),
+            downloaded_at: Set(p.downloaded_at),
+            deleted_at: Set(p.deleted_at),
+            raw_json: Set(raw_json(p)),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+        report.added.posts += 1;
+    }
+
+    for m in &catalog.media {
+        let post_id = find_post(db, &m.platform_id, &m.post_external_id)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| {
+                format!(
+                    "Media {} belongs to post {} {}, which is not in the catalog",
+                    m.path, m.platform_id, m.post_external_id
+                )
+            })?;
+        let relative = relative_media_path(&m.path, root)?;
+        let (dir, name) = match relative.rsplit_once('/') {
+            Some((dir, name)) => (Some(dir), name),
+            None => (None, relative.as_str()),
+        };
+        let folder_id = match dir {
+            Some(dir) => ensure_folder(db, Path::new(dir)).await.map_err(db_error)?,
+            None => None,
+        };
+        let file_path = name.to_string();
+        let on_disk: PathBuf = root.join(relative.split('/').collect::<PathBuf>());
+
+        let existing = media::Entity::find()
+            .filter(media::Column::PostId.eq(post_id))
+            .filter(location_filter(folder_id, &file_path))
+            .one(db)
+            .await
+            .map_err(db_error)?;
+        if existing.is_some() {
+            report.existing.media += 1;
+            continue;
+        }
+        // The files are copied separately; until they arrive the rows show as missing.
+        let status = if on_disk.is_file() {
+            MEDIA_AVAILABLE
+        } else {
+            MEDIA_MISSING
+        };
+        media::Entity::insert(media::ActiveModel {
+            post_id: Set(post_id),
+            media_type: Set(m.media_type.clone()),
+            file_path: Set(file_path),
+            folder_id: Set(folder_id),
+            order_index: Set(m.order_index),
+            width: Set(m.width),
+            height: Set(m.height),
+            duration: Set(m.duration),
+            file_size: Set(m.file_size),
+            checksum: Set(m.checksum.clone()),
+            language: Set(m.language.clone()),
+            status: Set(status.to_string()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(db_error)?;
+        report.added.media += 1;
+    }
+
+    Ok(report)
+}
+
+/// A catalog media path as `/`-separated components below `root`. Deleting a
+/// post deletes its files, so paths leaving the download folder are refused;
+/// absolute ones are accepted only below `root`.
+fn relative_media_path(path: &str, root: &Path) -> Result<String, String> {
+    let outside = || format!("Media path {} is outside the download folder", path);
+    let path_ref = Path::new(path);
+    let relative = if path_ref.is_absolute() {
+        path_ref.strip_prefix(root).map_err(|_| outside())?
+    } else {
+        path_ref
+    };
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            Component::CurDir => {}
+            _ => return Err(outside()),
+        }
+    }
+    if parts.is_empty() {
+        return Err(format!("Media path {:?} names no file", path));
+    }
+    Ok(parts.join("/"))
+}
+
+/// Matches creators the way `metadata::store` does: by external ID when there is
+/// one, else by name.
+async fn find_creator(
+    db: &impl ConnectionTrait,
+    platform_id: &str,
+    external_id: Option<&str>,
+    name: &str,
+) -> Result<Option<i64>, DbErr> {
+    let query = creator::Entity::find().filter(creator::Column::PlatformId.eq(platform_id));
+    let query = match external_id {
+        Some(id) => query.filter(creator::Column::ExternalId.eq(id)),
+        None => query.filter(creator::Column::Name.eq(name)),
+    };
+    Ok(query.one(db).await?.map(|c| c.id))
+}
+
+async fn find_source(
+    db: &impl ConnectionTrait,
+    platform_id: &str,
+    url: &str,
+    feed_type: Option<&str>,
+) -> Result<Option<i64>, DbErr> {
+    let query = source::Entity::find()
+        .filter(source::Column::PlatformId.eq(platform_id))
+        .filter(source::Column::Url.eq(url));
+    let query = match feed_type {
+        Some(feed) => query.filter(source::Column::FeedType.eq(feed)),
+        None => query.filter(source::Column::FeedType.is_null()),
+    };
+    Ok(query.one(db).await?.map(|s| s.id))
+}
+
+async fn find_post(
+    db: &impl ConnectionTrait,
+    platform_id: &str,
+    external_id: &str,
+) -> Result<Option<i64>, DbErr> {
+    Ok(post::Entity::find()
+        .join(JoinType::InnerJoin, post::Relation::Creator.def())
+        .filter(creator::Column::PlatformId.eq(platform_id))
+        .filter(post::Column::ExternalId.eq(external_id))
+        .one(db)
+        .await?
+        .map(|p| p.id))
+}
+
+/// The exported metadata fields as yt-dlp JSON, so they show up where the app
+/// reads a post's stored metadata.
+fn raw_json(p: &PostRecord) -> Option<String> {
+    let json = serde_json::json!({
+        "id": p.external_id,
+        "title": p.title.clone().unwrap_or_default(),
+        "description": p.description,
+        "duration": p.duration,
+        "view_count": p.view_count,
+        "like_count": p.like_count,
+        "webpage_url": p.original_url,
+    });
+    let video: YtDlpVideo = serde_json::from_value(json).ok()?;
+    serde_json::to_string(&video).ok()
+}
+
+fn to_csv<T: Serialize>(rows: &[T]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer
+            .serialize(row)
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+async fn from_csv<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    csv::Reader::from_reader(bytes.as_slice())
+        .deserialize()
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(|e| format!("Invalid CSV {}: {}", path.display(), e))
+}
+
+/// Write `contents` next to `path` and rename it into place.
+async fn write_file(path: &Path, contents: Vec<u8>) -> Result<(), String> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".tmp");
+    tokio::fs::write(&partial, contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    tokio::fs::rename(&partial, path)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::metadata::models::YtDlpOutput;
+    use crate::metadata::store::save_metadata;
+
+    fn video(id: &str) -> YtDlpOutput {
+        let json = serde_json::json!({
+            "_type": "video",
+            "id": id,
+            "title": format!("Title, \"{}\"", id),
+            "uploader": "Channel",
+            "uploader_id": "channel",
+            "duration": 61.5,
+            "view_count": 1234,
+            "webpage_url": format!("https://www.youtube.com/watch?v={}", id),
+        });
+        YtDlpOutput::Video(serde_json::from_value::<YtDlpVideo>(json).unwrap())
+    }
+
+    async fn add_media(db: &DatabaseConnection, post_id: i64, dir: &str, name: &str) {
+        let folder_id = ensure_folder(db, Path::new(dir)).await.unwrap();
+        media::Entity::insert(media::ActiveModel {
+            post_id: Set(post_id),
+            media_type: Set("VIDEO".to_string()),
+            file_path: Set(name.to_string()),
+            folder_id: Set(folder_id),
+            order_index: Set(0),
+            checksum: Set(Some("abc123".to_string())),
+            status: Set(MEDIA_AVAILABLE.to_string()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_catalog_round_trip() {
+        let dir = std::env::temp_dir().join(format!("vdp-catalog-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let source = db::init_test_db().await.expect("Failed to init test db");
+        let first = save_metadata(&source, video("first000001"), None, None, None, None)
+            .await
+            .unwrap();
+        save_metadata(&source, video("second00001"), None, None, None, None)
+            .await
+            .unwrap();
+        add_media(&source, first, "youtube/Channel", "first.mp4").await;
+
+        for format in [CatalogFormat::Json, CatalogFormat::Csv] {
+            let path = dir.join(format!("{:?}", format));
+            let counts = export_catalog(&source, &path, format).await.unwrap();
+            assert_eq!((counts.creators, counts.posts, counts.media), (1, 2, 1));
+
+            let exported = collect(&source).await.unwrap();
+            let read = read_catalog(&path, format).await.unwrap();
+            assert_eq!(read.posts, exported.posts);
+            assert_eq!(read.media, exported.media);
+            assert_eq!(read.media[0].path, "youtube/Channel/first.mp4");
+            assert_eq!(read.posts[0].view_count, Some(1234));
+
+            // Into a library that already has one of the posts.
+            let target = db::init_test_db().await.expect("Failed to init test db");
+            save_metadata(&target, video("second00001"), None, None, None, None)
+                .await
+                .unwrap();
+            let report = import_catalog(&target, &path, format).await.unwrap();
+            assert_eq!(report.added.posts, 1);
+            assert_eq!(report.existing.posts, 1);
+            assert_eq!(report.existing.creators, 1);
+            assert_eq!(report.added.media, 1);
+
+            // Importing again adds nothing.
+            let again = import_catalog(&target, &path, format).await.unwrap();
+            assert_eq!(again.added, CatalogCounts::default());
+
+            let imported = collect(&target).await.unwrap();
+            assert_eq!(imported.posts, exported.posts);
+            assert_eq!(imported.media, exported.media);
+            let status = media::Entity::find()
+                .one(&target)
+                .await
+                .unwrap()
+                .unwrap()
+                .status;
+            assert_eq!(status, MEDIA_MISSING);
+        }
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[test]
+    fn test_relative_media_path_stays_in_root() {
+        let root = std::env::temp_dir().join("vdp-root");
+        assert_eq!(
+            relative_media_path("youtube/Channel/a.mp4", &root).unwrap(),
+            "youtube/Channel/a.mp4"
+        );
+        let inside = root.join("youtube").join("a.mp4");
+        assert_eq!(
+            relative_media_path(&inside.to_string_lossy(), &root).unwrap(),
+            "youtube/a.mp4"
+        );
+        let outside = std::env::temp_dir().join("elsewhere").join("a.mp4");
+        assert!(relative_media_path(&outside.to_string_lossy(), &root).is_err());
+        assert!(relative_media_path("../a.mp4", &root).is_err());
+        assert!(relative_media_path("youtube/../../a.mp4", &root).is_err());
+        assert!(relative_media_path("", &root).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! Where library files live. A media row stores a file name and the folder
+//! holding it, and its generated assets by name next to that file; absolute
+//! paths are computed here at runtime from the current download folder, so
+//! changing or moving it never invalidates the rows.
+
+use sea_orm::{
+    sea_query::Condition, ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::download::template::guard_path;
+use crate::entity::{folder, media};
+
+/// The download folder: the `download_path` setting (`~` expands to the home
+/// directory), else the OS download folder.
+pub async fn download_root(db: &impl ConnectionTrait) -> PathBuf {
+    match crate::db::get_setting(db, "download_path").await {
+        Some(p) if p.starts_with("~/") || p.starts_with("~\\") => match dirs::home_dir() {
+            Some(home) => home.join(&p[2..]),
+            None => PathBuf::from(p),
+        },
+        Some(p) => PathBuf::from(p),
+        None => dirs::download_dir().unwrap_or(PathBuf::from("downloads")),
+    }
+}
+
+/// The download folder and every folder below it, loaded once to resolve many
+/// media paths.
+#[derive(Debug, Clone)]
+pub struct Library {
+    root: PathBuf,
+    folders: HashMap<i64, String>,
+}
+
+impl Library {
+    pub async fn load(db: &impl ConnectionTrait) -> Result<Self, DbErr> {
+        let root = download_root(db).await;
+        Self::load_at(db, root).await
+    }
+
+    /// The library as if the download folder were `root`.
+    pub async fn load_at(db: &impl ConnectionTrait, root: PathBuf) -> Result<Self, DbErr> {
+        let folders = folder::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|f| (f.id, f.relative_path))
+            .collect();
+        Ok(Self { root, folders })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The absolute path of a media file: download folder / folder / file name.
+    /// Files recorded outside the download folder keep their absolute path.
+    pub fn resolve_media_path(&self, m: &media::Model) -> Result<PathBuf, String> {
+        self.resolve_in_folder(m, &m.file_path)
+    }
+
+    /// The absolute path of one of a media row's generated files (thumbnail,
+    /// poster, preview, sprites), stored as `name` next to the media file.
+    pub fn resolve_asset_path(&self, m: &media::Model, name: &str) -> Result<PathBuf, String> {
+        self.resolve_in_folder(m, name)
+    }
+
+    fn resolve_in_folder(&self, m: &media::Model, name: &str) -> Result<PathBuf, String> {
+        let file = Path::new(name);
+        if m.folder_id.is_none() && file.is_absolute() {
+            return Ok(file.to_path_buf());
+        }
+
+        let mut relative = PathBuf::new();
+        if let Some(folder_id) = m.folder_id {
+            let folder = self
+                .folders
+                .get(&folder_id)
+                .ok_or_else(|| format!("Media {} is in unknown folder {}", m.id, folder_id))?;
+            relative.extend(folder.split('/'));
+        }
+        if file.components().count() != 1 {
+            return Err(format!("Media {} has an invalid file name: {}", m.id, name));
+        }
+        relative.push(file);
+        guard_path(&self.root, &relative)
+    }
+
+    /// Every file on disk owned by a media row: the file itself plus its derived assets.
+    pub fn asset_paths(&self, m: &media::Model) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.resolve_media_path(m).into_iter().collect();
+        paths.extend(self.derived_asset_paths(m));
+        paths
+    }
+
+    /// The generated assets of a media row: thumbnail, poster, preview and sprites.
+    pub fn derived_asset_paths(&self, m: &media::Model) -> Vec<PathBuf> {
+        [
+            &m.thumbnail_path,
+            &m.poster_path,
+            &m.preview_path,
+            &m.sprite_path,
+            &m.sprite_vtt_path,
+        ]
+        .into_iter()
+        .filter_map(|name| self.resolve_asset_path(m, name.as_deref()?).ok())
+        .collect()
+    }
+}
+
+/// How a generated file at `asset`, next to a media file stored as `file_path`, is
+/// stored: by name, or by absolute path like a media file outside the download folder.
+pub fn asset_name(file_path: &str, asset: &Path) -> String {
+    match asset.file_name() {
+        Some(name) if !Path::new(file_path).is_absolute() => name.to_string_lossy().to_string(),
+        _ => asset.to_string_lossy().to_string(),
+    }
+}
+
+/// After the file of `m` turned up at `path` (now stored as `file_path`), keep the
+/// assets that moved along with it and adopt a thumbnail or poster named after the
+/// new file. Assets left behind are dropped from `row`. Returns the assets it keeps.
+pub fn follow_assets(
+    m: &media::Model,
+    row: &mut media::ActiveModel,
+    path: &Path,
+    file_path: &str,
+) -> Vec<PathBuf> {
+    use crate::download::post_process::{poster_output_path, thumbnail_output_path};
+
+    let dir = path.parent().unwrap_or(path);
+    let mut kept = Vec::new();
+    for (current, column, named_after) in [
+        (
+            &m.thumbnail_path,
+            &mut row.thumbnail_path,
+            Some(thumbnail_output_path(path)),
+        ),
+        (
+            &m.poster_path,
+            &mut row.poster_path,
+            Some(poster_output_path(path)),
+        ),
+        (&m.preview_path, &mut row.preview_path, None),
+        (&m.sprite_path, &mut row.sprite_path, None),
+        (&m.sprite_vtt_path, &mut row.sprite_vtt_path, None),
+    ] {
+        let moved_along = current
+            .as_deref()
+            .and_then(|name| Path::new(name).file_name())
+            .map(|name| dir.join(name));
+        let found = moved_along
+            .into_iter()
+            .chain(named_after)
+            .find(|p| p.exists());
+        let name = found.as_deref().map(|p| asset_name(file_path, p));
+        if name != *current {
+            *column = Set(name);
+        }
+        kept.extend(found);
+    }
+    kept
+}
+
+/// The folder row for `relative_dir` (below the download folder), creating it
+/// and its parents as needed. The download folder itself is None.
+pub async fn ensure_folder(
+    db: &impl ConnectionTrait,
+    relative_dir: &Path,
+) -> Result<Option<i64>, DbErr> {
+    let mut parent_id = None;
+    let mut relative_path = String::new();
+    for component in relative_dir.components() {
+        let name = match component {
+            Component::Normal(name) => name.to_string_lossy().to_string(),
+            Component::CurDir => continue,
+            _ => {
+                return Err(DbErr::Custom(format!(
+                    "Folder escapes the download folder: {}",
+                    relative_dir.display()
+                )))
+            }
+        };
+        if !relative_path.is_empty() {
+            relative_path.push('/');
+        }
+        relative_path.push_str(&name);
+
+        let id = match find_folder(db, &relative_path).await? {
+            Some(id) => id,
+            None => {
+                let now = chrono::Utc::now();
+                let inserted = folder::ActiveModel {
+                    name: Set(name),
+                    relative_path: Set(relative_path.clone()),
+                    parent_id: Set(parent_id),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await;
+                match inserted {
+                    Ok(f) => f.id,
+                    // Another download created it first.
+                    Err(e) => find_folder(db, &relative_path).await?.ok_or(e)?,
+                }
+            }
+        };
+        parent_id = Some(id);
+    }
+    Ok(parent_id)
+}
+
+async fn find_folder(db: &impl ConnectionTrait, relative_path: &str) -> Result<Option<i64>, DbErr> {
+    Ok(folder::Entity::find()
+        .filter(folder::Column::RelativePath.eq(relative_path))
+        .one(db)
+        .await?
+        .map(|f| f.id))
+}
+
+/// How a file at the absolute `path` is stored on a media row: its folder and
+/// file name, or no folder and the absolute path if it is outside `root`.
+pub async fn locate(
+    db: &impl ConnectionTrait,
+    root: &Path,
+    path: &Path,
+) -> Result<(Option<i64>, String), DbErr> {
+    match path.strip_prefix(root) {
+        Ok(relative) if relative.file_name().is_some() => {
+            let folder_id = match relative.parent() {
+                Some(dir) => ensure_folder(db, dir).await?,
+                None => None,
+            };
+            let name = relative.file_name().unwrap_or_default();
+            Ok((folder_id, name.to_string_lossy().to_string()))
+        }
+        _ => Ok((None, path.to_string_lossy().to_string())),
+    }
+}
+
+/// Matches the media rows stored at a [`locate`] result.
+pub fn location_filter(folder_id: Option<i64>, file_path: &str) -> Condition {
+    let folder = match folder_id {
+        Some(id) => media::Column::FolderId.eq(id),
+        None => media::Column::FolderId.is_null(),
+    };
+    Condition::all()
+        .add(folder)
+        .add(media::Column::FilePath.eq(file_path))
+}
+
+/// Refuse a directory that resolves outside `root` once symlinks are followed.
+/// Both must exist.
+pub fn ensure_within(root: &Path, dir: &Path) -> Result<(), String> {
+    let canonical_root = std::fs::canonicalize(root)
+        .map_err(|e| format!("Failed to resolve {}: {}", root.display(), e))?;
+    let canonical_dir = std::fs::canonicalize(dir)
+        .map_err(|e| format!("Failed to resolve {}: {}", dir.display(), e))?;
+    if canonical_dir.starts_with(&canonical_root) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Output path escapes the download folder: {}",
+            canonical_dir.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    #[tokio::test]
+    async fn test_folders_and_media_paths() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let root = std::env::temp_dir().join("vdp-folders");
+
+        let clip = root.join("youtube").join("Creator").join("clip.mp4");
+        let (folder_id, name) = locate(&db, &root, &clip).await.unwrap();
+        assert_eq!(name, "clip.mp4");
+        // The same folder is reused, nested under its parent.
+        assert_eq!(
+            ensure_folder(&db, Path::new("youtube/Creator"))
+                .await
+                .unwrap(),
+            folder_id
+        );
+        let nested = folder::Entity::find_by_id(folder_id.unwrap())
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(nested.relative_path, "youtube/Creator");
+        assert_eq!(
+            nested.parent_id,
+            ensure_folder(&db, Path::new("youtube")).await.unwrap()
+        );
+        assert_eq!(ensure_folder(&db, Path::new("")).await.unwrap(), None);
+        assert!(ensure_folder(&db, Path::new("../up")).await.is_err());
+
+        let outside = std::env::temp_dir().join("elsewhere.mp4");
+        assert_eq!(
+            locate(&db, &root, &outside).await.unwrap(),
+            (None, outside.to_string_lossy().to_string())
+        );
+
+        let library = Library {
+            root: root.clone(),
+            folders: folder::Entity::find()
+                .all(&db)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|f| (f.id, f.relative_path))
+                .collect(),
+        };
+        let mut m = media::Model {
+            id: 1,
+            post_id: 1,
+            media_type: "VIDEO".to_string(),
+            file_path: name,
+            folder_id,
+            thumbnail_path: None,
+            order_index: 0,
+            width: None,
+            height: None,
+            duration: None,
+            file_size: None,
+            format_id: None,
+            checksum: None,
+            deleted_at: None,
+            preview_path: None,
+            sprite_path: None,
+            sprite_vtt_path: None,
+            poster_path: None,
+            language: None,
+            loudness_lufs: None,
+            status: "AVAILABLE".to_string(),
+        };
+        assert_eq!(library.resolve_media_path(&m).unwrap(), clip);
+
+        m.thumbnail_path = Some("clip.thumb_sm.jpg".to_string());
+        assert_eq!(
+            library.derived_asset_paths(&m),
+            [clip.with_file_name("clip.thumb_sm.jpg")]
+        );
+
+        m.file_path = outside.to_string_lossy().to_string();
+        m.folder_id = None;
+        assert_eq!(library.resolve_media_path(&m).unwrap(), outside);
+        let thumbnail = outside.with_file_name("elsewhere.thumb_sm.jpg");
+        m.thumbnail_path = Some(asset_name(&m.file_path, &thumbnail));
+        assert_eq!(library.derived_asset_paths(&m), [thumbnail]);
+
+        m.file_path = "../../etc/passwd".to_string();
+        assert!(library.resolve_media_path(&m).is_err());
+        assert!(library.resolve_asset_path(&m, "../x.jpg").is_err());
+    }
+}
@@ -0,0 +1,490 @@
+//! Importing media files downloaded outside the app, such as a folder of plain
+//! yt-dlp downloads. Each file is identified from the `.info.json` written next
+//! to it or the `[id]` yt-dlp puts in file names, saved as a post through
+//! `metadata::store`, and moved or copied into the library layout. Files that
+//! can't be identified become local posts.
+
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    Set,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::reconcile::{is_derived_asset, sha256_file, walk};
+use super::relocate::RelocateMode;
+use crate::download::template::{self, TemplateContext};
+use crate::entity::{media, post};
+use crate::metadata::models::{YtDlpOutput, YtDlpVideo};
+use crate::queue::DownloadQueue;
+
+/// Extensions of the files an import picks up.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "webm", "mkv", "avi", "mov", "flv", "mp3", "m4a", "wav", "aac", "ogg", "opus", "jpg",
+    "jpeg", "png", "gif", "webp", "bmp",
+];
+
+/// Shortest and longest text between brackets taken for a platform ID.
+const ID_LENGTH: std::ops::RangeInclusive<usize> = 5..=64;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub files_done: usize,
+    pub files_total: usize,
+    /// Path of the file being imported.
+    pub current: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    /// Files matched to a post on their platform.
+    pub identified: usize,
+    /// Files imported as local posts.
+    pub local: usize,
+    /// Files already in the library, or whose post already has media.
+    pub skipped: usize,
+    /// `path: error` for each file that couldn't be imported.
+    pub failed: Vec<String>,
+}
+
+/// Import every media file below `source` into the library. With an app handle,
+/// files known only by ID get their metadata fetched (if `refetch` is set) and
+/// imported media get thumbnails.
+pub async fn import(
+    db: &DatabaseConnection,
+    app: Option<&AppHandle>,
+    source: &Path,
+    mode: RelocateMode,
+    refetch: bool,
+    on_progress: impl Fn(ImportProgress),
+) -> Result<ImportReport, String> {
+    if !source.is_dir() {
+        return Err(format!("Not a folder: {}", source.display()));
+    }
+    let root = super::folders::download_root(db).await;
+    tokio::fs::create_dir_all(&root)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
+
+    let mut report = ImportReport::default();
+    let tracked = tracked_files(db).await?;
+    let mut files: Vec<PathBuf> = walk(source).await.into_iter().map(|(p, _)| p).collect();
+    let all = files.clone();
+    files.retain(|p| is_importable(p, &all));
+    files.retain(|p| {
+        let known = tracked.contains(p);
+        report.skipped += known as usize;
+        !known
+    });
+    files.sort();
+
+    let ffmpeg = app.and_then(|app| {
+        crate::sidecar::get_binary_path(app, crate::sidecar::types::SidecarBinary::Ffmpeg).ok()
+    });
+    for (i, file) in files.iter().enumerate() {
+        on_progress(ImportProgress {
+            files_done: i,
+            files_total: files.len(),
+            current: Some(file.to_string_lossy().to_string()),
+        });
+        let imported = import_file(db, app, &root, file, mode, refetch, ffmpeg.as_deref()).await;
+        match imported {
+            Ok(Imported::Identified) => report.identified += 1,
+            Ok(Imported::Local) => report.local += 1,
+            Ok(Imported::Skipped) => report.skipped += 1,
+            Err(e) => {
+                tracing::warn!("Failed to import {}: {}", file.display(), e);
+                report.failed.push(format!("{}: {}", file.display(), e));
+            }
+        }
+    }
+    on_progress(ImportProgress {
+        files_done: files.len(),
+        files_total: files.len(),
+        current: None,
+    });
+
+    if report.identified + report.local > 0 {
+        super::archive::export_configured(db).await;
+    }
+    tracing::info!("Imported {}: {:?}", source.display(), report);
+    Ok(report)
+}
+
+/// Files the library already has a media row for.
+async fn tracked_files(db: &DatabaseConnection) -> Result<HashSet<PathBuf>, String> {
+    let library = super::Library::load(db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+    let medias = media::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error loading media: {}", e))?;
+    Ok(medias
+        .iter()
+        .filter_map(|m| library.resolve_media_path(m).ok())
+        .collect())
+}
+
+enum Imported {
+    Identified,
+    Local,
+    Skipped,
+}
+
+/// A media file, not an intermediate, generated asset, or the thumbnail yt-dlp
+/// wrote next to a video of the same name.
+fn is_importable(path: &Path, all: &[PathBuf]) -> bool {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !MEDIA_EXTENSIONS.contains(&ext.as_str())
+        || is_derived_asset(path)
+        || super::watcher::is_transient(path)
+    {
+        return false;
+    }
+    if DownloadQueue::media_type_for(path) != "IMAGE" {
+        return true;
+    }
+    !all.iter().any(|other| {
+        other != path
+            && other.parent() == path.parent()
+            && other.file_stem() == path.file_stem()
+            && DownloadQueue::media_type_for(other) != "IMAGE"
+    })
+}
+
+/// The ID yt-dlp's default `%(title)s [%(id)s]` names end with.
+pub fn extract_id(stem: &str) -> Option<&str> {
+    let inner = stem.trim_end().strip_suffix(']')?;
+    let id = &inner[inner.rfind('[')? + 1..];
+    let valid = id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    (valid && ID_LENGTH.contains(&id.len())).then_some(id)
+}
+
+/// The file's title: its name without the trailing `[id]`.
+fn title_of(stem: &str) -> String {
+    let title = match extract_id(stem) {
+        Some(id) => stem
+            .trim_end()
+            .strip_suffix(&format!("[{}]", id))
+            .unwrap_or(stem),
+        None => stem,
+    };
+    match title.trim() {
+        "" => stem.to_string(),
+        title => title.to_string(),
+    }
+}
+
+/// The metadata yt-dlp wrote next to `file` with `--write-info-json`.
+async fn read_info_json(file: &Path) -> Option<YtDlpVideo> {
+    let stem = file.file_stem()?.to_string_lossy();
+    let info = file.with_file_name(format!("{}.info.json", stem));
+    let json = tokio::fs::read_to_string(info).await.ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Whether the video's creator can be filed under a platform the library knows,
+/// judged from the URL `metadata::store` takes it from.
+fn has_platform(v: &YtDlpVideo) -> bool {
+    v.webpage_url
+        .as_deref()
+        .or(v.url.as_deref())
+        .or(v.uploader_url.as_deref())
+        .or(v.channel_url.as_deref())
+        .and_then(crate::platform::detect_platform)
+        .is_some()
+}
+
+async fn identify(
+    db: &DatabaseConnection,
+    app: Option<&AppHandle>,
+    file: &Path,
+    refetch: bool,
+) -> Result<(i64, Imported), String> {
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut video = read_info_json(file).await.filter(has_platform);
+    if video.is_none() && refetch {
+        if let (Some(app), Some(id)) = (app, extract_id(&stem)) {
+            // yt-dlp's default names come from YouTube downloads.
+            let url = format!("https://www.youtube.com/watch?v={}", id);
+            match crate::metadata::fetcher::fetch_metadata(app, &url, None, None).await {
+                Ok(YtDlpOutput::Video(v)) | Ok(YtDlpOutput::VideoFallback(v)) => video = Some(v),
+                Ok(_) => {}
+                Err(e) => tracing::info!("No metadata for {} ({}): {}", stem, url, e),
+            }
+        }
+    }
+
+    if let Some(v) = video {
+        let post_id = crate::metadata::store::save_metadata(
+            db,
+            YtDlpOutput::Video(v),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| format!("Database error saving post: {}", e))?;
+        return Ok((post_id, Imported::Identified));
+    }
+
+    let checksum = sha256_file(file)
+        .await
+        .ok_or_else(|| "Failed to read file".to_string())?;
+    let posted_at = tokio::fs::metadata(file)
+        .await
+        .and_then(|m| m.modified())
+        .ok()
+        .map(chrono::DateTime::<chrono::Utc>::from);
+    let post_id = crate::metadata::store::save_local_post(
+        db,
+        &format!("local-{}", &checksum[..16]),
+        &title_of(&stem),
+        &format!("file://{}", file.to_string_lossy()),
+        posted_at,
+    )
+    .await
+    .map_err(|e| format!("Database error saving post: {}", e))?;
+    Ok((post_id, Imported::Local))
+}
+
+async fn import_file(
+    db: &DatabaseConnection,
+    app: Option<&AppHandle>,
+    root: &Path,
+    file: &Path,
+    mode: RelocateMode,
+    refetch: bool,
+    ffmpeg: Option<&Path>,
+) -> Result<Imported, String> {
+    let (post_id, imported) = identify(db, app, file, refetch).await?;
+    let existing = media::Entity::find()
+        .filter(media::Column::PostId.eq(post_id))
+        .count(db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    if existing > 0 {
+        return Ok(Imported::Skipped);
+    }
+
+    let target = target_path(db, root, post_id, file).await?;
+    if target != file {
+        transfer(file, &target, mode).await?;
+    }
+
+    let (folder_id, file_path) = super::folders::locate(db, root, &target)
+        .await
+        .map_err(|e| format!("Database error recording folder: {}", e))?;
+    let file_size = tokio::fs::metadata(&target)
+        .await
+        .ok()
+        .map(|m| m.len() as i32);
+    let inserted = media::ActiveModel {
+        post_id: Set(post_id),
+        media_type: Set(DownloadQueue::media_type_for(&target).to_string()),
+        file_path: Set(file_path),
+        folder_id: Set(folder_id),
+        order_index: Set(0),
+        file_size: Set(file_size),
+        checksum: Set(sha256_file(&target).await),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .map_err(|e| format!("Database error saving media: {}", e))?;
+    post::Entity::update(post::ActiveModel {
+        id: Set(post_id),
+        status: Set("COMPLETED".to_string()),
+        downloaded_at: Set(Some(chrono::Utc::now())),
+        ..Default::default()
+    })
+    .exec(db)
+    .await
+    .map_err(|e| format!("Database error updating post: {}", e))?;
+
+    if let Some(ffmpeg) = ffmpeg {
+        if let Err(e) = DownloadQueue::refresh_thumbnails(db, ffmpeg, &inserted, &target).await {
+            tracing::warn!(
+                "Failed to record thumbnails for {}: {}",
+                target.display(),
+                e
+            );
+        }
+    }
+    Ok(imported)
+}
+
+/// Where the post's file goes in the library, following its filename template.
+/// An existing different file of the same name gets a ` (n)` suffix.
+async fn target_path(
+    db: &DatabaseConnection,
+    root: &Path,
+    post_id: i64,
+    file: &Path,
+) -> Result<PathBuf, String> {
+    let template = template::template_for_post(db, post_id).await;
+    let ctx = TemplateContext::for_post(db, post_id)
+        .await
+        .unwrap_or_default();
+    let output = template::render(&template, &ctx)
+        .or_else(|_| template::render(template::DEFAULT_TEMPLATE, &ctx))?;
+
+    let dir = template::guard_path(root, &output.dir)?;
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    super::folders::ensure_within(root, &dir)?;
+
+    let ext = file
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut target = dir.join(format!("{}{}", output.stem, ext));
+    let mut n = 1;
+    while target != file && tokio::fs::try_exists(&target).await.unwrap_or(false) {
+        target = dir.join(format!("{} ({}){}", output.stem, n, ext));
+        n += 1;
+    }
+    Ok(target)
+}
+
+/// Move or copy `from` to `to`. A move across file systems is a copy and delete.
+async fn transfer(from: &Path, to: &Path, mode: RelocateMode) -> Result<(), String> {
+    let failed = |e: std::io::Error| format!("Failed to copy to {}: {}", to.display(), e);
+    match mode {
+        RelocateMode::Copy => tokio::fs::copy(from, to).await.map(|_| ()).map_err(failed),
+        RelocateMode::Move => {
+            if tokio::fs::rename(from, to).await.is_ok() {
+                return Ok(());
+            }
+            tokio::fs::copy(from, to).await.map_err(failed)?;
+            tokio::fs::remove_file(from)
+                .await
+                .map_err(|e| format!("Failed to remove {}: {}", from.display(), e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::entity::{creator, setting};
+
+    #[test]
+    fn test_extract_id() {
+        assert_eq!(extract_id("Never Gonna [dQw4w9WgXcQ]"), Some("dQw4w9WgXcQ"));
+        assert_eq!(
+            extract_id("clip [7312345678901234567]"),
+            Some("7312345678901234567")
+        );
+        assert_eq!(extract_id("Song [Live]"), None);
+        assert_eq!(extract_id("Song [HD 1080p]"), None);
+        assert_eq!(extract_id("[ab]"), None);
+        assert_eq!(extract_id("holiday"), None);
+        assert_eq!(title_of("Never Gonna [dQw4w9WgXcQ]"), "Never Gonna");
+        assert_eq!(title_of("[dQw4w9WgXcQ]"), "[dQw4w9WgXcQ]");
+    }
+
+    #[tokio::test]
+    async fn test_import_identifies_and_copies_files() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let base = std::env::temp_dir().join(format!("vdp-import-{}", uuid::Uuid::new_v4()));
+        let root = base.join("library");
+        let source = base.join("old");
+        setting::Entity::update(setting::ActiveModel {
+            key: Set("download_path".to_string()),
+            value: Set(root.to_string_lossy().to_string()),
+            updated_at: Set(chrono::Utc::now()),
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+
+        tokio::fs::create_dir_all(&source).await.unwrap();
+        let known = source.join("Song [dQw4w9WgXcQ].mp4");
+        let info = serde_json::json!({
+            "_type": "video",
+            "id": "dQw4w9WgXcQ",
+            "title": "Song",
+            "uploader": "Singer",
+            "uploader_id": "@singer",
+            "webpage_url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+        });
+        tokio::fs::write(&known, b"video").await.unwrap();
+        tokio::fs::write(
+            source.join("Song [dQw4w9WgXcQ].info.json"),
+            info.to_string(),
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(source.join("Song [dQw4w9WgXcQ].jpg"), b"thumbnail")
+            .await
+            .unwrap();
+        let unknown = source.join("holiday.mp4");
+        tokio::fs::write(&unknown, b"holiday").await.unwrap();
+        tokio::fs::write(source.join("partial.mp4.part"), b"par")
+            .await
+            .unwrap();
+
+        let report = import(&db, None, &source, RelocateMode::Copy, false, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            ImportReport {
+                identified: 1,
+                local: 1,
+                ..Default::default()
+            }
+        );
+        assert!(known.exists() && unknown.exists());
+
+        let library = super::super::Library::load(&db).await.unwrap();
+        let medias = media::Entity::find().all(&db).await.unwrap();
+        let mut paths: Vec<PathBuf> = medias
+            .iter()
+            .map(|m| library.resolve_media_path(m).unwrap())
+            .collect();
+        paths.sort();
+        assert_eq!(paths, [root.join("Song.mp4"), root.join("holiday.mp4")]);
+
+        let posts = post::Entity::find().all(&db).await.unwrap();
+        assert!(posts.iter().all(|p| p.status == "COMPLETED"));
+        let local = posts
+            .iter()
+            .find(|p| p.external_id.starts_with("local-"))
+            .unwrap();
+        let local_creator = creator::Entity::find_by_id(local.creator_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            local_creator.platform_id,
+            crate::constants::platform_id::LOCAL
+        );
+
+        // Importing again finds nothing new.
+        let report = import(&db, None, &source, RelocateMode::Copy, false, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(report.skipped, 2);
+        assert_eq!(media::Entity::find().count(&db).await.unwrap(), 2);
+
+        let _ = tokio::fs::remove_dir_all(&base).await;
+    }
+}
@@ -0,0 +1,42 @@
+pub mod archive;
+pub mod catalog;
+pub mod folders;
+pub mod import;
+pub mod reconcile;
+pub mod relocate;
+pub mod watcher;
+
+pub use folders::Library;
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::{creator, post};
+    use sea_orm::{ActiveModelTrait, DatabaseConnection, Set};
+    use std::path::Path;
+
+    /// Point the download folder at `root` and add a post to attach media to.
+    pub(super) async fn seed_post(db: &DatabaseConnection, root: &Path) -> post::Model {
+        crate::db::set_setting(db, "download_path", &root.to_string_lossy())
+            .await
+            .unwrap();
+        let creator = creator::ActiveModel {
+            platform_id: Set("youtube".to_string()),
+            name: Set("Creator".to_string()),
+            url: Set("https://example.com".to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        post::ActiveModel {
+            creator_id: Set(creator.id),
+            external_id: Set("p1".to_string()),
+            original_url: Set("https://example.com/p1".to_string()),
+            status: Set("COMPLETED".to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+}
@@ -0,0 +1,417 @@
+//! Reconciling the library with the file system. Files deleted or moved outside
+//! the app leave media rows pointing nowhere: moved files are found again under
+//! the download folder by size and checksum (or name), the rest are marked
+//! MISSING so the UI can offer to download them again.
+
+use sea_orm::{ActiveModelTrait, ConnectionTrait, DatabaseConnection, EntityTrait, Set};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+
+use super::Library;
+use crate::entity::media;
+
+pub const MEDIA_AVAILABLE: &str = "AVAILABLE";
+pub const MEDIA_MISSING: &str = "MISSING";
+
+/// File name endings of the assets generated next to a media file: Wall
+/// thumbnail, platform poster, hover preview and scrub sprites.
+const DERIVED_ASSET_SUFFIXES: &[&str] = &[
+    ".thumb_sm.jpg",
+    ".poster.jpg",
+    ".preview.webp",
+    ".sprite.jpg",
+    ".sprite.vtt",
+];
+
+/// Media types kept alongside a download rather than being its content.
+const COMPANION_MEDIA_TYPES: &[&str] = &["SUBTITLE", "METADATA"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReconcileReport {
+    pub checked: usize,
+    /// Rows whose file is gone.
+    pub missing: usize,
+    /// Rows whose file was found elsewhere under the download folder.
+    pub relocated: usize,
+    /// Rows marked MISSING before whose file is back.
+    pub restored: usize,
+    /// Posts whose media is missing, for re-downloading.
+    pub missing_post_ids: Vec<i64>,
+    /// Generated thumbnails and previews no media row refers to.
+    pub orphan_assets: Vec<String>,
+}
+
+/// A file found under the download folder that no available row claims.
+struct Candidate {
+    path: PathBuf,
+    size: u64,
+    sha256: Option<String>,
+}
+
+/// Check every media row against the file system and record the result.
+pub async fn reconcile(db: &DatabaseConnection) -> Result<ReconcileReport, String> {
+    let library = Library::load(db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+    // An unmounted drive would make every file look deleted.
+    if !tokio::fs::metadata(library.root())
+        .await
+        .is_ok_and(|m| m.is_dir())
+    {
+        return Err(format!(
+            "Download folder {} is not available",
+            library.root().display()
+        ));
+    }
+    let medias = media::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error loading media: {}", e))?;
+
+    let mut report = ReconcileReport {
+        checked: medias.len(),
+        ..Default::default()
+    };
+    let mut claimed = HashSet::new();
+    let mut lost = Vec::new();
+    for m in medias.iter() {
+        match library.resolve_media_path(m) {
+            Ok(path) if tokio::fs::try_exists(&path).await.unwrap_or(false) => {
+                if m.status == MEDIA_MISSING {
+                    set_status(db, m, MEDIA_AVAILABLE).await?;
+                    report.restored += 1;
+                }
+                claimed.insert(path);
+            }
+            _ => lost.push(m),
+        }
+    }
+
+    let referenced: HashSet<PathBuf> = medias
+        .iter()
+        .flat_map(|m| library.derived_asset_paths(m))
+        .collect();
+    let mut candidates = Vec::new();
+    let mut orphans = BTreeSet::new();
+    for (path, size) in walk(library.root()).await {
+        if is_derived_asset(&path) {
+            if !referenced.contains(&path) {
+                orphans.insert(path.to_string_lossy().to_string());
+            }
+        } else if !claimed.contains(&path) {
+            candidates.push(Candidate {
+                path,
+                size,
+                sha256: None,
+            });
+        }
+    }
+
+    let mut missing_posts = BTreeSet::new();
+    for m in lost {
+        match find_moved(m, &mut candidates).await {
+            Some(path) => {
+                for adopted in relocate_row(db, &library, m, &path).await? {
+                    orphans.remove(adopted.to_string_lossy().as_ref());
+                }
+                report.relocated += 1;
+            }
+            None => {
+                if m.status != MEDIA_MISSING {
+                    set_status(db, m, MEDIA_MISSING).await?;
+                }
+                report.missing += 1;
+                if !COMPANION_MEDIA_TYPES.contains(&m.media_type.as_str()) {
+                    missing_posts.insert(m.post_id);
+                }
+            }
+        }
+    }
+    report.missing_post_ids = missing_posts.into_iter().collect();
+    report.orphan_assets = orphans.into_iter().collect();
+
+    tracing::info!(
+        "Library scan: {} checked, {} missing, {} relocated, {} restored, {} orphan assets",
+        report.checked,
+        report.missing,
+        report.relocated,
+        report.restored,
+        report.orphan_assets.len()
+    );
+    Ok(report)
+}
+
+/// Take the candidate that is `m`'s file: same size and checksum when a checksum
+/// was recorded, otherwise the only file with the same name (and size, if known).
+async fn find_moved(m: &media::Model, candidates: &mut Vec<Candidate>) -> Option<PathBuf> {
+    // `file_size` is stored truncated to 32 bits; compare it the same way.
+    let size_matches = |c: &Candidate| m.file_size.is_none_or(|s| c.size as i32 == s);
+
+    let index = match m.checksum.as_deref() {
+        Some(expected) => {
+            let mut found = None;
+            for (i, c) in candidates.iter_mut().enumerate() {
+                if !size_matches(c) {
+                    continue;
+                }
+                if c.sha256.is_none() {
+                    c.sha256 = sha256_file(&c.path).await;
+                }
+                if c.sha256
+                    .as_deref()
+                    .is_some_and(|h| h.eq_ignore_ascii_case(expected))
+                {
+                    found = Some(i);
+                    break;
+                }
+            }
+            found?
+        }
+        None => {
+            let name = Path::new(&m.file_path).file_name()?;
+            let mut matches = candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.path.file_name() == Some(name) && size_matches(c));
+            let (i, _) = matches.next()?;
+            if matches.next().is_some() {
+                return None;
+            }
+            i
+        }
+    };
+    Some(candidates.swap_remove(index).path)
+}
+
+/// Point a row at the file found at `path`, with the assets that moved along with
+/// it (see [`super::folders::follow_assets`]). Returns those assets.
+async fn relocate_row(
+    db: &DatabaseConnection,
+    library: &Library,
+    m: &media::Model,
+    path: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    let (folder_id, file_path) = super::folders::locate(db, library.root(), path)
+        .await
+        .map_err(|e| format!("Database error recording folder: {}", e))?;
+    let mut row: media::ActiveModel = m.clone().into();
+    let adopted = super::folders::follow_assets(m, &mut row, path, &file_path);
+    row.file_path = Set(file_path);
+    row.folder_id = Set(folder_id);
+    row.status = Set(MEDIA_AVAILABLE.to_string());
+
+    row.update(db)
+        .await
+        .map_err(|e| format!("Database error updating media {}: {}", m.id, e))?;
+    tracing::info!("Media {} found at {}", m.id, path.display());
+    Ok(adopted)
+}
+
+pub(super) async fn set_status(
+    db: &impl ConnectionTrait,
+    m: &media::Model,
+    status: &str,
+) -> Result<(), String> {
+    media::Entity::update(media::ActiveModel {
+        id: Set(m.id),
+        status: Set(status.to_string()),
+        ..Default::default()
+    })
+    .exec(db)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("Database error updating media {}: {}", m.id, e))
+}
+
+pub(super) fn is_derived_asset(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    DERIVED_ASSET_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+/// Every file under `root` with its size. Unreadable directories are skipped.
+pub(super) async fn walk(root: &Path) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(file_type) = entry.file_type().await else {
+                continue;
+            };
+            if file_type.is_dir() {
+                dirs.push(entry.path());
+            } else if file_type.is_file() {
+                if let Ok(meta) = entry.metadata().await {
+                    files.push((entry.path(), meta.len()));
+                }
+            }
+        }
+    }
+    files
+}
+
+pub(super) async fn sha256_file(path: &Path) -> Option<String> {
+    let mut file = tokio::fs::File::open(path).await.ok()?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 256 * 1024];
+    loop {
+        let n = file.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return Some(format!("{:x}", hasher.finalize()));
+        }
+        hasher.update(&buf[..n]);
+    }
+}
+
+/// Posts with media marked MISSING, grouped with those rows.
+pub async fn missing_media_by_post(
+    db: &impl ConnectionTrait,
+) -> Result<HashMap<i64, Vec<media::Model>>, String> {
+    use sea_orm::{ColumnTrait, QueryFilter};
+
+    let rows = media::Entity::find()
+        .filter(media::Column::Status.eq(MEDIA_MISSING))
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error loading missing media: {}", e))?;
+    let mut by_post: HashMap<i64, Vec<media::Model>> = HashMap::new();
+    for m in rows {
+        by_post.entry(m.post_id).or_default().push(m);
+    }
+    Ok(by_post)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::entity::post;
+
+    async fn insert_media(
+        db: &DatabaseConnection,
+        post_id: i64,
+        root: &Path,
+        path: &Path,
+        checksum: Option<String>,
+    ) -> media::Model {
+        let (folder_id, file_path) = super::super::folders::locate(db, root, path).await.unwrap();
+        media::ActiveModel {
+            post_id: Set(post_id),
+            media_type: Set("VIDEO".to_string()),
+            file_path: Set(file_path),
+            folder_id: Set(folder_id),
+            order_index: Set(0),
+            file_size: Set(Some(tokio::fs::metadata(path).await.unwrap().len() as i32)),
+            checksum: Set(checksum),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+
+    /// A download folder at a fresh temp path, and a post to attach media to.
+    async fn setup_library(db: &DatabaseConnection) -> (PathBuf, post::Model) {
+        let root = std::env::temp_dir().join(format!("vdp-reconcile-{}", uuid::Uuid::new_v4()));
+        let post = crate::library::tests::seed_post(db, &root).await;
+        (root, post)
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_finds_moved_and_missing_files() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let (root, post) = setup_library(&db).await;
+
+        tokio::fs::create_dir_all(root.join("a")).await.unwrap();
+        let kept = root.join("kept.mp4");
+        let hashed = root.join("hashed.mp4");
+        let named = root.join("named.mp4");
+        let gone = root.join("gone.mp4");
+        for (path, data) in [
+            (&kept, &b"kept"[..]),
+            (&hashed, b"hashed"),
+            (&named, b"named"),
+            (&gone, b"gone"),
+        ] {
+            tokio::fs::write(path, data).await.unwrap();
+        }
+        let checksum = format!("{:x}", Sha256::digest(b"hashed"));
+        insert_media(&db, post.id, &root, &kept, None).await;
+        let hashed_row = insert_media(&db, post.id, &root, &hashed, Some(checksum)).await;
+        let named_row = insert_media(&db, post.id, &root, &named, None).await;
+        let gone_row = insert_media(&db, post.id, &root, &gone, None).await;
+
+        // Moved (and renamed) by hand, moved into a subfolder, and deleted.
+        tokio::fs::rename(&hashed, root.join("a").join("renamed.mp4"))
+            .await
+            .unwrap();
+        tokio::fs::rename(&named, root.join("a").join("named.mp4"))
+            .await
+            .unwrap();
+        tokio::fs::remove_file(&gone).await.unwrap();
+        tokio::fs::write(root.join("old.thumb_sm.jpg"), b"jpeg")
+            .await
+            .unwrap();
+
+        let report = reconcile(&db).await.unwrap();
+        assert_eq!(
+            (report.checked, report.relocated, report.missing),
+            (4, 2, 1)
+        );
+        assert_eq!(report.missing_post_ids, vec![post.id]);
+        assert_eq!(report.orphan_assets.len(), 1);
+
+        let library = Library::load(&db).await.unwrap();
+        let find = |id| media::Entity::find_by_id(id).one(&db);
+        let hashed_row = find(hashed_row.id).await.unwrap().unwrap();
+        assert_eq!(
+            library.resolve_media_path(&hashed_row).unwrap(),
+            root.join("a").join("renamed.mp4")
+        );
+        let named_row = find(named_row.id).await.unwrap().unwrap();
+        assert_eq!(named_row.status, MEDIA_AVAILABLE);
+        assert_eq!(
+            library.resolve_media_path(&named_row).unwrap(),
+            root.join("a").join("named.mp4")
+        );
+        assert_eq!(
+            find(gone_row.id).await.unwrap().unwrap().status,
+            MEDIA_MISSING
+        );
+
+        // The file comes back.
+        tokio::fs::write(&gone, b"gone").await.unwrap();
+        let report = reconcile(&db).await.unwrap();
+        assert_eq!((report.restored, report.missing), (1, 0));
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_refuses_missing_download_folder() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let (root, post) = setup_library(&db).await;
+        tokio::fs::create_dir_all(&root).await.unwrap();
+        let file = root.join("video.mp4");
+        tokio::fs::write(&file, b"video").await.unwrap();
+        let row = insert_media(&db, post.id, &root, &file, None).await;
+
+        // The drive holding the download folder is unplugged.
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+        assert!(reconcile(&db).await.is_err());
+        let row = media::Entity::find_by_id(row.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.status, MEDIA_AVAILABLE);
+    }
+}
@@ -0,0 +1,544 @@
+//! Moving the whole library to a new download folder. Files are copied and
+//! verified first, the database is switched over in one transaction, and only
+//! then are the originals removed. A journal in the settings table lets a
+//! relocation cut short by a crash or quit be resumed from where it stopped; a
+//! run that fails cleans up after itself and clears the journal, so it is
+//! started over rather than resumed.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::Library;
+use crate::entity::{media, setting};
+
+/// Settings key holding the [`Relocation`] in progress.
+const JOURNAL_KEY: &str = "library_relocation";
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelocateMode {
+    /// Remove the originals once the library points at the new folder.
+    Move,
+    /// Keep the originals.
+    Copy,
+}
+
+/// A relocation in progress, as recorded in the journal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relocation {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub mode: RelocateMode,
+    /// The database already points at `target`; only removing the originals is left.
+    pub committed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelocateProgress {
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Path of the file being copied, relative to the library.
+    pub current: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RelocateReport {
+    /// Files copied to the new folder.
+    pub copied: usize,
+    /// Files already in the new folder from an interrupted run.
+    pub reused: usize,
+    /// Tracked files that were missing from the old folder.
+    pub missing: usize,
+    /// Originals removed after a move.
+    pub removed: usize,
+    pub bytes: u64,
+}
+
+/// One tracked file, relative to the library root.
+struct PlannedFile {
+    relative: PathBuf,
+    /// SHA-256 recorded for the file at download time, if any.
+    checksum: Option<String>,
+}
+
+/// The relocation left unfinished by a run that crashed or was quit, if any.
+pub async fn pending(db: &impl ConnectionTrait) -> Option<Relocation> {
+    crate::db::get_setting(db, JOURNAL_KEY)
+        .await
+        .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// Relocate the library to `target`, or resume the pending relocation to it.
+/// On failure every file this run copied is removed, the journal is cleared and
+/// the library keeps pointing at its current folder.
+pub async fn relocate(
+    db: &DatabaseConnection,
+    target: &Path,
+    mode: RelocateMode,
+    on_progress: impl Fn(RelocateProgress),
+) -> Result<RelocateReport, String> {
+    let journal = match pending(db).await {
+        Some(journal) if journal.target == target && journal.mode == mode => journal,
+        Some(journal) => {
+            return Err(format!(
+                "A relocation to {} is unfinished; resume it first",
+                journal.target.display()
+            ))
+        }
+        None => Relocation {
+            source: super::folders::download_root(db).await,
+            target: target.to_path_buf(),
+            mode,
+            committed: false,
+        },
+    };
+    let (source, target) = (journal.source.clone(), journal.target.clone());
+    if source == target {
+        return Err("The library is already in that folder".to_string());
+    }
+    if target.starts_with(&source) || source.starts_with(&target) {
+        return Err("The new folder can't be inside the current one, or contain it".to_string());
+    }
+
+    let mut report = RelocateReport::default();
+    if !journal.committed {
+        tokio::fs::create_dir_all(&target)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        write_journal(db, &journal)
+            .await
+            .map_err(|e| format!("Database error saving relocation: {}", e))?;
+
+        let files = plan(db, &source).await?;
+        let mut created = Vec::new();
+        let copied = copy_files(
+            &source,
+            &target,
+            &files,
+            &mut report,
+            &mut created,
+            &on_progress,
+        )
+        .await;
+        let committed = match copied {
+            Ok(()) => commit(db, &target, &journal).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = committed {
+            rollback(&target, &created).await;
+            let _ = setting::Entity::delete_by_id(JOURNAL_KEY).exec(db).await;
+            return Err(e);
+        }
+    }
+
+    if mode == RelocateMode::Move {
+        // The database already points at the target; plan from there.
+        for file in plan(db, &target).await? {
+            let original = source.join(&file.relative);
+            let moved = target.join(&file.relative);
+            if same_size(&original, &moved).await {
+                match tokio::fs::remove_file(&original).await {
+                    Ok(()) => report.removed += 1,
+                    Err(e) => tracing::warn!("Failed to remove {}: {}", original.display(), e),
+                }
+                prune_empty_dirs(&source, &original).await;
+            }
+        }
+    }
+
+    setting::Entity::delete_by_id(JOURNAL_KEY)
+        .exec(db)
+        .await
+        .map_err(|e| format!("Database error clearing relocation: {}", e))?;
+    tracing::info!(
+        "Library relocated from {} to {}: {:?}",
+        source.display(),
+        target.display(),
+        report
+    );
+    Ok(report)
+}
+
+/// Every tracked file under `root`: media files and their derived assets.
+async fn plan(db: &DatabaseConnection, root: &Path) -> Result<Vec<PlannedFile>, String> {
+    let library = Library::load_at(db, root.to_path_buf())
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+    let medias = media::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error loading media: {}", e))?;
+
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    for m in &medias {
+        let file = library.resolve_media_path(m).ok();
+        for path in library.asset_paths(m) {
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            if seen.insert(relative.to_path_buf()) {
+                files.push(PlannedFile {
+                    relative: relative.to_path_buf(),
+                    checksum: m.checksum.clone().filter(|_| file.as_ref() == Some(&path)),
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+async fn copy_files(
+    source: &Path,
+    target: &Path,
+    files: &[PlannedFile],
+    report: &mut RelocateReport,
+    created: &mut Vec<PathBuf>,
+    on_progress: &impl Fn(RelocateProgress),
+) -> Result<(), String> {
+    let mut sizes = Vec::with_capacity(files.len());
+    for file in files {
+        let size = tokio::fs::metadata(source.join(&file.relative))
+            .await
+            .ok()
+            .map(|m| m.len());
+        sizes.push(size);
+    }
+    let mut progress = RelocateProgress {
+        files_done: 0,
+        files_total: files.len(),
+        bytes_done: 0,
+        bytes_total: sizes.iter().flatten().sum(),
+        current: None,
+    };
+    on_progress(progress.clone());
+
+    for (file, size) in files.iter().zip(sizes) {
+        let from = source.join(&file.relative);
+        let to = target.join(&file.relative);
+        progress.current = Some(file.relative.to_string_lossy().to_string());
+
+        match size {
+            None => {
+                tracing::warn!("{} is missing, nothing to relocate", from.display());
+                report.missing += 1;
+            }
+            Some(size) if tokio::fs::try_exists(&to).await.unwrap_or(false) => {
+                // Left by an interrupted run, or a different file in the way.
+                verify(&to, size, file.checksum.as_deref())
+                    .await
+                    .map_err(|e| {
+                        format!("{} already exists in the new folder: {}", to.display(), e)
+                    })?;
+                report.reused += 1;
+                progress.bytes_done += size;
+            }
+            Some(size) => {
+                if let Some(parent) = to.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                // Copied under a temporary name, so an interrupted copy is never
+                // mistaken for a finished one.
+                let mut partial = to.clone().into_os_string();
+                partial.push(".relocating");
+                let partial = PathBuf::from(partial);
+                created.push(partial.clone());
+
+                let base = progress.bytes_done;
+                let hash = copy_file(&from, &partial, |copied| {
+                    progress.bytes_done = base + copied;
+                    on_progress(progress.clone());
+                })
+                .await?;
+                if let Some(expected) = file.checksum.as_deref() {
+                    if !hash.eq_ignore_ascii_case(expected) {
+                        return Err(format!("{} does not match its checksum", from.display()));
+                    }
+                }
+                verify(&partial, size, None).await?;
+                tokio::fs::rename(&partial, &to)
+                    .await
+                    .map_err(|e| format!("Failed to rename {}: {}", partial.display(), e))?;
+                created.push(to.clone());
+                report.copied += 1;
+                report.bytes += size;
+                progress.bytes_done = base + size;
+            }
+        }
+        progress.files_done += 1;
+        on_progress(progress.clone());
+    }
+    Ok(())
+}
+
+/// Copy `from` to `to`, reporting the bytes copied so far. Returns the SHA-256
+/// of the data copied.
+async fn copy_file(
+    from: &Path,
+    to: &Path,
+    mut on_bytes: impl FnMut(u64),
+) -> Result<String, String> {
+    let mut reader = tokio::fs::File::open(from)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", from.display(), e))?;
+    let mut writer = tokio::fs::File::create(to)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let write_error = |e: std::io::Error| format!("Failed to write {}: {}", to.display(), e);
+
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 256 * 1024];
+    let mut copied = 0u64;
+    let mut last_report = Instant::now();
+    loop {
+        let n = reader
+            .read(&mut buf)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n]).await.map_err(write_error)?;
+        hasher.update(&buf[..n]);
+        copied += n as u64;
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            on_bytes(copied);
+            last_report = Instant::now();
+        }
+    }
+    writer.flush().await.map_err(write_error)?;
+    writer.sync_all().await.map_err(write_error)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Check a copied file's size, and its checksum when one was recorded.
+async fn verify(path: &Path, size: u64, checksum: Option<&str>) -> Result<(), String> {
+    let actual = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if actual != size {
+        return Err(format!("size is {} bytes, expected {}", actual, size));
+    }
+    if let Some(expected) = checksum {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 256 * 1024];
+        loop {
+            let n = file
+                .read(&mut buf)
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        if !format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(expected) {
+            return Err("checksum does not match".to_string());
+        }
+    }
+    Ok(())
+}
+
+async fn same_size(a: &Path, b: &Path) -> bool {
+    match (tokio::fs::metadata(a).await, tokio::fs::metadata(b).await) {
+        (Ok(a), Ok(b)) => a.len() == b.len(),
+        _ => false,
+    }
+}
+
+/// Point the library at `target`: switch `download_path` and mark the journal
+/// committed, in one transaction. Media rows store paths relative to the download
+/// folder, so none of them change.
+async fn commit(
+    db: &DatabaseConnection,
+    target: &Path,
+    journal: &Relocation,
+) -> Result<(), String> {
+    let db_error = |e: DbErr| format!("Database error updating library paths: {}", e);
+    let txn = db.begin().await.map_err(db_error)?;
+
+    crate::db::set_setting(&txn, "download_path", &target.to_string_lossy())
+        .await
+        .map_err(db_error)?;
+    let committed = Relocation {
+        committed: true,
+        ..journal.clone()
+    };
+    write_journal(&txn, &committed).await.map_err(db_error)?;
+    txn.commit().await.map_err(db_error)
+}
+
+async fn write_journal(db: &impl ConnectionTrait, journal: &Relocation) -> Result<(), DbErr> {
+    let json = serde_json::to_string(journal).map_err(|e| DbErr::Custom(e.to_string()))?;
+    crate::db::set_setting(db, JOURNAL_KEY, &json).await
+}
+
+/// Remove the files a failed run copied, and the directories left empty.
+async fn rollback(target: &Path, created: &[PathBuf]) {
+    for path in created.iter().rev() {
+        if let Err(e) = tokio::fs::remove_file(path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+        prune_empty_dirs(target, path).await;
+    }
+}
+
+/// Remove the now-empty directories between `file` and `root` (exclusive).
+async fn prune_empty_dirs(root: &Path, file: &Path) {
+    let mut dir = file.parent();
+    while let Some(d) = dir {
+        if d == root || !d.starts_with(root) || tokio::fs::remove_dir(d).await.is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    async fn seed(db: &DatabaseConnection, root: &Path) -> (PathBuf, media::Model) {
+        let post = crate::library::tests::seed_post(db, root).await;
+
+        let file = root.join("youtube").join("clip.mp4");
+        let thumb = root.join("youtube").join("clip_thumb.jpg");
+        tokio::fs::create_dir_all(file.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(&file, b"video data").await.unwrap();
+        tokio::fs::write(&thumb, b"jpeg").await.unwrap();
+
+        let (folder_id, name) = super::super::folders::locate(db, root, &file)
+            .await
+            .unwrap();
+        let m = media::ActiveModel {
+            post_id: Set(post.id),
+            media_type: Set("VIDEO".to_string()),
+            file_path: Set(name),
+            folder_id: Set(folder_id),
+            thumbnail_path: Set(Some("clip_thumb.jpg".to_string())),
+            order_index: Set(0),
+            checksum: Set(Some(format!("{:x}", Sha256::digest(b"video data")))),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+        (file, m)
+    }
+
+    #[tokio::test]
+    async fn test_move_library() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let base = std::env::temp_dir().join(format!("vdp-relocate-{}", uuid::Uuid::new_v4()));
+        let (old, new) = (base.join("old"), base.join("new"));
+        let (file, m) = seed(&db, &old).await;
+
+        let report = relocate(&db, &new, RelocateMode::Move, |_| {})
+            .await
+            .unwrap();
+        assert_eq!((report.copied, report.removed, report.missing), (2, 2, 0));
+        assert!(!file.exists());
+        assert!(!old.join("youtube").exists());
+
+        let moved = media::Entity::find_by_id(m.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        let library = Library::load(&db).await.unwrap();
+        assert_eq!(library.root(), new.as_path());
+        let path = library.resolve_media_path(&moved).unwrap();
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"video data");
+        assert_eq!(
+            library.derived_asset_paths(&moved),
+            [new.join("youtube").join("clip_thumb.jpg")]
+        );
+        assert!(pending(&db).await.is_none());
+
+        let _ = tokio::fs::remove_dir_all(&base).await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_relocation_rolls_back() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let base = std::env::temp_dir().join(format!("vdp-relocate-{}", uuid::Uuid::new_v4()));
+        let (old, new) = (base.join("old"), base.join("new"));
+        let (file, m) = seed(&db, &old).await;
+        // Corrupt the file after its checksum was recorded.
+        tokio::fs::write(&file, b"video dat4").await.unwrap();
+
+        assert!(relocate(&db, &new, RelocateMode::Move, |_| {})
+            .await
+            .is_err());
+        assert!(file.exists());
+        assert!(!new.join("youtube").exists());
+        let library = Library::load(&db).await.unwrap();
+        assert_eq!(library.root(), old.as_path());
+        let unchanged = media::Entity::find_by_id(m.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged, m);
+        assert!(pending(&db).await.is_none());
+
+        let _ = tokio::fs::remove_dir_all(&base).await;
+    }
+
+    #[tokio::test]
+    async fn test_resume_reuses_copied_files() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let base = std::env::temp_dir().join(format!("vdp-relocate-{}", uuid::Uuid::new_v4()));
+        let (old, new) = (base.join("old"), base.join("new"));
+        seed(&db, &old).await;
+
+        // An earlier run copied the video, then stopped.
+        let journal = Relocation {
+            source: old.clone(),
+            target: new.clone(),
+            mode: RelocateMode::Copy,
+            committed: false,
+        };
+        write_journal(&db, &journal).await.unwrap();
+        tokio::fs::create_dir_all(new.join("youtube"))
+            .await
+            .unwrap();
+        tokio::fs::write(new.join("youtube").join("clip.mp4"), b"video data")
+            .await
+            .unwrap();
+
+        let other = base.join("other");
+        assert!(relocate(&db, &other, RelocateMode::Copy, |_| {})
+            .await
+            .is_err());
+
+        let report = relocate(&db, &new, RelocateMode::Copy, |_| {})
+            .await
+            .unwrap();
+        assert_eq!((report.copied, report.reused, report.removed), (1, 1, 0));
+        assert!(old.join("youtube").join("clip.mp4").exists());
+
+        let _ = tokio::fs::remove_dir_all(&base).await;
+    }
+}
@@ -0,0 +1,317 @@
+//! Following changes made to the download folder while the app runs. File
+//! system events are turned into [`FileChange`]s, then applied to the media rows:
+//! a file renamed or moved within the folder is followed, one deleted is marked
+//! MISSING, and one that comes back is available again.
+
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{Event, EventKind};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::reconcile::{set_status, MEDIA_AVAILABLE, MEDIA_MISSING};
+use super::Library;
+use crate::entity::media;
+
+/// File name endings of the files our own downloads and post-processing write
+/// before the final file exists.
+const TRANSIENT_SUFFIXES: &[&str] = &[".part", ".ytdl", ".tmp", ".temp", ".relocating"];
+
+/// Markers of intermediate files: yt-dlp fragments and the outputs of
+/// loudness normalization and audio tagging before they replace the original.
+const TRANSIENT_MARKERS: &[&str] = &[".part-Frag", ".loudnorm.", ".tagged."];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileChange {
+    /// A file or directory renamed or moved within the download folder.
+    Moved { from: PathBuf, to: PathBuf },
+    /// A file or directory deleted or moved out of the download folder.
+    Removed(PathBuf),
+    /// A file or directory created or moved into the download folder.
+    Created(PathBuf),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WatchReport {
+    /// Rows following a renamed or moved file.
+    pub moved: usize,
+    /// Rows whose file was deleted.
+    pub missing: usize,
+    /// MISSING rows whose file is back.
+    pub restored: usize,
+}
+
+impl WatchReport {
+    pub fn is_empty(&self) -> bool {
+        self.moved == 0 && self.missing == 0 && self.restored == 0
+    }
+}
+
+/// Whether `path` is a download or post-processing intermediate.
+pub fn is_transient(path: &Path) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return false;
+    };
+    TRANSIENT_SUFFIXES.iter().any(|s| name.ends_with(s))
+        || TRANSIENT_MARKERS.iter().any(|m| name.contains(m))
+}
+
+/// The changes a debounced event stands for, leaving out transient files. A
+/// rename from a transient name is the finished file appearing.
+pub fn classify(event: &Event) -> Vec<FileChange> {
+    let kept = |path: &PathBuf| !is_transient(path);
+    match event.kind {
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match event.paths.as_slice() {
+            [from, to] => match (kept(from), kept(to)) {
+                (true, true) => vec![FileChange::Moved {
+                    from: from.clone(),
+                    to: to.clone(),
+                }],
+                (true, false) => vec![FileChange::Removed(from.clone())],
+                (false, true) => vec![FileChange::Created(to.clone())],
+                (false, false) => Vec::new(),
+            },
+            _ => Vec::new(),
+        },
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => event
+            .paths
+            .iter()
+            .filter(|p| kept(p))
+            .map(|p| FileChange::Removed(p.clone()))
+            .collect(),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) | EventKind::Create(_) => event
+            .paths
+            .iter()
+            .filter(|p| kept(p))
+            .map(|p| FileChange::Created(p.clone()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Apply `changes`, in order, to the media rows. Rows are matched on the path
+/// itself or, for a directory, anything below it.
+pub async fn apply(db: &DatabaseConnection, changes: &[FileChange]) -> Result<WatchReport, String> {
+    let mut report = WatchReport::default();
+    let (mut library, mut rows) = load(db).await?;
+    for change in changes {
+        match change {
+            FileChange::Moved { from, to } => {
+                let mut moved = 0;
+                for (m, path) in rows.iter() {
+                    let Ok(relative) = path.strip_prefix(from) else {
+                        continue;
+                    };
+                    let target = if relative.as_os_str().is_empty() {
+                        to.clone()
+                    } else {
+                        to.join(relative)
+                    };
+                    follow(db, &library, m, &target).await?;
+                    moved += 1;
+                }
+                if moved > 0 {
+                    report.moved += moved;
+                    // New folder rows may have been created; resolve afresh.
+                    (library, rows) = load(db).await?;
+                }
+            }
+            FileChange::Removed(removed) => {
+                for (m, path) in rows.iter_mut() {
+                    if m.status == MEDIA_MISSING || !path.starts_with(removed) {
+                        continue;
+                    }
+                    if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                        continue;
+                    }
+                    set_status(db, m, MEDIA_MISSING).await?;
+                    m.status = MEDIA_MISSING.to_string();
+                    tracing::info!("Media {} deleted from {}", m.id, path.display());
+                    report.missing += 1;
+                }
+            }
+            FileChange::Created(created) => {
+                for (m, path) in rows.iter_mut() {
+                    if m.status != MEDIA_MISSING || !path.starts_with(created) {
+                        continue;
+                    }
+                    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+                        continue;
+                    }
+                    set_status(db, m, MEDIA_AVAILABLE).await?;
+                    m.status = MEDIA_AVAILABLE.to_string();
+                    tracing::info!("Media {} is back at {}", m.id, path.display());
+                    report.restored += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// The library and every media row with its resolved path.
+async fn load(db: &DatabaseConnection) -> Result<(Library, Vec<(media::Model, PathBuf)>), String> {
+    let library = Library::load(db)
+        .await
+        .map_err(|e| format!("Database error loading folders: {}", e))?;
+    let rows = media::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error loading media: {}", e))?
+        .into_iter()
+        .filter_map(|m| {
+            let path = library.resolve_media_path(&m).ok()?;
+            Some((m, path))
+        })
+        .collect();
+    Ok((library, rows))
+}
+
+/// Point a row at `target`, its file's new location, with the generated assets
+/// that moved along with it.
+async fn follow(
+    db: &DatabaseConnection,
+    library: &Library,
+    m: &media::Model,
+    target: &Path,
+) -> Result<(), String> {
+    let (folder_id, file_path) = super::folders::locate(db, library.root(), target)
+        .await
+        .map_err(|e| format!("Database error recording folder: {}", e))?;
+    let mut row: media::ActiveModel = m.clone().into();
+    super::folders::follow_assets(m, &mut row, target, &file_path);
+    row.file_path = Set(file_path);
+    row.folder_id = Set(folder_id);
+    row.status = Set(MEDIA_AVAILABLE.to_string());
+
+    row.update(db)
+        .await
+        .map_err(|e| format!("Database error updating media {}: {}", m.id, e))?;
+    tracing::info!("Media {} moved to {}", m.id, target.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::library::folders::asset_name;
+    use notify_debouncer_full::notify::event::{CreateKind, RemoveKind};
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        Event {
+            kind,
+            paths: paths.iter().map(PathBuf::from).collect(),
+            attrs: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_classify_ignores_transient_files() {
+        let rename = EventKind::Modify(ModifyKind::Name(RenameMode::Both));
+        assert_eq!(
+            classify(&event(rename, &["/d/a.mp4", "/d/b.mp4"])),
+            [FileChange::Moved {
+                from: PathBuf::from("/d/a.mp4"),
+                to: PathBuf::from("/d/b.mp4"),
+            }]
+        );
+        assert_eq!(
+            classify(&event(rename, &["/d/a.mp4.part", "/d/a.mp4"])),
+            [FileChange::Created(PathBuf::from("/d/a.mp4"))]
+        );
+        let created = EventKind::Create(CreateKind::File);
+        assert!(classify(&event(created, &["/d/a.mp4.ytdl"])).is_empty());
+        assert!(classify(&event(created, &["/d/a.f137.mp4.part-Frag3"])).is_empty());
+        assert!(classify(&event(created, &["/d/a.loudnorm.mp4"])).is_empty());
+        assert_eq!(
+            classify(&event(EventKind::Remove(RemoveKind::File), &["/d/a.mp4"])),
+            [FileChange::Removed(PathBuf::from("/d/a.mp4"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_follows_moves_and_deletes() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let root = std::env::temp_dir().join(format!("vdp-watch-{}", uuid::Uuid::new_v4()));
+        let post = crate::library::tests::seed_post(&db, &root).await;
+
+        let old_dir = root.join("old");
+        tokio::fs::create_dir_all(&old_dir).await.unwrap();
+        let clip = old_dir.join("clip.mp4");
+        let thumbnail = old_dir.join("clip.thumb_sm.jpg");
+        let other = root.join("other.mp4");
+        for path in [&clip, &thumbnail, &other] {
+            tokio::fs::write(path, b"data").await.unwrap();
+        }
+        let mut ids = Vec::new();
+        for (path, thumb) in [(&clip, Some(&thumbnail)), (&other, None)] {
+            let (folder_id, file_path) = super::super::folders::locate(&db, &root, path)
+                .await
+                .unwrap();
+            let m = media::ActiveModel {
+                post_id: Set(post.id),
+                media_type: Set("VIDEO".to_string()),
+                file_path: Set(file_path.clone()),
+                folder_id: Set(folder_id),
+                thumbnail_path: Set(thumb.map(|t| asset_name(&file_path, t))),
+                order_index: Set(0),
+                ..Default::default()
+            }
+            .insert(&db)
+            .await
+            .unwrap();
+            ids.push(m.id);
+        }
+
+        // A folder renamed in the file manager, then a file deleted and put back.
+        let new_dir = root.join("new");
+        tokio::fs::rename(&old_dir, &new_dir).await.unwrap();
+        tokio::fs::remove_file(&other).await.unwrap();
+        let changes = [
+            FileChange::Moved {
+                from: old_dir.clone(),
+                to: new_dir.clone(),
+            },
+            FileChange::Removed(other.clone()),
+        ];
+        let report = apply(&db, &changes).await.unwrap();
+        assert_eq!(
+            report,
+            WatchReport {
+                moved: 1,
+                missing: 1,
+                restored: 0,
+            }
+        );
+
+        let library = Library::load(&db).await.unwrap();
+        let moved = media::Entity::find_by_id(ids[0])
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            library.resolve_media_path(&moved).unwrap(),
+            new_dir.join("clip.mp4")
+        );
+        assert_eq!(
+            library.derived_asset_paths(&moved),
+            [new_dir.join("clip.thumb_sm.jpg")]
+        );
+        let deleted = media::Entity::find_by_id(ids[1])
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(deleted.status, MEDIA_MISSING);
+
+        tokio::fs::write(&other, b"data").await.unwrap();
+        let report = apply(&db, &[FileChange::Created(other.clone())])
+            .await
+            .unwrap();
+        assert_eq!(report.restored, 1);
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
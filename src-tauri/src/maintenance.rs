@@ -0,0 +1,462 @@
+//! Database upkeep: SQLite's integrity and foreign key checks, removal of rows
+//! left behind by earlier deletions (media, chapters and tasks of posts that are
+//! gone, posts created only for a download that was cleared from the history,
+//! creators and folders nothing refers to), then `ANALYZE`, `VACUUM` and a WAL
+//! checkpoint.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    sea_query::Query, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QuerySelect, Statement,
+};
+use serde::Serialize;
+
+use crate::entity::{
+    chapter, creator, download_task, folder, media, post, source, sponsor_segment,
+};
+
+/// Days between scheduled maintenance runs; 0 turns them off.
+pub const INTERVAL_DAYS_KEY: &str = "maintenance_interval_days";
+/// When maintenance last ran (RFC 3339).
+pub const LAST_RUN_KEY: &str = "maintenance_last_run";
+
+const DEFAULT_INTERVAL_DAYS: i64 = 7;
+
+/// Posts younger than this are left alone: a download may have saved the post
+/// but not yet created its task or media.
+const UNUSED_POST_GRACE_HOURS: i64 = 24;
+
+/// Task states after which a task is only history.
+const FINISHED_TASK_STATUSES: &[&str] = &["COMPLETED", "CANCELLED", "FAILED"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MaintenanceReport {
+    /// Problems `integrity_check` found. When there are any, nothing is repaired:
+    /// restore a backup instead.
+    pub integrity_errors: Vec<String>,
+    /// `table row → parent` for each row `foreign_key_check` found pointing at a
+    /// missing row, before the repairs below.
+    pub foreign_key_errors: Vec<String>,
+    pub media_removed: u64,
+    pub chapters_removed: u64,
+    pub segments_removed: u64,
+    /// Finished tasks of posts that are gone.
+    pub tasks_removed: u64,
+    /// Unfinished tasks of posts that are gone; they resolve the post again.
+    pub tasks_detached: u64,
+    pub posts_removed: u64,
+    pub creators_removed: u64,
+    pub folders_removed: u64,
+    /// Database size in bytes before and after.
+    pub size_before: i64,
+    pub size_after: i64,
+}
+
+/// Check the database, repair orphaned rows and compact it, recording the run
+/// under `maintenance_last_run`. Refuses while a download is running, since
+/// `VACUUM` holds the database for its duration.
+pub async fn run(db: &DatabaseConnection) -> Result<MaintenanceReport, String> {
+    let db_error = |e: DbErr| format!("Database error during maintenance: {}", e);
+    if downloads_running(db).await.map_err(db_error)? {
+        return Err("Wait for active downloads to finish before running maintenance".to_string());
+    }
+    let mut report = MaintenanceReport {
+        size_before: database_size(db).await.map_err(db_error)?,
+        ..Default::default()
+    };
+
+    report.integrity_errors = pragma_rows(db, "PRAGMA integrity_check")
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .filter_map(|row| row.try_get_by_index::<String>(0).ok())
+        .filter(|line| line != "ok")
+        .collect();
+    if !report.integrity_errors.is_empty() {
+        tracing::error!(
+            "Database integrity check failed: {:?}",
+            report.integrity_errors
+        );
+        report.size_after = report.size_before;
+        return Ok(report);
+    }
+
+    report.foreign_key_errors = pragma_rows(db, "PRAGMA foreign_key_check")
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|row| {
+            let table = row.try_get_by_index::<String>(0).unwrap_or_default();
+            let rowid = row.try_get_by_index::<Option<i64>>(1).ok().flatten();
+            let parent = row.try_get_by_index::<String>(2).unwrap_or_default();
+            format!("{} {} → {}", table, rowid.unwrap_or_default(), parent)
+        })
+        .collect();
+
+    repair(db, &mut report).await.map_err(db_error)?;
+
+    for statement in ["ANALYZE", "VACUUM", "PRAGMA wal_checkpoint(TRUNCATE)"] {
+        db.execute_unprepared(statement).await.map_err(db_error)?;
+    }
+    report.size_after = database_size(db).await.map_err(db_error)?;
+    crate::db::set_setting(db, LAST_RUN_KEY, &Utc::now().to_rfc3339())
+        .await
+        .map_err(db_error)?;
+
+    tracing::info!("Database maintenance finished: {:?}", report);
+    Ok(report)
+}
+
+async fn repair(db: &DatabaseConnection, report: &mut MaintenanceReport) -> Result<(), DbErr> {
+    let post_ids = || {
+        Query::select()
+            .column(post::Column::Id)
+            .from(post::Entity)
+            .to_owned()
+    };
+
+    report.media_removed = media::Entity::delete_many()
+        .filter(media::Column::PostId.not_in_subquery(post_ids()))
+        .exec(db)
+        .await?
+        .rows_affected;
+    report.chapters_removed = chapter::Entity::delete_many()
+        .filter(chapter::Column::PostId.not_in_subquery(post_ids()))
+        .exec(db)
+        .await?
+        .rows_affected;
+    report.segments_removed = sponsor_segment::Entity::delete_many()
+        .filter(sponsor_segment::Column::PostId.not_in_subquery(post_ids()))
+        .exec(db)
+        .await?
+        .rows_affected;
+
+    report.tasks_removed = download_task::Entity::delete_many()
+        .filter(download_task::Column::PostId.is_not_null())
+        .filter(download_task::Column::PostId.not_in_subquery(post_ids()))
+        .filter(download_task::Column::Status.is_in(FINISHED_TASK_STATUSES.iter().copied()))
+        .exec(db)
+        .await?
+        .rows_affected;
+    report.tasks_detached = download_task::Entity::update_many()
+        .col_expr(
+            download_task::Column::PostId,
+            sea_orm::sea_query::Expr::value(Option::<i64>::None),
+        )
+        .filter(download_task::Column::PostId.is_not_null())
+        .filter(download_task::Column::PostId.not_in_subquery(post_ids()))
+        .exec(db)
+        .await?
+        .rows_affected;
+
+    let grace = Utc::now() - chrono::Duration::hours(UNUSED_POST_GRACE_HOURS);
+    report.posts_removed = remove_unused_posts(db, None, Some(grace)).await?;
+
+    report.creators_removed = creator::Entity::delete_many()
+        .filter(creator::Column::IsSelf.eq(false))
+        .filter(
+            creator::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(post::Column::CreatorId)
+                    .from(post::Entity)
+                    .to_owned(),
+            ),
+        )
+        .filter(
+            creator::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(source::Column::CreatorId)
+                    .from(source::Entity)
+                    .and_where(source::Column::CreatorId.is_not_null())
+                    .to_owned(),
+            ),
+        )
+        .exec(db)
+        .await?
+        .rows_affected;
+
+    // Removing a folder can leave its parent empty, so repeat until none go.
+    loop {
+        let removed = folder::Entity::delete_many()
+            .filter(
+                folder::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(media::Column::FolderId)
+                        .from(media::Entity)
+                        .and_where(media::Column::FolderId.is_not_null())
+                        .to_owned(),
+                ),
+            )
+            .filter(
+                folder::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(download_task::Column::FolderId)
+                        .from(download_task::Entity)
+                        .and_where(download_task::Column::FolderId.is_not_null())
+                        .to_owned(),
+                ),
+            )
+            .filter(
+                folder::Column::Id.not_in_subquery(
+                    Query::select()
+                        .column(folder::Column::ParentId)
+                        .from(folder::Entity)
+                        .and_where(folder::Column::ParentId.is_not_null())
+                        .to_owned(),
+                ),
+            )
+            .exec(db)
+            .await?
+            .rows_affected;
+        if removed == 0 {
+            break;
+        }
+        report.folders_removed += removed;
+    }
+
+    Ok(())
+}
+
+/// Delete the posts that only existed for a one-off download which never
+/// completed and is no longer in the queue: no source, still PENDING, and no
+/// media or tasks. `among` limits the check to those posts and `created_before`
+/// to older ones. Returns the number deleted.
+pub async fn remove_unused_posts(
+    db: &DatabaseConnection,
+    among: Option<&[i64]>,
+    created_before: Option<DateTime<Utc>>,
+) -> Result<u64, DbErr> {
+    let mut query = post::Entity::find()
+        .select_only()
+        .column(post::Column::Id)
+        .filter(post::Column::SourceId.is_null())
+        .filter(post::Column::Status.eq("PENDING"))
+        .filter(post::Column::DeletedAt.is_null())
+        .filter(
+            post::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(media::Column::PostId)
+                    .from(media::Entity)
+                    .to_owned(),
+            ),
+        )
+        .filter(
+            post::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(download_task::Column::PostId)
+                    .from(download_task::Entity)
+                    .and_where(download_task::Column::PostId.is_not_null())
+                    .to_owned(),
+            ),
+        );
+    if let Some(ids) = among {
+        query = query.filter(post::Column::Id.is_in(ids.iter().copied()));
+    }
+    if let Some(before) = created_before {
+        query = query.filter(post::Column::CreatedAt.lt(before));
+    }
+    let unused: Vec<i64> = query.into_tuple().all(db).await?;
+
+    for post_id in &unused {
+        crate::metadata::store::delete_post(db, *post_id).await?;
+    }
+    Ok(unused.len() as u64)
+}
+
+/// Run maintenance if it hasn't run for `maintenance_interval_days`. Waits for a
+/// moment without downloads, since `VACUUM` holds the database for its duration.
+pub async fn run_scheduled(db: &DatabaseConnection) -> Result<Option<MaintenanceReport>, String> {
+    let interval_days = crate::db::get_setting(db, INTERVAL_DAYS_KEY)
+        .await
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_DAYS);
+    if interval_days <= 0 {
+        return Ok(None);
+    }
+    let last_run = crate::db::get_setting(db, LAST_RUN_KEY)
+        .await
+        .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+        .map(|at| at.with_timezone(&Utc));
+    if last_run.is_some_and(|at| Utc::now() - at < chrono::Duration::days(interval_days)) {
+        return Ok(None);
+    }
+
+    let downloading = downloads_running(db)
+        .await
+        .map_err(|e| format!("Database error during maintenance: {}", e))?;
+    if downloading {
+        tracing::info!("Downloads in progress, postponing database maintenance");
+        return Ok(None);
+    }
+
+    run(db).await.map(Some)
+}
+
+async fn downloads_running(db: &DatabaseConnection) -> Result<bool, DbErr> {
+    let running = download_task::Entity::find()
+        .filter(download_task::Column::Status.eq("PROCESSING"))
+        .count(db)
+        .await?;
+    Ok(running > 0)
+}
+
+async fn pragma_rows(
+    db: &impl ConnectionTrait,
+    pragma: &str,
+) -> Result<Vec<sea_orm::QueryResult>, DbErr> {
+    db.query_all(Statement::from_string(
+        db.get_database_backend(),
+        pragma.to_owned(),
+    ))
+    .await
+}
+
+async fn database_size(db: &impl ConnectionTrait) -> Result<i64, DbErr> {
+    let pages = pragma_rows(db, "PRAGMA page_count").await?;
+    let page_size = pragma_rows(db, "PRAGMA page_size").await?;
+    let value = |rows: &[sea_orm::QueryResult]| {
+        rows.first()
+            .and_then(|row| row.try_get_by_index::<i64>(0).ok())
+            .unwrap_or_default()
+    };
+    Ok(value(&pages) * value(&page_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::metadata::models::{YtDlpOutput, YtDlpVideo};
+    use crate::metadata::store::save_metadata;
+    use sea_orm::Set;
+
+    fn video(id: &str, uploader: &str) -> YtDlpOutput {
+        let json = serde_json::json!({
+            "_type": "video",
+            "id": id,
+            "title": id,
+            "uploader": uploader,
+            "uploader_id": uploader,
+            "webpage_url": format!("https://www.youtube.com/watch?v={}", id),
+        });
+        YtDlpOutput::Video(serde_json::from_value::<YtDlpVideo>(json).unwrap())
+    }
+
+    fn task(post_id: Option<i64>, status: &str) -> download_task::ActiveModel {
+        download_task::ActiveModel {
+            url: Set("https://www.youtube.com/watch?v=x".to_string()),
+            post_id: Set(post_id),
+            status: Set(status.to_string()),
+            priority: Set(0),
+            progress: Set(0.0),
+            retries: Set(0),
+            max_retries: Set(3),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_repairs_orphans() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let kept = save_metadata(&db, video("kept0000001", "kept"), None, None, None, None)
+            .await
+            .unwrap();
+        download_task::Entity::insert(task(Some(kept), "QUEUED"))
+            .exec(&db)
+            .await
+            .unwrap();
+        // A cancelled one-off download whose task was cleared from the history.
+        let gone = save_metadata(&db, video("gone0000001", "gone"), None, None, None, None)
+            .await
+            .unwrap();
+        post::Entity::update_many()
+            .col_expr(
+                post::Column::CreatedAt,
+                sea_orm::sea_query::Expr::value(Utc::now() - chrono::Duration::days(2)),
+            )
+            .filter(post::Column::Id.eq(gone))
+            .exec(&db)
+            .await
+            .unwrap();
+        // A download that saved its post but hasn't created the task yet.
+        let fresh = save_metadata(&db, video("new00000001", "new"), None, None, None, None)
+            .await
+            .unwrap();
+        let folder_id = crate::library::folders::ensure_folder(&db, "youtube/empty".as_ref())
+            .await
+            .unwrap();
+        assert!(folder_id.is_some());
+
+        // Rows written before deletions cleaned up after themselves.
+        db.execute_unprepared("PRAGMA foreign_keys = OFF")
+            .await
+            .unwrap();
+        media::Entity::insert(media::ActiveModel {
+            post_id: Set(999),
+            media_type: Set("VIDEO".to_string()),
+            file_path: Set("lost.mp4".to_string()),
+            order_index: Set(0),
+            status: Set("AVAILABLE".to_string()),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+        download_task::Entity::insert(task(Some(999), "COMPLETED"))
+            .exec(&db)
+            .await
+            .unwrap();
+        download_task::Entity::insert(task(Some(998), "QUEUED"))
+            .exec(&db)
+            .await
+            .unwrap();
+        db.execute_unprepared("PRAGMA foreign_keys = ON")
+            .await
+            .unwrap();
+
+        let report = run(&db).await.unwrap();
+        assert!(report.integrity_errors.is_empty());
+        assert_eq!(report.foreign_key_errors.len(), 3);
+        assert_eq!(report.media_removed, 1);
+        assert_eq!(report.tasks_removed, 1);
+        assert_eq!(report.tasks_detached, 1);
+        assert_eq!(report.posts_removed, 1);
+        assert_eq!(report.creators_removed, 1);
+        assert_eq!(report.folders_removed, 2);
+
+        for id in [kept, fresh] {
+            assert!(post::Entity::find_by_id(id)
+                .one(&db)
+                .await
+                .unwrap()
+                .is_some());
+        }
+        assert_eq!(
+            download_task::Entity::find().all(&db).await.unwrap().len(),
+            2
+        );
+        let again = run(&db).await.unwrap();
+        assert!(again.foreign_key_errors.is_empty());
+        assert_eq!(again.posts_removed + again.creators_removed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_maintenance_waits_for_downloads() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let processing = download_task::Entity::insert(task(None, "PROCESSING"))
+            .exec(&db)
+            .await
+            .unwrap()
+            .last_insert_id;
+        assert!(run_scheduled(&db).await.unwrap().is_none());
+        assert!(run(&db).await.is_err());
+        assert!(db::get_setting(&db, LAST_RUN_KEY).await.is_none());
+
+        download_task::Entity::delete_by_id(processing)
+            .exec(&db)
+            .await
+            .unwrap();
+        assert!(run_scheduled(&db).await.unwrap().is_some());
+    }
+}
@@ -24,6 +24,8 @@ pub async fn fetch_metadata(
     // --dump-single-json: Ensure we get a single JSON object (Video or Playlist)
     // --flat-playlist: Don't recurse into playlist items (fast)
     // --no-warnings: Keep stderr clean
+    // --ignore-no-formats-error: Upcoming premieres/streams have no formats yet but
+    //   still report their start time
     // Windows: hide console window
     let mut cmd = Command::new(binary_path);
     cmd.env("PYTHONIOENCODING", "utf-8");
@@ -32,6 +34,7 @@ pub async fn fetch_metadata(
     cmd.arg("--dump-single-json")
         .arg("--flat-playlist")
         .arg("--no-warnings")
+        .arg("--ignore-no-formats-error")
         .arg("--js-runtimes")
         .arg(deno_arg);
 
@@ -23,6 +23,12 @@ pub struct ProcessedMetadata {
     pub audio_tracks: Vec<AudioTrack>,
     pub subtitle_tracks: Vec<SubtitleTrack>,
     pub is_playlist: bool,
+    /// The URL is a stream that is broadcasting now; downloading it records the stream.
+    pub is_live: bool,
+    /// A scheduled premiere or stream that hasn't started; the download waits for it.
+    pub is_upcoming: bool,
+    /// Unix timestamp the upcoming event is scheduled to start at, if known.
+    pub release_timestamp: Option<i64>,
     /// Populated only when `is_playlist` is true.
     /// Contains the individual items of the playlist for UI selection.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -114,9 +120,106 @@ pub struct DownloadOptions {
     /// Embed subtitles directly into the video container.
     #[serde(default)]
     pub embed_subs: bool,
+    /// Convert downloaded subtitle files from VTT to SRT.
+    #[serde(default)]
+    pub subs_as_srt: bool,
     /// Override output container: "mp4", "mkv", "webm". None = auto.
     #[serde(default)]
     pub container: Option<String>,
+    /// Write chapter markers into the output file.
+    #[serde(default)]
+    pub embed_chapters: bool,
+    /// Additionally write one file per chapter next to the full download.
+    #[serde(default)]
+    pub split_chapters: bool,
+    /// SponsorBlock handling. None = use the `sponsorblock_mode` setting.
+    #[serde(default)]
+    pub sponsorblock_mode: Option<SponsorBlockMode>,
+    /// SponsorBlock categories: ["sponsor", "intro"]. Empty = use the `sponsorblock_categories` setting.
+    #[serde(default)]
+    pub sponsorblock_categories: Vec<String>,
+    /// Write title, uploader, date, description and URL tags into the file.
+    /// None = use the `embed_metadata` setting.
+    #[serde(default)]
+    pub embed_metadata: Option<bool>,
+    /// Embed the platform thumbnail as cover art. None = use the `embed_thumbnail` setting.
+    #[serde(default)]
+    pub embed_thumbnail: Option<bool>,
+    /// Save yt-dlp's `.info.json` next to the file. None = use the `write_info_json` setting.
+    #[serde(default)]
+    pub write_info_json: Option<bool>,
+    /// Portions to download instead of the whole video: `start-end` timestamps
+    /// ("1:02:03-1:02:33", "90-inf") or chapter title regexes. Empty = whole video.
+    #[serde(default)]
+    pub sections: Vec<String>,
+    /// Re-encode around section cuts so clips start exactly on the requested time.
+    #[serde(default)]
+    pub force_keyframes_at_cuts: bool,
+    /// Transcode profile id to run after the download ("h264_1080p", ...).
+    /// None = use the `transcode_profile` setting; "" or "none" = keep the download as is.
+    #[serde(default)]
+    pub transcode_profile: Option<String>,
+    /// Split "Artist - Title" video titles into artist and title tags of extracted audio.
+    /// None = use the `audio_parse_artist_title` setting.
+    #[serde(default)]
+    pub parse_artist_title: Option<bool>,
+    /// Normalize loudness (EBU R128) after download. None = use the `loudnorm_enabled` setting.
+    #[serde(default)]
+    pub loudnorm: Option<bool>,
+    /// When recording a live stream, start from the beginning of the broadcast
+    /// instead of from the moment the download starts.
+    #[serde(default)]
+    pub live_from_start: bool,
+}
+
+impl DownloadOptions {
+    /// `--download-sections` values for [`Self::sections`]: time ranges get yt-dlp's
+    /// `*` prefix, anything else is passed through as a chapter regex.
+    pub fn download_section_args(&self) -> Vec<String> {
+        self.sections
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                let range = s.strip_prefix('*').unwrap_or(s);
+                if is_time_range(range) {
+                    format!("*{}", range.replace(' ', ""))
+                } else {
+                    s.to_string()
+                }
+            })
+            .collect()
+    }
+}
+
+/// `start-end` where both ends are `[[HH:]MM:]SS[.ms]` timestamps (the end may be `inf`).
+fn is_time_range(value: &str) -> bool {
+    fn is_timestamp(t: &str) -> bool {
+        let t = t.trim();
+        !t.is_empty()
+            && t.split(':').count() <= 3
+            && t.split(':').all(|part| {
+                !part.is_empty() && part.chars().all(|c| c.is_ascii_digit() || c == '.')
+            })
+    }
+
+    match value.split_once('-') {
+        Some((start, end)) => is_timestamp(start) && (end.trim() == "inf" || is_timestamp(end)),
+        None => false,
+    }
+}
+
+/// What to do with SponsorBlock segments in a download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SponsorBlockMode {
+    /// Leave the video untouched.
+    #[default]
+    Off,
+    /// Mark segments as chapters (`--sponsorblock-mark`).
+    Mark,
+    /// Cut segments out of the file (`--sponsorblock-remove`).
+    Remove,
 }
 
 // ─── Processing Logic ──────────────────────────────────────────────────────────
@@ -138,6 +241,9 @@ pub fn process_metadata(video: &YtDlpVideo) -> ProcessedMetadata {
         audio_tracks,
         subtitle_tracks,
         is_playlist: false,
+        is_live: video.is_live_now(),
+        is_upcoming: video.is_upcoming(),
+        release_timestamp: video.release_timestamp,
         playlist_entries: Vec::new(),
     }
 }
@@ -190,6 +296,9 @@ pub fn process_playlist(playlist: &YtDlpPlaylist) -> ProcessedMetadata {
         audio_tracks: Vec::new(),
         subtitle_tracks: Vec::new(),
         is_playlist: true,
+        is_live: false,
+        is_upcoming: false,
+        release_timestamp: None,
         playlist_entries: entries,
     }
 }
@@ -552,9 +661,14 @@ mod tests {
             subtitles,
             automatic_captions: auto_caps,
             requested_subtitles: None,
+            chapters: None,
             playlist_index: None,
             playlist_title: None,
             playlist_id: None,
+            is_live: None,
+            was_live: None,
+            live_status: None,
+            release_timestamp: None,
         }
     }
 
@@ -745,4 +859,21 @@ mod tests {
         assert!(result.audio_tracks.is_empty());
         assert!(result.subtitle_tracks.is_empty());
     }
+
+    #[test]
+    fn test_download_section_args() {
+        let opts = DownloadOptions {
+            sections: vec![
+                "1:02:03-1:02:33".to_string(),
+                "*90 - inf".to_string(),
+                "Intro|Outro".to_string(),
+                "  ".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.download_section_args(),
+            vec!["*1:02:03-1:02:33", "*90-inf", "Intro|Outro"]
+        );
+    }
 }
@@ -67,6 +67,16 @@ pub struct YtDlpSubtitle {
 /// Subtitle map: language_code → Vec<YtDlpSubtitle>
 pub type SubtitleMap = HashMap<String, Vec<YtDlpSubtitle>>;
 
+/// Chapter marker from yt-dlp's `chapters` array. Times are in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YtDlpChapter {
+    pub start_time: f64,
+    #[serde(default)]
+    pub end_time: Option<f64>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct YtDlpVideo {
     pub id: String,
@@ -106,10 +116,26 @@ pub struct YtDlpVideo {
     #[serde(default)]
     pub requested_subtitles: Option<SubtitleMap>,
 
+    // Chapter markers
+    #[serde(default)]
+    pub chapters: Option<Vec<YtDlpChapter>>,
+
     // Playlist info (if it's an item in a playlist)
     pub playlist_index: Option<u32>,
     pub playlist_title: Option<String>,
     pub playlist_id: Option<String>,
+
+    // Live streams
+    #[serde(default)]
+    pub is_live: Option<bool>,
+    #[serde(default)]
+    pub was_live: Option<bool>,
+    /// "is_live", "is_upcoming", "was_live", "post_live" or "not_live".
+    #[serde(default)]
+    pub live_status: Option<String>,
+    /// Unix timestamp of a scheduled premiere or stream start.
+    #[serde(default)]
+    pub release_timestamp: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -137,6 +163,17 @@ pub struct YtDlpPlaylist {
 }
 
 impl YtDlpVideo {
+    /// Whether the stream is broadcasting right now (as opposed to a finished
+    /// broadcast, which downloads like any other video).
+    pub fn is_live_now(&self) -> bool {
+        self.is_live == Some(true) || self.live_status.as_deref() == Some("is_live")
+    }
+
+    /// A premiere or stream that is scheduled but hasn't started yet.
+    pub fn is_upcoming(&self) -> bool {
+        self.live_status.as_deref() == Some("is_upcoming")
+    }
+
     pub fn best_thumbnail(&self) -> Option<String> {
         self.thumbnails
             .as_ref()?
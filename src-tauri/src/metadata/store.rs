@@ -3,8 +3,13 @@ use sea_orm::{
     EntityTrait, QueryFilter, TransactionTrait,
 };
 
-use super::models::{YtDlpOutput, YtDlpPlaylist, YtDlpVideo};
-use crate::entity::{creator, post, source}; // media removed as unused for now
+use super::models::{YtDlpChapter, YtDlpOutput, YtDlpPlaylist, YtDlpVideo};
+use crate::constants::platform_id::LOCAL;
+use crate::entity::{chapter, creator, download_task, media, post, source, sponsor_segment};
+use crate::library::archive;
+
+/// Name of the creator that local posts are filed under.
+const LOCAL_CREATOR_NAME: &str = "Local Files";
 
 /// Saves the fetched metadata to the database.
 /// Returns the ID of the main post created/updated, or the playlist ID.
@@ -31,6 +36,95 @@ pub async fn save_metadata(
     }
 }
 
+/// Saves a post for a local file that matches no platform, under the shared
+/// "Local Files" creator. Returns the ID of the post, existing or created.
+pub async fn save_local_post(
+    db: &DatabaseConnection,
+    external_id: &str,
+    title: &str,
+    original_url: &str,
+    posted_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<i64, DbErr> {
+    let txn = db.begin().await?;
+
+    let existing_creator = creator::Entity::find()
+        .filter(creator::Column::PlatformId.eq(LOCAL))
+        .filter(creator::Column::ExternalId.is_null())
+        .one(&txn)
+        .await?;
+    let creator_id = match existing_creator {
+        Some(c) => c.id,
+        None => {
+            let active = creator::ActiveModel {
+                id: sea_orm::ActiveValue::NotSet,
+                platform_id: Set(LOCAL.to_string()),
+                external_id: Set(None),
+                is_self: Set(false),
+                name: Set(LOCAL_CREATOR_NAME.to_string()),
+                url: Set(String::new()),
+                ..Default::default()
+            };
+            creator::Entity::insert(active).exec(&txn).await?.last_insert_id
+        }
+    };
+
+    let existing_post = post::Entity::find()
+        .filter(post::Column::ExternalId.eq(external_id))
+        .one(&txn)
+        .await?;
+    let post_id = match existing_post {
+        Some(p) => p.id,
+        None => {
+            let active = post::ActiveModel {
+                id: sea_orm::ActiveValue::NotSet,
+                creator_id: Set(creator_id),
+                source_id: Set(None),
+                external_id: Set(external_id.to_string()),
+                title: Set(Some(title.to_string())),
+                description: Set(None),
+                original_url: Set(original_url.to_string()),
+                status: Set("PENDING".to_string()),
+                posted_at: Set(posted_at),
+                raw_json: Set(None),
+                ..Default::default()
+            };
+            post::Entity::insert(active).exec(&txn).await?.last_insert_id
+        }
+    };
+
+    txn.commit().await?;
+
+    Ok(post_id)
+}
+
+/// Hard-deletes a post with its media rows, chapters, SponsorBlock segments and
+/// download tasks in one transaction. Files on disk are left to the caller.
+pub async fn delete_post(db: &DatabaseConnection, post_id: i64) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    media::Entity::delete_many()
+        .filter(media::Column::PostId.eq(post_id))
+        .exec(&txn)
+        .await?;
+    chapter::Entity::delete_many()
+        .filter(chapter::Column::PostId.eq(post_id))
+        .exec(&txn)
+        .await?;
+    sponsor_segment::Entity::delete_many()
+        .filter(sponsor_segment::Column::PostId.eq(post_id))
+        .exec(&txn)
+        .await?;
+    download_task::Entity::delete_many()
+        .filter(download_task::Column::PostId.eq(post_id))
+        .exec(&txn)
+        .await?;
+    post::Entity::delete_by_id(post_id).exec(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
 async fn save_video(db: &DatabaseConnection, v: YtDlpVideo) -> Result<i64, DbErr> {
     let txn = db.begin().await?;
 
@@ -179,6 +273,17 @@ async fn save_playlist(
     Ok(source_id)
 }
 
+/// The platform a video belongs to, judged from the first of its URLs.
+fn video_platform(v: &YtDlpVideo) -> &'static str {
+    v.webpage_url
+        .as_deref()
+        .or(v.url.as_deref())
+        .or(v.uploader_url.as_deref())
+        .or(v.channel_url.as_deref())
+        .and_then(crate::platform::detect_platform)
+        .unwrap_or("unknown")
+}
+
 async fn upsert_creator(db: &impl ConnectionTrait, v: &YtDlpVideo) -> Result<i64, DbErr> {
     let external_id = v
         .uploader_id
@@ -195,15 +300,7 @@ async fn upsert_creator(db: &impl ConnectionTrait, v: &YtDlpVideo) -> Result<i64
         .or_else(|| v.channel_url.clone())
         .unwrap_or_default();
 
-    let platform = v
-        .webpage_url
-        .as_deref()
-        .or(v.url.as_deref())
-        .or(v.uploader_url.as_deref())
-        .or(v.channel_url.as_deref())
-        .and_then(crate::platform::detect_platform)
-        .unwrap_or("unknown")
-        .to_string();
+    let platform = video_platform(v).to_string();
 
     let existing = if let Some(ref ext_id) = external_id {
         creator::Entity::find()
@@ -264,7 +361,7 @@ async fn upsert_post(
         .one(db)
         .await?;
 
-    if let Some(existing_post) = existing {
+    let post_id = if let Some(existing_post) = existing {
         let mut active: post::ActiveModel = existing_post.into();
         active.title = Set(Some(v.title.clone()));
         active.description = Set(v.description.clone());
@@ -272,8 +369,14 @@ async fn upsert_post(
         active.source_id = Set(source_id);
         active.original_url = Set(original_url);
         let updated = active.update(db).await?;
-        Ok(updated.id)
+        updated.id
     } else {
+        // Videos an imported download archive lists were downloaded elsewhere.
+        let status = if archive::is_archived(db, video_platform(v), &external_id).await {
+            archive::POST_ARCHIVED
+        } else {
+            "PENDING"
+        };
         let active = post::ActiveModel {
             id: sea_orm::ActiveValue::NotSet,
             creator_id: Set(creator_id),
@@ -282,15 +385,50 @@ async fn upsert_post(
             title: Set(Some(v.title.clone())),
             description: Set(v.description.clone()),
             original_url: Set(original_url),
-            status: Set("PENDING".to_string()),
+            status: Set(status.to_string()),
             posted_at: Set(parse_date(&v.upload_date)),
             raw_json: Set(raw_json),
             ..Default::default()
         };
 
         let result = post::Entity::insert(active).exec(db).await?;
-        Ok(result.last_insert_id)
+        result.last_insert_id
+    };
+
+    // Flat-playlist entries carry no chapter data; keep whatever was stored before.
+    if let Some(chapters) = &v.chapters {
+        replace_chapters(db, post_id, chapters).await?;
     }
+
+    Ok(post_id)
+}
+
+/// Replace the stored chapter list of a post with `chapters`.
+async fn replace_chapters(
+    db: &impl ConnectionTrait,
+    post_id: i64,
+    chapters: &[YtDlpChapter],
+) -> Result<(), DbErr> {
+    chapter::Entity::delete_many()
+        .filter(chapter::Column::PostId.eq(post_id))
+        .exec(db)
+        .await?;
+
+    if chapters.is_empty() {
+        return Ok(());
+    }
+
+    let rows = chapters.iter().enumerate().map(|(i, c)| chapter::ActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        post_id: Set(post_id),
+        chapter_index: Set(i as i32),
+        start_time: Set(c.start_time),
+        end_time: Set(c.end_time),
+        title: Set(c.title.clone()),
+    });
+    chapter::Entity::insert_many(rows).exec(db).await?;
+
+    Ok(())
 }
 
 fn parse_date(date_str: &Option<String>) -> Option<chrono::DateTime<chrono::Utc>> {
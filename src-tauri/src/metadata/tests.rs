@@ -1,10 +1,10 @@
 #[cfg(test)]
 mod tests {
     use crate::db;
-    use crate::entity::{creator, post};
+    use crate::entity::{chapter, creator, post};
     use crate::metadata::models::{YtDlpOutput, YtDlpPlaylist, YtDlpVideo};
     use crate::metadata::store::save_metadata;
-    use sea_orm::{EntityTrait, QueryFilter, ColumnTrait};
+    use sea_orm::{EntityTrait, QueryFilter, QueryOrder, ColumnTrait};
 
     #[tokio::test]
     async fn test_save_video_metadata() {
@@ -27,6 +27,7 @@ mod tests {
             subtitles: None,
             automatic_captions: None,
             requested_subtitles: None,
+            chapters: None,
             original_url: None,
             url: None,
             channel: None,
@@ -35,6 +36,10 @@ mod tests {
             playlist_index: None,
             playlist_title: None,
             playlist_id: None,
+            is_live: None,
+            was_live: None,
+            live_status: None,
+            release_timestamp: None,
         };
 
         let output = YtDlpOutput::Video(video);
@@ -62,6 +67,56 @@ mod tests {
         assert_eq!(saved_creator.name, "Test Channel");
     }
 
+    #[tokio::test]
+    async fn test_save_video_chapters_replaces_previous() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+
+        let video: YtDlpVideo = serde_json::from_str(
+            r#"{
+                "id": "lecture1",
+                "title": "Lecture",
+                "uploader": "Uni",
+                "uploader_id": "uni1",
+                "webpage_url": "https://youtube.com/watch?v=lecture1",
+                "chapters": [
+                    {"start_time": 0.0, "end_time": 60.0, "title": "Intro"},
+                    {"start_time": 60.0, "end_time": 300.0, "title": "Part 1"},
+                    {"start_time": 300.0, "end_time": 420.0, "title": "Q&A"}
+                ]
+            }"#,
+        )
+        .expect("Should deserialize chapters");
+
+        let post_id = save_metadata(&db, YtDlpOutput::Video(video.clone()), None, None, None, None)
+            .await
+            .expect("Failed to save metadata");
+
+        let chapters = chapter::Entity::find()
+            .filter(chapter::Column::PostId.eq(post_id))
+            .order_by_asc(chapter::Column::ChapterIndex)
+            .all(&db)
+            .await
+            .expect("DB error");
+        assert_eq!(chapters.len(), 3);
+        assert_eq!(chapters[1].title.as_deref(), Some("Part 1"));
+        assert_eq!(chapters[2].start_time, 300.0);
+
+        // Re-fetching with fewer chapters replaces the stored list.
+        let mut refetched = video;
+        refetched.chapters.as_mut().unwrap().truncate(1);
+        save_metadata(&db, YtDlpOutput::Video(refetched), None, None, None, None)
+            .await
+            .expect("Failed to re-save metadata");
+
+        let count = chapter::Entity::find()
+            .filter(chapter::Column::PostId.eq(post_id))
+            .all(&db)
+            .await
+            .expect("DB error")
+            .len();
+        assert_eq!(count, 1);
+    }
+
     #[tokio::test]
     async fn test_save_playlist_metadata() {
         let db = db::init_test_db().await.expect("Failed to init test db");
@@ -85,6 +140,7 @@ mod tests {
             subtitles: None,
             automatic_captions: None,
             requested_subtitles: None,
+            chapters: None,
             original_url: None,
             channel: None,
             channel_id: None,
@@ -92,6 +148,10 @@ mod tests {
             playlist_index: None,
             playlist_title: None,
             playlist_id: None,
+            is_live: None,
+            was_live: None,
+            live_status: None,
+            release_timestamp: None,
         };
 
         let playlist = YtDlpPlaylist {
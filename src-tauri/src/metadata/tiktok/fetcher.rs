@@ -442,10 +442,15 @@ fn tiktok_item_to_video(item: TikTokItem, liked_by_username: &str) -> YtDlpVideo
         subtitles: None,
         automatic_captions: None,
         requested_subtitles: None,
+        chapters: None,
 
         playlist_index: None,
         playlist_title: None,
         playlist_id: None,
+        is_live: None,
+        was_live: None,
+        live_status: None,
+        release_timestamp: None,
     }
 }
 
@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Chapters::Table)
+                    .if_not_exists()
+                    .col(integer(Chapters::Id).auto_increment().primary_key())
+                    .col(integer(Chapters::PostId).not_null())
+                    .col(integer(Chapters::ChapterIndex).not_null())
+                    .col(double(Chapters::StartTime).not_null())
+                    .col(double_null(Chapters::EndTime))
+                    .col(string_null(Chapters::Title))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Chapters::Table, Chapters::PostId)
+                            .to(Posts::Table, Posts::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_chapters_post")
+                    .table(Chapters::Table)
+                    .col(Chapters::PostId)
+                    .col(Chapters::ChapterIndex)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Chapters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Chapters {
+    Table,
+    Id,
+    PostId,
+    ChapterIndex,
+    StartTime,
+    EndTime,
+    Title,
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    Id,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SponsorSegments::Table)
+                    .if_not_exists()
+                    .col(integer(SponsorSegments::Id).auto_increment().primary_key())
+                    .col(integer(SponsorSegments::PostId).not_null())
+                    .col(string(SponsorSegments::Category).not_null())
+                    .col(double(SponsorSegments::StartTime).not_null())
+                    .col(double(SponsorSegments::EndTime).not_null())
+                    .col(string(SponsorSegments::Action).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(SponsorSegments::Table, SponsorSegments::PostId)
+                            .to(Posts::Table, Posts::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sponsor_segments_post")
+                    .table(SponsorSegments::Table)
+                    .col(SponsorSegments::PostId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SponsorSegments::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SponsorSegments {
+    Table,
+    Id,
+    PostId,
+    Category,
+    StartTime,
+    EndTime,
+    Action,
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    Id,
+}
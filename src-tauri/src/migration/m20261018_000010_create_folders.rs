@@ -0,0 +1,407 @@
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::{prelude::*, schema::*};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Folders::Table)
+                    .if_not_exists()
+                    .col(integer(Folders::Id).auto_increment().primary_key())
+                    .col(string(Folders::Name))
+                    .col(string_uniq(Folders::RelativePath))
+                    .col(integer_null(Folders::ParentId))
+                    .col(timestamp(Folders::CreatedAt))
+                    .col(timestamp(Folders::UpdatedAt))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .from(Folders::Table, Folders::ParentId)
+                            .to(Folders::Table, Folders::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Media::Table)
+                    .add_column(ColumnDef::new(Media::FolderId).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DownloadTasks::Table)
+                    .add_column(ColumnDef::new(DownloadTasks::FolderId).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        relativize_media_paths(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        absolutize_media_paths(manager).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DownloadTasks::Table)
+                    .drop_column(DownloadTasks::FolderId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Media::Table)
+                    .drop_column(Media::FolderId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Folders::Table).to_owned())
+            .await
+    }
+}
+
+/// The download root at migration time: the `download_path` setting, else the
+/// OS download folder (what the queue used when these rows were written).
+async fn download_root(manager: &SchemaManager<'_>) -> Result<PathBuf, DbErr> {
+    let db = manager.get_connection();
+    let stmt = Query::select()
+        .column(Settings::Value)
+        .from(Settings::Table)
+        .and_where(Expr::col(Settings::Key).eq("download_path"))
+        .to_owned();
+    let custom = match db.query_one(db.get_database_backend().build(&stmt)).await? {
+        Some(row) => Some(row.try_get::<String>("", "value")?).filter(|v| !v.is_empty()),
+        None => None,
+    };
+
+    Ok(match custom {
+        Some(p) if p.starts_with("~/") || p.starts_with("~\\") => match dirs::home_dir() {
+            Some(home) => home.join(&p[2..]),
+            None => PathBuf::from(p),
+        },
+        Some(p) => PathBuf::from(p),
+        None => dirs::download_dir().unwrap_or(PathBuf::from("downloads")),
+    })
+}
+
+/// The media asset columns, stored like `file_path`: a file name next to the
+/// media file once it lives under the root.
+const ASSETS: [(Media, &str); 5] = [
+    (Media::ThumbnailPath, "thumbnail_path"),
+    (Media::PosterPath, "poster_path"),
+    (Media::PreviewPath, "preview_path"),
+    (Media::SpritePath, "sprite_path"),
+    (Media::SpriteVttPath, "sprite_vtt_path"),
+];
+
+/// Rewrite absolute `media.file_path`s under the download root to a folder row
+/// plus a bare file name, and their assets to names in the same directory
+/// (assets elsewhere are dropped and regenerated). Files outside the root keep
+/// their absolute paths.
+async fn relativize_media_paths(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let backend = db.get_database_backend();
+    let root = download_root(manager).await?;
+
+    let stmt = Query::select()
+        .columns([Media::Id, Media::FilePath])
+        .columns(ASSETS.map(|(column, _)| column))
+        .from(Media::Table)
+        .to_owned();
+    let rows = db.query_all(backend.build(&stmt)).await?;
+
+    let mut folders: HashMap<String, i64> = HashMap::new();
+    for row in rows {
+        let id: i64 = row.try_get("", "id")?;
+        let file_path: String = row.try_get("", "file_path")?;
+        let Ok(relative) = Path::new(&file_path).strip_prefix(&root) else {
+            continue;
+        };
+        let Some(filename) = relative
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+
+        let mut parent_id: Option<i64> = None;
+        let mut relative_path = String::new();
+        for component in relative.parent().into_iter().flat_map(Path::components) {
+            let Component::Normal(name) = component else {
+                continue;
+            };
+            let name = name.to_string_lossy().to_string();
+            if !relative_path.is_empty() {
+                relative_path.push('/');
+            }
+            relative_path.push_str(&name);
+
+            let folder_id = match folders.get(&relative_path) {
+                Some(id) => *id,
+                None => {
+                    let now = chrono::Utc::now();
+                    let insert = Query::insert()
+                        .into_table(Folders::Table)
+                        .columns([
+                            Folders::Name,
+                            Folders::RelativePath,
+                            Folders::ParentId,
+                            Folders::CreatedAt,
+                            Folders::UpdatedAt,
+                        ])
+                        .values_panic([
+                            name.into(),
+                            relative_path.clone().into(),
+                            parent_id.into(),
+                            now.into(),
+                            now.into(),
+                        ])
+                        .to_owned();
+                    let id = db.execute(backend.build(&insert)).await?.last_insert_id() as i64;
+                    folders.insert(relative_path.clone(), id);
+                    id
+                }
+            };
+            parent_id = Some(folder_id);
+        }
+
+        let dir = Path::new(&file_path).parent();
+        let mut update = Query::update()
+            .table(Media::Table)
+            .values([
+                (Media::FilePath, filename.into()),
+                (Media::FolderId, parent_id.into()),
+            ])
+            .and_where(Expr::col(Media::Id).eq(id))
+            .to_owned();
+        for (column, name) in ASSETS {
+            let asset: Option<String> = row.try_get("", name)?;
+            let name = asset.as_deref().map(Path::new).and_then(|asset| {
+                let sibling = asset.parent() == dir;
+                sibling.then(|| asset.file_name())?
+            });
+            update.value(column, name.map(|n| n.to_string_lossy().to_string()));
+        }
+        db.execute(backend.build(&update)).await?;
+    }
+    Ok(())
+}
+
+/// Undo [`relativize_media_paths`]: join bare file names back onto the root.
+async fn absolutize_media_paths(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let backend = db.get_database_backend();
+    let root = download_root(manager).await?;
+
+    let rows = db
+        .query_all(Statement::from_string(
+            backend,
+            "SELECT media.id, media.file_path, media.thumbnail_path, media.poster_path, \
+             media.preview_path, media.sprite_path, media.sprite_vtt_path, \
+             folders.relative_path FROM media \
+             LEFT JOIN folders ON folders.id = media.folder_id",
+        ))
+        .await?;
+
+    for row in rows {
+        let id: i64 = row.try_get("", "id")?;
+        let file_path: String = row.try_get("", "file_path")?;
+        if Path::new(&file_path).is_absolute() {
+            continue;
+        }
+        let folder: Option<String> = row.try_get("", "relative_path")?;
+        let mut absolute = root.clone();
+        absolute.extend(folder.iter().flat_map(|f| f.split('/')));
+        let mut update = Query::update()
+            .table(Media::Table)
+            .and_where(Expr::col(Media::Id).eq(id))
+            .to_owned();
+        for (column, name) in ASSETS {
+            let asset: Option<String> = row.try_get("", name)?;
+            if let Some(asset) = asset.filter(|a| !Path::new(a).is_absolute()) {
+                update.value(column, absolute.join(asset).to_string_lossy().to_string());
+            }
+        }
+        absolute.push(&file_path);
+        update.value(Media::FilePath, absolute.to_string_lossy().to_string());
+        db.execute(backend.build(&update)).await?;
+    }
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+enum Folders {
+    Table,
+    Id,
+    Name,
+    RelativePath,
+    ParentId,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden, Clone, Copy)]
+enum Media {
+    Table,
+    Id,
+    FilePath,
+    FolderId,
+    ThumbnailPath,
+    PosterPath,
+    PreviewPath,
+    SpritePath,
+    SpriteVttPath,
+}
+
+#[derive(DeriveIden)]
+enum DownloadTasks {
+    Table,
+    FolderId,
+}
+
+#[derive(DeriveIden)]
+enum Settings {
+    Table,
+    Key,
+    Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::migration::Migrator;
+    use sea_orm_migration::sea_orm::{ConnectionTrait, Database, Statement};
+    use sea_orm_migration::MigratorTrait;
+
+    #[tokio::test]
+    async fn test_existing_paths_become_relative() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let previous = Migrator::migrations()
+            .iter()
+            .position(|m| m.name() == "m20261018_000010_create_folders")
+            .unwrap();
+        Migrator::up(&db, Some(previous as u32)).await.unwrap();
+
+        let root = std::env::temp_dir().join("vdp-library");
+        let inside = root.join("youtube").join("Creator").join("clip.mp4");
+        let flat = root.join("flat.mp4");
+        let outside = std::env::temp_dir().join("elsewhere").join("other.mp4");
+        let backend = db.get_database_backend();
+        db.execute(Statement::from_sql_and_values(
+            backend,
+            "UPDATE settings SET value = ? WHERE key = 'download_path'",
+            [root.to_string_lossy().to_string().into()],
+        ))
+        .await
+        .unwrap();
+        db.execute(Statement::from_string(
+            backend,
+            "INSERT INTO creators (id, platform_id, external_id, name, url) \
+             VALUES (1, 'youtube', 'c', 'Creator', 'https://example.com')",
+        ))
+        .await
+        .unwrap();
+        db.execute(Statement::from_string(
+            backend,
+            "INSERT INTO posts (id, creator_id, external_id, original_url, status) \
+             VALUES (1, 1, 'p', 'https://example.com/p', 'COMPLETED')",
+        ))
+        .await
+        .unwrap();
+        let thumbnail = inside.with_file_name("clip.thumb_sm.jpg");
+        let stray = root.join("cache").join("flat.poster.jpg");
+        for (path, thumbnail, poster) in [
+            (&inside, Some(&thumbnail), None),
+            (&flat, None, Some(&stray)),
+            (&outside, None, None),
+        ] {
+            let [path, thumbnail, poster] =
+                [Some(path), thumbnail, poster].map(|p| p.map(|p| p.to_string_lossy().to_string()));
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "INSERT INTO media (post_id, type, file_path, thumbnail_path, poster_path, \
+                 order_index) VALUES (1, 'VIDEO', ?, ?, ?, 0)",
+                [path.into(), thumbnail.into(), poster.into()],
+            ))
+            .await
+            .unwrap();
+        }
+
+        Migrator::up(&db, Some(1)).await.unwrap();
+
+        let rows = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT media.file_path, media.thumbnail_path, media.poster_path, \
+                 folders.relative_path FROM media \
+                 LEFT JOIN folders ON folders.id = media.folder_id ORDER BY media.id",
+            ))
+            .await
+            .unwrap();
+        let paths: Vec<[Option<String>; 4]> = rows
+            .iter()
+            .map(|r| {
+                [
+                    "file_path",
+                    "thumbnail_path",
+                    "poster_path",
+                    "relative_path",
+                ]
+                .map(|c| r.try_get("", c).unwrap())
+            })
+            .collect();
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            paths,
+            [
+                [
+                    some("clip.mp4"),
+                    some("clip.thumb_sm.jpg"),
+                    None,
+                    some("youtube/Creator")
+                ],
+                [some("flat.mp4"), None, None, None],
+                [
+                    Some(outside.to_string_lossy().to_string()),
+                    None,
+                    None,
+                    None
+                ],
+            ]
+        );
+
+        Migrator::down(&db, Some(1)).await.unwrap();
+        let row = db
+            .query_one(Statement::from_string(
+                backend,
+                "SELECT file_path, thumbnail_path FROM media WHERE id = 1",
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            row.try_get::<String>("", "file_path").unwrap(),
+            inside.to_string_lossy()
+        );
+        assert_eq!(
+            row.try_get::<String>("", "thumbnail_path").unwrap(),
+            thumbnail.to_string_lossy()
+        );
+    }
+}
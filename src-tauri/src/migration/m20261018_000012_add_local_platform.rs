@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Platform of posts imported from files that couldn't be matched to a site.
+const LOCAL_PLATFORM: &str = "local";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let insert = Query::insert()
+            .into_table(Platforms::Table)
+            .columns([Platforms::Id, Platforms::Name, Platforms::BaseUrl])
+            .values_panic([LOCAL_PLATFORM.into(), "Local Files".into(), "".into()])
+            .on_conflict(OnConflict::column(Platforms::Id).do_nothing().to_owned())
+            .to_owned();
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let delete = Query::delete()
+            .from_table(Platforms::Table)
+            .and_where(Expr::col(Platforms::Id).eq(LOCAL_PLATFORM))
+            .to_owned();
+        manager.exec_stmt(delete).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Platforms {
+    Table,
+    Id,
+    Name,
+    BaseUrl,
+}
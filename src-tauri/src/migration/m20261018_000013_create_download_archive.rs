@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DownloadArchive::Table)
+                    .if_not_exists()
+                    .col(integer(DownloadArchive::Id).auto_increment().primary_key())
+                    .col(string(DownloadArchive::Extractor).not_null())
+                    .col(string(DownloadArchive::ExternalId).not_null())
+                    .col(
+                        timestamp(DownloadArchive::CreatedAt)
+                            .default(Expr::current_timestamp())
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_download_archive_entry")
+                    .table(DownloadArchive::Table)
+                    .col(DownloadArchive::Extractor)
+                    .col(DownloadArchive::ExternalId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DownloadArchive::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DownloadArchive {
+    Table,
+    Id,
+    Extractor,
+    ExternalId,
+    CreatedAt,
+}
@@ -4,6 +4,19 @@ mod m20260217_000001_initial_schema;
 mod m20260217_000002_add_download_stats;
 mod m20260219_000001_add_username_to_sessions;
 mod m20260224_000001_add_avatar_and_error_to_sessions;
+mod m20261018_000001_add_media_preview_assets;
+mod m20261018_000002_add_media_poster_path;
+mod m20261018_000003_add_media_language;
+mod m20261018_000004_create_chapters;
+mod m20261018_000005_create_sponsor_segments;
+mod m20261018_000006_add_task_sections;
+mod m20261018_000007_add_media_loudness;
+mod m20261018_000008_add_task_schedule;
+mod m20261018_000009_add_source_filename_template;
+mod m20261018_000010_create_folders;
+mod m20261018_000011_add_media_status;
+mod m20261018_000012_add_local_platform;
+mod m20261018_000013_create_download_archive;
 
 pub struct Migrator;
 
@@ -15,6 +28,19 @@ impl MigratorTrait for Migrator {
             Box::new(m20260217_000002_add_download_stats::Migration),
             Box::new(m20260219_000001_add_username_to_sessions::Migration),
             Box::new(m20260224_000001_add_avatar_and_error_to_sessions::Migration),
+            Box::new(m20261018_000001_add_media_preview_assets::Migration),
+            Box::new(m20261018_000002_add_media_poster_path::Migration),
+            Box::new(m20261018_000003_add_media_language::Migration),
+            Box::new(m20261018_000004_create_chapters::Migration),
+            Box::new(m20261018_000005_create_sponsor_segments::Migration),
+            Box::new(m20261018_000006_add_task_sections::Migration),
+            Box::new(m20261018_000007_add_media_loudness::Migration),
+            Box::new(m20261018_000008_add_task_schedule::Migration),
+            Box::new(m20261018_000009_add_source_filename_template::Migration),
+            Box::new(m20261018_000010_create_folders::Migration),
+            Box::new(m20261018_000011_add_media_status::Migration),
+            Box::new(m20261018_000012_add_local_platform::Migration),
+            Box::new(m20261018_000013_create_download_archive::Migration),
         ]
     }
 }
@@ -1,12 +1,20 @@
+use crate::download::audio_tags::{self, AudioTags};
+use crate::download::loudnorm;
+use crate::download::sponsorblock::{self, Segment, SponsorBlockConfig};
+use crate::download::transcode;
+use crate::download::worker::DownloadProgressPayload;
 use crate::download::{DownloadError, DownloadResult, DownloadWorker};
-use crate::entity::{download_task, media, post};
+use crate::entity::{creator, download_task, media, post, source};
+use crate::metadata::format_processor::DownloadOptions;
+use crate::metadata::models::YtDlpVideo;
 use crate::AppState;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
 };
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
@@ -23,6 +31,15 @@ const PROGRESS_COMPLETED: f32 = 100.0;
 const MEDIA_TYPE_VIDEO: &str = "VIDEO";
 const MEDIA_TYPE_AUDIO: &str = "AUDIO";
 const MEDIA_TYPE_IMAGE: &str = "IMAGE";
+const MEDIA_TYPE_SUBTITLE: &str = "SUBTITLE";
+const MEDIA_TYPE_METADATA: &str = "METADATA";
+
+/// Minimum interval between transcode progress events.
+const TRANSCODE_PROGRESS_THROTTLE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How long a WAITING task waits before checking again when its event's start
+/// time is unknown or has passed without the event starting.
+const WAIT_RECHECK_SECS: i64 = 300;
 
 /// Base delay for exponential backoff on retries.
 const RETRY_BASE_DELAY_SECS: u64 = 5;
@@ -36,8 +53,16 @@ pub struct DownloadQueue {
     semaphore: Arc<Semaphore>,
     /// Parent cancellation token — cancelling this stops the scheduler + all workers.
     shutdown_token: CancellationToken,
+    /// Cancels background preview generation; a child of `shutdown_token`.
+    preview_token: CancellationToken,
     /// Per-task cancellation tokens keyed by task ID.
     task_tokens: Arc<Mutex<HashMap<i64, CancellationToken>>>,
+    /// Per-task stop tokens keyed by task ID. Stopping a live recording keeps what
+    /// was recorded, unlike cancelling.
+    stop_tokens: Arc<Mutex<HashMap<i64, CancellationToken>>>,
+    /// Tasks whose download finished and that are transcoding, normalizing or
+    /// tagging. They can be cancelled but not paused.
+    post_processing: Arc<Mutex<HashSet<i64>>>,
     /// Global pause flag — when true, the scheduler stops picking up new tasks.
     paused: Arc<AtomicBool>,
     /// Receives live concurrency-limit updates from `update_setting`.
@@ -52,6 +77,15 @@ struct ErrorDetails {
     filename: Option<String>,
 }
 
+/// What ran on a finished download before it was marked completed.
+#[derive(Default)]
+struct PostProcessed {
+    /// Resulting loudness per normalized file, for the media rows created afterwards.
+    loudness: Vec<(PathBuf, f64)>,
+    /// Steps that failed. The download is kept; the completed task shows them.
+    warnings: Vec<String>,
+}
+
 impl ErrorDetails {
     fn from_error(err: &DownloadError) -> Self {
         match err {
@@ -78,6 +112,13 @@ impl ErrorDetails {
                 downloaded: *downloaded_bytes,
                 filename: filename.clone(),
             },
+            DownloadError::Upcoming { .. } => Self {
+                is_cancelled: false,
+                message: "Scheduled event has not started yet".to_string(),
+                total: None,
+                downloaded: 0,
+                filename: None,
+            },
         }
     }
 }
@@ -90,12 +131,16 @@ impl DownloadQueue {
     ///   live limit changes without restarting.
     pub fn new(app_handle: AppHandle, concurrency_rx: watch::Receiver<usize>) -> Self {
         let initial_concurrency = *concurrency_rx.borrow();
+        let shutdown_token = CancellationToken::new();
         Self {
             app_handle,
             notify: Arc::new(Notify::new()),
             semaphore: Arc::new(Semaphore::new(initial_concurrency)),
-            shutdown_token: CancellationToken::new(),
+            preview_token: shutdown_token.child_token(),
+            shutdown_token,
             task_tokens: Arc::new(Mutex::new(HashMap::new())),
+            stop_tokens: Arc::new(Mutex::new(HashMap::new())),
+            post_processing: Arc::new(Mutex::new(HashSet::new())),
             paused: Arc::new(AtomicBool::new(false)),
             concurrency_rx,
         }
@@ -117,12 +162,38 @@ impl DownloadQueue {
         }
     }
 
+    /// Stop recording a running live stream; the recording is finalized and kept.
+    /// Returns false for tasks that aren't recording a live stream.
+    pub async fn stop_recording(&self, task_id: i64) -> bool {
+        let db = &self.app_handle.state::<AppState>().db;
+        if !DownloadWorker::is_live_task(db, task_id).await {
+            return false;
+        }
+        let tokens = self.stop_tokens.lock().await;
+        if let Some(token) = tokens.get(&task_id) {
+            token.cancel();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether a running task is past its download and post-processing the files.
+    pub async fn is_post_processing(&self, task_id: i64) -> bool {
+        self.post_processing.lock().await.contains(&task_id)
+    }
+
     /// Trigger graceful shutdown of the scheduler and all workers.
     pub fn shutdown(&self) {
         tracing::info!("Shutting down download queue...");
         self.shutdown_token.cancel();
     }
 
+    /// Stop background preview generation, killing its ffmpeg, e.g. when the app exits.
+    pub fn stop_previews(&self) {
+        self.preview_token.cancel();
+    }
+
     /// Pause the queue — no new tasks will be picked up.
     pub fn pause_queue(&self) {
         tracing::info!("Queue paused");
@@ -141,6 +212,43 @@ impl DownloadQueue {
         self.paused.load(Ordering::SeqCst)
     }
 
+    /// Move WAITING tasks whose scheduled time has come back to the queue.
+    async fn release_waiting_tasks(&self) {
+        let db = &self.app_handle.state::<AppState>().db;
+
+        match download_task::Entity::update_many()
+            .col_expr(
+                download_task::Column::Status,
+                sea_orm::sea_query::Expr::value("QUEUED"),
+            )
+            .filter(download_task::Column::Status.eq("WAITING"))
+            .filter(download_task::Column::ScheduledAt.lte(Utc::now()))
+            .exec(db)
+            .await
+        {
+            Ok(res) if res.rows_affected > 0 => {
+                tracing::info!("Released {} waiting task(s) to the queue", res.rows_affected);
+            }
+            Err(e) => tracing::error!("Failed to release waiting tasks: {}", e),
+            _ => {}
+        }
+    }
+
+    /// Time until the earliest WAITING task is due, if there is one.
+    async fn next_waiting_wakeup(&self) -> Option<std::time::Duration> {
+        let db = &self.app_handle.state::<AppState>().db;
+
+        let task = download_task::Entity::find()
+            .filter(download_task::Column::Status.eq("WAITING"))
+            .filter(download_task::Column::ScheduledAt.is_not_null())
+            .order_by_asc(download_task::Column::ScheduledAt)
+            .one(db)
+            .await
+            .ok()??;
+        let due = task.scheduled_at?;
+        Some((due - Utc::now()).to_std().unwrap_or_default())
+    }
+
     /// Recover tasks that were left in PROCESSING state (e.g. after a crash).
     async fn recover_stale_tasks(&self) {
         let db = &self.app_handle.state::<AppState>().db;
@@ -221,12 +329,19 @@ impl DownloadQueue {
                 }
             }
 
+            // Premieres and scheduled streams that are due go back to the queue
+            self.release_waiting_tasks().await;
+
             // Check for next queued task
             let task_model = match self.get_next_task().await {
                 Some(task) => task,
                 None => {
+                    // Sleep until a new task arrives or the next waiting task is due
+                    let wakeup = self.next_waiting_wakeup().await;
+                    let timer = tokio::time::sleep(wakeup.unwrap_or_default());
                     tokio::select! {
                         _ = self.notify.notified() => continue,
+                        _ = timer, if wakeup.is_some() => continue,
                         _ = self.shutdown_token.cancelled() => break,
                     }
                 }
@@ -266,6 +381,11 @@ impl DownloadQueue {
             .lock()
             .await
             .insert(task_id, task_token.clone());
+        let stop_token = CancellationToken::new();
+        self.stop_tokens
+            .lock()
+            .await
+            .insert(task_id, stop_token.clone());
 
         // Optimistic locking
         let update_result = download_task::Entity::update_many()
@@ -301,7 +421,7 @@ impl DownloadQueue {
         let queue = self.clone();
         tokio::spawn(async move {
             queue
-                .process_standalone_task(app, task_model, permit, task_token)
+                .process_standalone_task(app, task_model, permit, task_token, stop_token)
                 .await;
         });
 
@@ -327,11 +447,12 @@ impl DownloadQueue {
         task: download_task::Model,
         _permit: OwnedSemaphorePermit, // Holds the semaphore permit until task is dropped
         task_token: CancellationToken,
+        stop_token: CancellationToken,
     ) {
         let task_id = task.id;
         let db = app.state::<AppState>().db.clone();
 
-        let (download_dir, rate_limit) = Self::resolve_download_settings(&app, &db).await;
+        let (download_dir, rate_limit) = Self::resolve_download_settings(&db).await;
 
         if let Ok(false) = tokio::fs::try_exists(&download_dir).await {
             if let Err(e) = tokio::fs::create_dir_all(&download_dir).await {
@@ -345,28 +466,63 @@ impl DownloadQueue {
 
         let worker = DownloadWorker::new(app.clone());
 
-        match worker
+        let mut post_token = None;
+        let result = match worker
             .execute_download(
                 task_id,
                 task.url.clone(),
                 download_dir.clone(),
                 task.format_selection.clone(),
                 rate_limit,
-                task_token,
+                task_token.clone(),
+                stop_token,
                 db.clone(),
             )
             .await
         {
             Ok(res) => {
-                Self::handle_download_success(&app, &db, task_id, &res).await;
+                self.post_processing.lock().await.insert(task_id);
+                let token = Self::post_process_token(&db, task_id, &task_token);
+                let res =
+                    Self::transcode_download(&app, &db, &task, &download_dir, res, &token).await;
+                post_token = Some(token);
+                res
+            }
+            Err(err) => Err(err),
+        };
+        let result = match (result, &post_token) {
+            (Ok(res), Some(token)) => {
+                Self::post_process_download(&app, &db, &task, &download_dir, res, token).await
+            }
+            (result, _) => result.map(|res| (res, PostProcessed::default())),
+        };
+
+        match result {
+            Ok((res, processed)) => {
+                Self::handle_download_success(&app, &db, task_id, &res, &processed.warnings).await;
 
                 // Re-fetch task to get the updated post_id from metadata resolution
                 if let Ok(Some(updated_task)) = download_task::Entity::find_by_id(task_id).one(&db).await {
-                    Self::create_media_and_thumbnails(&app, &db, &updated_task, &download_dir, &res).await;
+                    Self::create_media_and_thumbnails(&app, &db, &updated_task, &download_dir, &res, &processed, &self.preview_token).await;
                 } else {
                     tracing::error!("Failed to fetch updated task {} for media creation", task_id);
                 }
             }
+            // Cancelled or paused while checking whether the event has started.
+            Err(DownloadError::Upcoming { .. }) if task_token.is_cancelled() => {
+                Self::handle_task_cancellation(
+                    &app,
+                    &db,
+                    task_id,
+                    &download_dir,
+                    "Download cancelled".to_string(),
+                    None,
+                )
+                .await;
+            }
+            Err(DownloadError::Upcoming { starts_at }) => {
+                Self::park_waiting_task(&app, &db, &task, starts_at, self.notify.clone()).await;
+            }
             Err(err) => {
                 Self::handle_download_error(
                     &app,
@@ -382,42 +538,59 @@ impl DownloadQueue {
             }
         }
 
+        if let Some(token) = post_token {
+            // Ends its watcher.
+            token.cancel();
+        }
+        self.post_processing.lock().await.remove(&task_id);
         self.task_tokens.lock().await.remove(&task_id);
+        self.stop_tokens.lock().await.remove(&task_id);
     }
 
-    async fn resolve_download_settings(
-        app: &AppHandle,
+    /// Token for the steps after the download: it follows `task_token` when the task
+    /// is cancelled, but not when it's paused. A resumed task starts the download
+    /// over, finds the output already there and would have nothing to process, so a
+    /// pause that lands after the download finished is withdrawn instead and the
+    /// task completes.
+    fn post_process_token(
         db: &DatabaseConnection,
-    ) -> (PathBuf, Option<String>) {
-        use crate::entity::setting::Entity as Setting;
-
-        let download_dir = {
-            let custom_path = Setting::find_by_id("download_path")
-                .one(db)
-                .await
-                .ok()
-                .flatten()
-                .map(|s| s.value)
-                .filter(|v| !v.is_empty());
-
-            match custom_path {
-                Some(p) => {
-                    if p.starts_with("~/") || p.starts_with("~\\") {
-                        if let Some(home) = dirs::home_dir() {
-                            home.join(&p[2..])
-                        } else {
-                            PathBuf::from(p)
-                        }
+        task_id: i64,
+        task_token: &CancellationToken,
+    ) -> CancellationToken {
+        let token = CancellationToken::new();
+        let (db, task_token, watched) = (db.clone(), task_token.clone(), token.clone());
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = watched.cancelled() => {}
+                _ = task_token.cancelled() => {
+                    let withdrawn = download_task::Entity::update_many()
+                        .col_expr(
+                            download_task::Column::Status,
+                            sea_orm::sea_query::Expr::value("PROCESSING"),
+                        )
+                        .filter(download_task::Column::Id.eq(task_id))
+                        .filter(download_task::Column::Status.eq("PAUSED"))
+                        .exec(&db)
+                        .await
+                        .is_ok_and(|res| res.rows_affected > 0);
+                    if withdrawn {
+                        tracing::info!(
+                            "Task {} finished downloading, completing it instead of pausing",
+                            task_id
+                        );
                     } else {
-                        PathBuf::from(p)
+                        watched.cancel();
                     }
                 }
-                None => app
-                    .path()
-                    .download_dir()
-                    .unwrap_or(PathBuf::from("downloads")),
             }
-        };
+        });
+        token
+    }
+
+    async fn resolve_download_settings(db: &DatabaseConnection) -> (PathBuf, Option<String>) {
+        use crate::entity::setting::Entity as Setting;
+
+        let download_dir = crate::library::folders::download_root(db).await;
 
         let rate_limit = {
             Setting::find_by_id("rate_limit")
@@ -431,11 +604,206 @@ impl DownloadQueue {
         (download_dir, rate_limit)
     }
 
+    /// Run the task's transcode profile over the downloaded files, reporting progress
+    /// through `download-progress`. Files that fail to transcode are kept as
+    /// downloaded; cancelling the task stops with `Cancelled`, naming the main file
+    /// as it stands so it's cleaned up.
+    async fn transcode_download(
+        app: &AppHandle,
+        db: &DatabaseConnection,
+        task: &download_task::Model,
+        download_dir: &Path,
+        res: DownloadResult,
+        cancel_token: &CancellationToken,
+    ) -> Result<DownloadResult, DownloadError> {
+        let options = Self::download_options(task);
+        let Some(profile) = transcode::resolve_profile(db, options.as_ref()).await else {
+            return Ok(res);
+        };
+        let Some(filename) = res.filename.clone() else {
+            return Ok(res);
+        };
+        let ffmpeg = match crate::sidecar::get_binary_path(
+            app,
+            crate::sidecar::types::SidecarBinary::Ffmpeg,
+        ) {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::error!("ffmpeg not available, skipping transcode: {}", e);
+                return Ok(res);
+            }
+        };
+        let keep_original = crate::db::get_bool_setting(db, "transcode_keep_original", true).await;
+
+        let mut files = vec![filename];
+        files.extend(res.extra_files.iter().cloned());
+        let mut outputs = Vec::with_capacity(files.len());
+        let mut copies = Vec::new();
+
+        for name in files {
+            if cancel_token.is_cancelled() {
+                return Err(DownloadError::Cancelled {
+                    total_bytes: res.total_bytes,
+                    downloaded_bytes: res.downloaded_bytes,
+                    filename: outputs.into_iter().next().or(Some(name)),
+                });
+            }
+            let input = download_dir.join(&name);
+            if Self::media_type_for(&input) == MEDIA_TYPE_AUDIO && !profile.audio_only {
+                outputs.push(name);
+                continue;
+            }
+
+            tracing::info!("Transcoding {} with profile {}", input.display(), profile.id);
+            let mut last_emit: Option<std::time::Instant> = None;
+            let result = transcode::transcode_file(
+                &ffmpeg,
+                &input,
+                profile,
+                keep_original,
+                cancel_token,
+                |p| {
+                    if last_emit.is_some_and(|t| t.elapsed() < TRANSCODE_PROGRESS_THROTTLE) {
+                        return;
+                    }
+                    last_emit = Some(std::time::Instant::now());
+                    let payload = DownloadProgressPayload {
+                        task_id: task.id,
+                        progress: p.progress,
+                        speed: format!("Transcoding {}", p.speed.unwrap_or_default()),
+                        eta: p
+                            .eta_secs
+                            .map(|s| format!("{:02}:{:02}", s / 60, s % 60))
+                            .unwrap_or_default(),
+                        downloaded_bytes: res.downloaded_bytes,
+                        total_bytes: res.total_bytes,
+                        elapsed_secs: None,
+                    };
+                    if let Err(e) = app.emit("download-progress", &payload) {
+                        tracing::error!("Failed to emit transcode progress: {}", e);
+                    }
+                },
+            )
+            .await;
+
+            match result {
+                Ok(output) => {
+                    // Relative to the download folder, like the names it replaces.
+                    let output_name = output
+                        .strip_prefix(download_dir)
+                        .unwrap_or(&output)
+                        .to_string_lossy()
+                        .to_string();
+                    if keep_original {
+                        outputs.push(name);
+                        copies.push(output_name);
+                    } else {
+                        outputs.push(output_name);
+                    }
+                }
+                Err(_) if cancel_token.is_cancelled() => {
+                    return Err(DownloadError::Cancelled {
+                        total_bytes: res.total_bytes,
+                        downloaded_bytes: res.downloaded_bytes,
+                        filename: outputs.into_iter().next().or(Some(name)),
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("Transcoding {} failed: {}", input.display(), e);
+                    outputs.push(name);
+                }
+            }
+        }
+
+        let mut outputs = outputs.into_iter();
+        let filename = outputs.next();
+        let total_bytes = match filename {
+            Some(ref name) => tokio::fs::metadata(download_dir.join(name))
+                .await
+                .ok()
+                .map(|m| m.len())
+                .or(res.total_bytes),
+            None => res.total_bytes,
+        };
+
+        Ok(DownloadResult {
+            total_bytes,
+            downloaded_bytes: total_bytes.unwrap_or(res.downloaded_bytes),
+            // The checksum only still describes the main file if it wasn't replaced.
+            checksum: res.checksum.filter(|_| filename == res.filename),
+            filename,
+            extra_files: outputs.chain(copies).collect(),
+            sponsor_segments: res.sponsor_segments,
+        })
+    }
+
+    /// Post-process a finished download while its task still runs, so a long
+    /// loudness normalization can be cancelled like a transcode: loudness, then tags
+    /// on extracted audio. `cancel_token` is the one from `post_process_token`, which
+    /// a pause doesn't trigger. Failed steps leave the files as they are and become
+    /// warnings.
+    async fn post_process_download(
+        app: &AppHandle,
+        db: &DatabaseConnection,
+        task: &download_task::Model,
+        download_dir: &Path,
+        res: DownloadResult,
+        cancel_token: &CancellationToken,
+    ) -> Result<(DownloadResult, PostProcessed), DownloadError> {
+        let options = Self::download_options(task);
+        let mut processed = PostProcessed::default();
+        if let Some(target) = loudnorm::resolve_target(db, options.as_ref()).await {
+            Self::normalize_files(
+                app,
+                task.id,
+                download_dir,
+                &res,
+                target,
+                cancel_token,
+                &mut processed,
+            )
+            .await?;
+        }
+
+        if options.as_ref().is_some_and(|o| o.audio_only) {
+            // The post is resolved during the download, after `task` was read.
+            let post_id = download_task::Entity::find_by_id(task.id)
+                .one(db)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|t| t.post_id);
+            if let Some(post_id) = post_id {
+                for name in res.filename.iter().chain(&res.extra_files) {
+                    if cancel_token.is_cancelled() {
+                        return Err(DownloadError::Cancelled {
+                            total_bytes: res.total_bytes,
+                            downloaded_bytes: res.downloaded_bytes,
+                            filename: res.filename.clone(),
+                        });
+                    }
+                    let path = download_dir.join(name);
+                    if Self::media_type_for(&path) != MEDIA_TYPE_AUDIO {
+                        continue;
+                    }
+                    if let Err(e) =
+                        Self::tag_audio_file(app, db, post_id, &path, options.as_ref()).await
+                    {
+                        tracing::warn!("Failed to tag {}: {}", path.display(), e);
+                        processed.warnings.push(e);
+                    }
+                }
+            }
+        }
+        Ok((res, processed))
+    }
+
     async fn handle_download_success(
         app: &AppHandle,
         db: &DatabaseConnection,
         task_id: i64,
         res: &DownloadResult,
+        warnings: &[String],
     ) {
         tracing::info!("Task completed: {}", task_id);
 
@@ -448,7 +816,7 @@ impl DownloadQueue {
             total_bytes: Set(res.total_bytes.map(|b| b as i64)),
             speed: Set(None),
             eta: Set(None),
-            error_message: Set(None),
+            error_message: Set((!warnings.is_empty()).then(|| warnings.join("; "))),
             ..Default::default()
         })
         .exec(db)
@@ -472,6 +840,8 @@ impl DownloadQueue {
         task: &download_task::Model,
         download_dir: &std::path::Path,
         res: &DownloadResult,
+        processed: &PostProcessed,
+        preview_token: &CancellationToken,
     ) {
         if let Some(ref post_id) = task.post_id {
             if let Err(e) = post::Entity::update(post::ActiveModel {
@@ -485,25 +855,33 @@ impl DownloadQueue {
             {
                 tracing::error!("Failed to mark post {} as completed: {}", post_id, e);
             }
+            crate::library::archive::export_configured(db).await;
+
+            if let Some(ref segments) = res.sponsor_segments {
+                Self::record_sponsor_segments(db, task, *post_id, segments).await;
+            }
+
+            let options = Self::download_options(task);
+            let loudness = &processed.loudness;
 
             if let Some(ref fname) = res.filename {
                 let file_path = download_dir.join(fname);
-                let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
-
-                let media_type = match ext.to_lowercase().as_str() {
-                    "mp4" | "webm" | "mkv" | "avi" | "mov" | "flv" => MEDIA_TYPE_VIDEO,
-                    "mp3" | "m4a" | "wav" | "aac" | "ogg" | "opus" => MEDIA_TYPE_AUDIO,
-                    "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" => MEDIA_TYPE_IMAGE,
-                    _ => MEDIA_TYPE_VIDEO,
-                };
+                let media_type = Self::media_type_for(&file_path);
+                // Normalizing or tagging rewrites the file after it was hashed.
+                let rewritten = options.as_ref().is_some_and(|o| o.audio_only)
+                    || loudness.iter().any(|(p, _)| *p == file_path);
+                let (folder_id, stored_path) =
+                    Self::media_location(db, download_dir, &file_path).await;
 
                 let media_model = media::ActiveModel {
                     id: sea_orm::ActiveValue::NotSet,
                     post_id: Set(*post_id),
                     media_type: Set(media_type.to_string()),
-                    file_path: Set(file_path.to_string_lossy().to_string()),
+                    file_path: Set(stored_path),
+                    folder_id: Set(folder_id),
                     order_index: Set(DEFAULT_MEDIA_ORDER_INDEX),
                     file_size: Set(res.total_bytes.map(|b| b as i32)),
+                    checksum: Set(res.checksum.clone().filter(|_| !rewritten)),
                     ..Default::default()
                 };
 
@@ -516,22 +894,53 @@ impl DownloadQueue {
                             file_path.display()
                         );
 
+                        let mut next_order = DEFAULT_MEDIA_ORDER_INDEX + 1;
+                        if !res.extra_files.is_empty() {
+                            let clips =
+                                res.extra_files.iter().map(|c| download_dir.join(c)).collect();
+                            next_order = Self::register_extra_media(
+                                app,
+                                db,
+                                download_dir,
+                                *post_id,
+                                media_type,
+                                clips,
+                                next_order,
+                            )
+                            .await;
+                        }
+                        if options.as_ref().is_some_and(|o| o.split_chapters) {
+                            let pieces =
+                                crate::download::post_process::find_chapter_files(&file_path).await;
+                            next_order = Self::register_extra_media(
+                                app,
+                                db,
+                                download_dir,
+                                *post_id,
+                                media_type,
+                                pieces,
+                                next_order,
+                            )
+                            .await;
+                        }
+                        next_order = Self::register_subtitles(
+                            db,
+                            download_dir,
+                            *post_id,
+                            &file_path,
+                            next_order,
+                        )
+                        .await;
+                        Self::register_info_json(db, download_dir, *post_id, &file_path, next_order)
+                            .await;
+                        Self::record_loudness(db, download_dir, loudness).await;
+
                         if let Ok(ffmpeg) = crate::sidecar::get_binary_path(
                             app,
                             crate::sidecar::types::SidecarBinary::Ffmpeg,
                         ) {
-                            let thumbs = crate::download::post_process::process_thumbnails(
-                                &ffmpeg, &file_path, media_type,
-                            )
-                            .await;
-
-                            if let Err(e) = media::Entity::update(media::ActiveModel {
-                                id: Set(inserted.id),
-                                thumbnail_path: Set(thumbs.thumbnail_path),
-                                ..Default::default()
-                            })
-                            .exec(db)
-                            .await
+                            if let Err(e) =
+                                Self::refresh_thumbnails(db, &ffmpeg, &inserted, &file_path).await
                             {
                                 tracing::error!(
                                     "Failed to update media thumbnail for post {}: {}",
@@ -539,11 +948,465 @@ impl DownloadQueue {
                                     e
                                 );
                             }
+
+                            if media_type == MEDIA_TYPE_VIDEO
+                                && crate::db::get_bool_setting(db, "generate_previews", false).await
+                            {
+                                Self::spawn_preview_generation(
+                                    app.clone(),
+                                    db.clone(),
+                                    ffmpeg,
+                                    inserted,
+                                    file_path.clone(),
+                                    preview_token.clone(),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Store the SponsorBlock segments a download marked or removed, as looked up
+    /// for it, so the UI can show what was cut.
+    async fn record_sponsor_segments(
+        db: &DatabaseConnection,
+        task: &download_task::Model,
+        post_id: i64,
+        segments: &[Segment],
+    ) {
+        let options = Self::download_options(task);
+        let config = SponsorBlockConfig::resolve(db, options.as_ref()).await;
+        if let Err(e) = sponsorblock::store_segments(db, post_id, &config, segments).await {
+            tracing::error!(
+                "Failed to store SponsorBlock segments for post {}: {}",
+                post_id,
+                e
+            );
+        }
+    }
+
+    /// Loudness-normalize every audio/video file of a download to `target` LUFS,
+    /// recording the resulting loudness per file in `processed`. Cancelling the task
+    /// stops with `Cancelled`; a pause lets the normalization finish, since the
+    /// download it would restart finds the file already there and skips it.
+    #[allow(clippy::too_many_arguments)]
+    async fn normalize_files(
+        app: &AppHandle,
+        task_id: i64,
+        download_dir: &Path,
+        res: &DownloadResult,
+        target: f64,
+        cancel_token: &CancellationToken,
+        processed: &mut PostProcessed,
+    ) -> Result<(), DownloadError> {
+        let ffmpeg =
+            match crate::sidecar::get_binary_path(app, crate::sidecar::types::SidecarBinary::Ffmpeg)
+            {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::error!("ffmpeg not available, skipping loudness normalization: {}", e);
+                    processed
+                        .warnings
+                        .push("Loudness normalization skipped: ffmpeg not available".to_string());
+                    return Ok(());
+                }
+            };
+
+        for name in res.filename.iter().chain(&res.extra_files) {
+            let path = download_dir.join(name);
+            if !matches!(Self::media_type_for(&path), MEDIA_TYPE_AUDIO | MEDIA_TYPE_VIDEO) {
+                continue;
+            }
+            let payload = DownloadProgressPayload {
+                task_id,
+                progress: PROGRESS_COMPLETED as f64,
+                speed: "Normalizing loudness".to_string(),
+                eta: String::new(),
+                downloaded_bytes: res.downloaded_bytes,
+                total_bytes: res.total_bytes,
+                elapsed_secs: None,
+            };
+            if let Err(e) = app.emit("download-progress", &payload) {
+                tracing::error!("Failed to emit normalization progress: {}", e);
+            }
+
+            match loudnorm::normalize(&ffmpeg, &path, target, cancel_token).await {
+                Ok(lufs) => processed.loudness.push((path, lufs)),
+                Err(_) if cancel_token.is_cancelled() => {
+                    return Err(DownloadError::Cancelled {
+                        total_bytes: res.total_bytes,
+                        downloaded_bytes: res.downloaded_bytes,
+                        filename: res.filename.clone(),
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!("Loudness normalization failed: {}", e);
+                    processed.warnings.push(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Store normalization results on the media rows of the normalized files,
+    /// refreshing their size since the audio was re-encoded.
+    async fn record_loudness(
+        db: &DatabaseConnection,
+        download_dir: &Path,
+        loudness: &[(PathBuf, f64)],
+    ) {
+        for (path, lufs) in loudness {
+            let file_size = tokio::fs::metadata(path).await.ok().map(|m| m.len() as i32);
+            let (folder_id, stored_path) = Self::media_location(db, download_dir, path).await;
+            if let Err(e) = media::Entity::update_many()
+                .col_expr(media::Column::LoudnessLufs, sea_orm::sea_query::Expr::value(*lufs))
+                .col_expr(media::Column::FileSize, sea_orm::sea_query::Expr::value(file_size))
+                .filter(crate::library::folders::location_filter(folder_id, &stored_path))
+                .exec(db)
+                .await
+            {
+                tracing::error!("Failed to store loudness for {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Normalize an existing media row's file (at `path`) to `target` LUFS, skipping
+    /// it when its stored loudness is already there. Returns the new loudness, or
+    /// None if skipped.
+    pub(crate) async fn normalize_media_loudness(
+        db: &DatabaseConnection,
+        ffmpeg: &Path,
+        m: &media::Model,
+        path: &Path,
+        target: f64,
+    ) -> Result<Option<f64>, String> {
+        if loudnorm::is_normalized(m.loudness_lufs, target) {
+            return Ok(None);
+        }
+        let lufs = loudnorm::normalize(ffmpeg, path, target, &CancellationToken::new()).await?;
+        let file_size = tokio::fs::metadata(path).await.ok().map(|m| m.len() as i32);
+        media::Entity::update(media::ActiveModel {
+            id: Set(m.id),
+            loudness_lufs: Set(Some(lufs)),
+            file_size: Set(file_size),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .map_err(|e| format!("Failed to store loudness for media {}: {}", m.id, e))?;
+        Ok(Some(lufs))
+    }
+
+    /// Write title/artist/album/track/date tags and cover art into an extracted audio file.
+    async fn tag_audio_file(
+        app: &AppHandle,
+        db: &DatabaseConnection,
+        post_id: i64,
+        file_path: &Path,
+        options: Option<&DownloadOptions>,
+    ) -> Result<(), String> {
+        let Ok(Some(post)) = post::Entity::find_by_id(post_id).one(db).await else {
+            return Ok(());
+        };
+        let Some(video) = post
+            .raw_json
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<YtDlpVideo>(json).ok())
+        else {
+            return Ok(());
+        };
+        let creator = creator::Entity::find_by_id(post.creator_id)
+            .one(db)
+            .await
+            .ok()
+            .flatten();
+        let source = match post.source_id {
+            Some(id) => source::Entity::find_by_id(id).one(db).await.ok().flatten(),
+            None => None,
+        };
+        let split_artist_title = match options.and_then(|o| o.parse_artist_title) {
+            Some(v) => v,
+            None => crate::db::get_bool_setting(db, "audio_parse_artist_title", false).await,
+        };
+
+        let tags = AudioTags::from_video(
+            &video,
+            creator.as_ref().map(|c| c.name.as_str()),
+            source.as_ref().map(|s| s.name.as_str()),
+            split_artist_title,
+        );
+        let cover = crate::download::post_process::poster_output_path(file_path);
+
+        let ffmpeg =
+            match crate::sidecar::get_binary_path(app, crate::sidecar::types::SidecarBinary::Ffmpeg)
+            {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::error!("ffmpeg not available, skipping audio tags: {}", e);
+                    return Err("Audio tags skipped: ffmpeg not available".to_string());
+                }
+            };
+        audio_tags::write_tags(&ffmpeg, file_path, &tags, Some(&cover)).await?;
+        tracing::info!("Audio tags written: {}", file_path.display());
+        Ok(())
+    }
+
+    /// How the file at `path` is stored on a media row (see `library::folders::locate`).
+    /// Falls back to the absolute path if its folder can't be recorded.
+    async fn media_location(
+        db: &DatabaseConnection,
+        download_dir: &Path,
+        path: &Path,
+    ) -> (Option<i64>, String) {
+        match crate::library::folders::locate(db, download_dir, path).await {
+            Ok(location) => location,
+            Err(e) => {
+                tracing::error!("Failed to record the folder of {}: {}", path.display(), e);
+                (None, path.to_string_lossy().to_string())
+            }
+        }
+    }
+
+    /// Media type of a downloaded file, by extension.
+    pub(crate) fn media_type_for(file_path: &Path) -> &'static str {
+        let ext = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        match ext.to_lowercase().as_str() {
+            "mp4" | "webm" | "mkv" | "avi" | "mov" | "flv" => MEDIA_TYPE_VIDEO,
+            "mp3" | "m4a" | "wav" | "aac" | "ogg" | "opus" => MEDIA_TYPE_AUDIO,
+            "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" => MEDIA_TYPE_IMAGE,
+            _ => MEDIA_TYPE_VIDEO,
+        }
+    }
+
+    /// The structured options a task was queued with, if it wasn't a raw format string.
+    fn download_options(task: &download_task::Model) -> Option<DownloadOptions> {
+        serde_json::from_str(task.format_selection.as_deref()?).ok()
+    }
+
+    /// Record additional files of a download (further section clips, per-chapter
+    /// pieces from `--split-chapters`) as media rows following the main file.
+    /// Returns the next free order index.
+    async fn register_extra_media(
+        app: &AppHandle,
+        db: &DatabaseConnection,
+        download_dir: &Path,
+        post_id: i64,
+        media_type: &str,
+        pieces: Vec<PathBuf>,
+        first_order_index: i32,
+    ) -> i32 {
+        let ffmpeg =
+            crate::sidecar::get_binary_path(app, crate::sidecar::types::SidecarBinary::Ffmpeg).ok();
+        let mut order_index = first_order_index;
+
+        for path in pieces {
+            let file_size = tokio::fs::metadata(&path).await.ok().map(|m| m.len() as i32);
+            let (folder_id, stored_path) = Self::media_location(db, download_dir, &path).await;
+            let piece = media::ActiveModel {
+                id: sea_orm::ActiveValue::NotSet,
+                post_id: Set(post_id),
+                media_type: Set(media_type.to_string()),
+                file_path: Set(stored_path),
+                folder_id: Set(folder_id),
+                order_index: Set(order_index),
+                file_size: Set(file_size),
+                ..Default::default()
+            };
+
+            match piece.insert(db).await {
+                Ok(inserted) => {
+                    order_index += 1;
+                    if let Some(ref ffmpeg) = ffmpeg {
+                        if let Err(e) =
+                            Self::refresh_thumbnails(db, ffmpeg, &inserted, &path).await
+                        {
+                            tracing::error!(
+                                "Failed to update media thumbnail for post {}: {}",
+                                post_id,
+                                e
+                            );
                         }
                     }
                 }
+                Err(e) => tracing::error!(
+                    "Failed to create media row for post {}: {}",
+                    post_id,
+                    e
+                ),
             }
         }
+
+        order_index
+    }
+
+    /// Record the subtitle files written next to `media_file` as SUBTITLE rows,
+    /// ordered after the main media so they never become the post's cover.
+    /// Returns the next free order index.
+    async fn register_subtitles(
+        db: &DatabaseConnection,
+        download_dir: &Path,
+        post_id: i64,
+        media_file: &Path,
+        first_order_index: i32,
+    ) -> i32 {
+        let subtitles = crate::download::post_process::find_subtitle_files(media_file).await;
+        let next_order_index = first_order_index + subtitles.len() as i32;
+
+        for (i, (path, lang)) in subtitles.into_iter().enumerate() {
+            let file_size = tokio::fs::metadata(&path).await.ok().map(|m| m.len() as i32);
+            let (folder_id, stored_path) = Self::media_location(db, download_dir, &path).await;
+            let subtitle = media::ActiveModel {
+                id: sea_orm::ActiveValue::NotSet,
+                post_id: Set(post_id),
+                media_type: Set(MEDIA_TYPE_SUBTITLE.to_string()),
+                file_path: Set(stored_path),
+                folder_id: Set(folder_id),
+                order_index: Set(first_order_index + i as i32),
+                file_size: Set(file_size),
+                language: Set(Some(lang)),
+                ..Default::default()
+            };
+
+            match subtitle.insert(db).await {
+                Ok(_) => tracing::info!(
+                    "Subtitle registered for post {}: {}",
+                    post_id,
+                    path.display()
+                ),
+                Err(e) => tracing::error!(
+                    "Failed to create subtitle row for post {}: {}",
+                    post_id,
+                    e
+                ),
+            }
+        }
+
+        next_order_index
+    }
+
+    /// Record the `.info.json` written by `--write-info-json` as a METADATA row, so the
+    /// library tracks (and trashes) it along with the media it describes.
+    async fn register_info_json(
+        db: &DatabaseConnection,
+        download_dir: &Path,
+        post_id: i64,
+        media_file: &Path,
+        order_index: i32,
+    ) {
+        let path = crate::download::post_process::info_json_path(media_file);
+        let Ok(metadata) = tokio::fs::metadata(&path).await else {
+            return;
+        };
+        let (folder_id, stored_path) = Self::media_location(db, download_dir, &path).await;
+
+        let info_json = media::ActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            post_id: Set(post_id),
+            media_type: Set(MEDIA_TYPE_METADATA.to_string()),
+            file_path: Set(stored_path),
+            folder_id: Set(folder_id),
+            order_index: Set(order_index),
+            file_size: Set(Some(metadata.len() as i32)),
+            ..Default::default()
+        };
+
+        if let Err(e) = info_json.insert(db).await {
+            tracing::error!("Failed to create info.json row for post {}: {}", post_id, e);
+        }
+    }
+
+    /// Generate hover preview and scrub sprite assets in the background. Detached
+    /// from the download so the queue slot is released immediately, but stopped by
+    /// `cancel` (see [`Self::stop_previews`]).
+    fn spawn_preview_generation(
+        app: AppHandle,
+        db: DatabaseConnection,
+        ffmpeg: PathBuf,
+        media: media::Model,
+        file_path: PathBuf,
+        cancel: CancellationToken,
+    ) {
+        tokio::spawn(async move {
+            let media_id = media.id;
+            let assets =
+                crate::download::preview::generate_preview_assets(&ffmpeg, &file_path, &cancel)
+                    .await;
+            if cancel.is_cancelled() {
+                return;
+            }
+            let name = |path: Option<String>| {
+                path.map(|p| crate::library::folders::asset_name(&media.file_path, Path::new(&p)))
+            };
+
+            if let Err(e) = media::Entity::update(media::ActiveModel {
+                id: Set(media_id),
+                preview_path: Set(name(assets.preview_path)),
+                sprite_path: Set(name(assets.sprite_path)),
+                sprite_vtt_path: Set(name(assets.sprite_vtt_path)),
+                ..Default::default()
+            })
+            .exec(&db)
+            .await
+            {
+                tracing::error!("Failed to store preview assets for media {}: {}", media_id, e);
+                return;
+            }
+
+            if let Err(e) = app.emit("media-previews-ready", media_id) {
+                tracing::warn!(
+                    "Failed to emit media-previews-ready event for media {}: {}",
+                    media_id,
+                    e
+                );
+            }
+        });
+    }
+
+    /// Build the Wall thumbnail for a media row (whose file is at `file_path`) and
+    /// store it, along with the platform poster yt-dlp saved next to the file (if
+    /// any). Returns the new thumbnail path.
+    pub(crate) async fn refresh_thumbnails(
+        db: &DatabaseConnection,
+        ffmpeg: &Path,
+        media: &media::Model,
+        file_path: &Path,
+    ) -> Result<Option<String>, DbErr> {
+        let poster = Some(crate::download::post_process::poster_output_path(file_path))
+            .filter(|p| p.exists());
+        let remote_thumbnail = Self::platform_thumbnail_url(db, media.post_id).await;
+
+        let thumbs = crate::download::post_process::process_thumbnails(
+            ffmpeg,
+            file_path,
+            &media.media_type,
+            poster.as_deref(),
+            remote_thumbnail.as_deref(),
+        )
+        .await;
+
+        media::Entity::update(media::ActiveModel {
+            id: Set(media.id),
+            thumbnail_path: Set(thumbs.thumbnail_path.as_deref().map(|p| {
+                crate::library::folders::asset_name(&media.file_path, Path::new(p))
+            })),
+            poster_path: Set(poster
+                .as_deref()
+                .map(|p| crate::library::folders::asset_name(&media.file_path, p))),
+            ..Default::default()
+        })
+        .exec(db)
+        .await?;
+
+        Ok(thumbs.thumbnail_path)
+    }
+
+    /// The platform's best thumbnail URL for a post, read from its stored `raw_json`.
+    async fn platform_thumbnail_url(db: &DatabaseConnection, post_id: i64) -> Option<String> {
+        let post = post::Entity::find_by_id(post_id).one(db).await.ok().flatten()?;
+        let video: YtDlpVideo = serde_json::from_str(post.raw_json.as_deref()?).ok()?;
+        video.best_thumbnail()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -690,6 +1553,12 @@ impl DownloadQueue {
                     tracing::warn!("Failed to delete file: {}", e);
                 }
             }
+            let poster_path = crate::download::post_process::poster_output_path(&file_path);
+            if matches!(tokio::fs::try_exists(&poster_path).await, Ok(true)) {
+                if let Err(e) = tokio::fs::remove_file(&poster_path).await {
+                    tracing::warn!("Failed to delete poster file: {}", e);
+                }
+            }
         }
 
         if let Err(e) = download_task::Entity::update_many()
@@ -725,6 +1594,88 @@ impl DownloadQueue {
         }
     }
 
+    /// Park a task whose premiere or stream hasn't started as WAITING until
+    /// `starts_at` (or the next recheck). The scheduler's timer requeues it then;
+    /// no download slot is held while waiting.
+    async fn park_waiting_task(
+        app: &AppHandle,
+        db: &DatabaseConnection,
+        task: &download_task::Model,
+        starts_at: Option<i64>,
+        notify: Arc<Notify>,
+    ) {
+        let now = Utc::now();
+        let start = starts_at
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .filter(|t| *t > now);
+        let wake_at = start.unwrap_or(now + chrono::Duration::seconds(WAIT_RECHECK_SECS));
+
+        // The give-up deadline is fixed the first time the task is parked.
+        let deadline = match task.wait_deadline {
+            Some(deadline) => Some(deadline),
+            None => crate::db::get_setting(db, "wait_give_up_hours")
+                .await
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|h| *h > 0)
+                .map(|h| start.unwrap_or(now) + chrono::Duration::hours(h)),
+        };
+        if deadline.is_some_and(|d| wake_at > d) {
+            Self::fail_task(
+                app,
+                db,
+                task.id,
+                task.retries,
+                task.max_retries,
+                "Scheduled event did not start before the give-up deadline",
+            )
+            .await;
+            return;
+        }
+
+        // Only a task still running is parked; one paused or cancelled meanwhile stays so.
+        let parked = download_task::Entity::update_many()
+            .set(download_task::ActiveModel {
+                status: Set("WAITING".to_string()),
+                scheduled_at: Set(Some(wake_at)),
+                wait_deadline: Set(deadline),
+                progress: Set(0.0),
+                speed: Set(None),
+                eta: Set(None),
+                error_message: Set(None),
+                ..Default::default()
+            })
+            .filter(download_task::Column::Id.eq(task.id))
+            .filter(download_task::Column::Status.eq("PROCESSING"))
+            .exec(db)
+            .await;
+        match parked {
+            Ok(res) if res.rows_affected == 0 => {
+                tracing::info!(
+                    "Task {} changed status while checking its start, not parking",
+                    task.id
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::error!("Failed to set task {} to WAITING: {}", task.id, e);
+                return;
+            }
+            Ok(_) => {}
+        }
+        tracing::info!("Task {} waiting for scheduled event until {}", task.id, wake_at);
+
+        if let Err(e) = app.emit("download-waiting", task.id) {
+            tracing::warn!(
+                "Failed to emit download-waiting event for task {}: {}",
+                task.id,
+                e
+            );
+        }
+
+        // Let the scheduler re-arm its timer for the new wake-up time.
+        notify.notify_one();
+    }
+
     async fn handle_task_retry(
         app: &AppHandle,
         db: &DatabaseConnection,
@@ -814,3 +1765,80 @@ impl DownloadQueue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    async fn processing_task(db: &DatabaseConnection) -> i64 {
+        download_task::Entity::insert(download_task::ActiveModel {
+            url: Set("https://www.youtube.com/watch?v=x".to_string()),
+            status: Set("PROCESSING".to_string()),
+            priority: Set(0),
+            progress: Set(PROGRESS_COMPLETED),
+            retries: Set(0),
+            max_retries: Set(3),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .unwrap()
+        .last_insert_id
+    }
+
+    async fn status(db: &DatabaseConnection, task_id: i64) -> String {
+        download_task::Entity::find_by_id(task_id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap()
+            .status
+    }
+
+    #[tokio::test]
+    async fn test_pause_during_transcode_is_withdrawn() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let task_id = processing_task(&db).await;
+        let task_token = CancellationToken::new();
+        let post_token = DownloadQueue::post_process_token(&db, task_id, &task_token);
+
+        // A pause that raced the check in `pause_download_task`.
+        download_task::Entity::update(download_task::ActiveModel {
+            id: Set(task_id),
+            status: Set("PAUSED".to_string()),
+            ..Default::default()
+        })
+        .exec(&db)
+        .await
+        .unwrap();
+        task_token.cancel();
+
+        for _ in 0..100 {
+            if status(&db, task_id).await == "PROCESSING" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        // The transcode keeps going, and there is no PAUSED task to resume and
+        // download again.
+        assert_eq!(status(&db, task_id).await, "PROCESSING");
+        assert!(!post_token.is_cancelled());
+        post_token.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_during_transcode_stops_it() {
+        let db = db::init_test_db().await.expect("Failed to init test db");
+        let task_id = processing_task(&db).await;
+        let task_token = CancellationToken::new();
+        let post_token = DownloadQueue::post_process_token(&db, task_id, &task_token);
+
+        task_token.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(1), post_token.cancelled())
+            .await
+            .expect("post-processing wasn't cancelled");
+        assert_eq!(status(&db, task_id).await, "PROCESSING");
+    }
+}
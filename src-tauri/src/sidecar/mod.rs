@@ -74,6 +74,7 @@ pub async fn setup_sidecars(handle: &AppHandle) -> Result<(), SidecarError> {
         SidecarBinary::YtDlp,
         SidecarBinary::Ffmpeg,
         SidecarBinary::Deno,
+        SidecarBinary::Aria2c,
     ] {
         let name = binary.display_name(); // "yt-dlp" or "ffmpeg"
         let target_filename = if cfg!(windows) {
@@ -203,23 +204,40 @@ pub fn get_binary_path(handle: &AppHandle, binary: SidecarBinary) -> Result<Path
         name.to_string()
     };
 
-    let path = app_data_dir.join("binaries").join(filename);
-    if !path.exists() {
-        return Err(SidecarError::BinaryNotFound(path.display().to_string()));
+    let path = app_data_dir.join("binaries").join(&filename);
+    if path.exists() {
+        return Ok(path);
     }
-    Ok(path)
+    // aria2c is optional and not bundled: use the system's copy if there is one.
+    if binary == SidecarBinary::Aria2c {
+        if let Some(found) = find_on_path(&filename) {
+            return Ok(found);
+        }
+    }
+    Err(SidecarError::BinaryNotFound(path.display().to_string()))
+}
+
+/// The first `filename` in a `PATH` directory.
+fn find_on_path(filename: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(filename))
+        .find(|candidate| candidate.is_file())
 }
 
-/// Run a full health check on both sidecar binaries.
+/// Run a full health check on all sidecar binaries.
 pub async fn check_all(handle: &AppHandle) -> SidecarStatus {
     let yt_dlp = check_one(handle, SidecarBinary::YtDlp).await;
     let ffmpeg = check_one(handle, SidecarBinary::Ffmpeg).await;
     let deno = check_one(handle, SidecarBinary::Deno).await;
+    // Optional: downloads fall back to yt-dlp's own downloader without it.
+    let aria2c = check_one(handle, SidecarBinary::Aria2c).await;
 
     tracing::info!(
         yt_dlp_available = yt_dlp.available,
         ffmpeg_available = ffmpeg.available,
         deno_available = deno.available,
+        aria2c_available = aria2c.available,
         "Sidecar health check complete"
     );
 
@@ -227,6 +245,7 @@ pub async fn check_all(handle: &AppHandle) -> SidecarStatus {
         yt_dlp,
         ffmpeg,
         deno,
+        aria2c,
     }
 }
 
@@ -283,6 +302,14 @@ fn parse_version(binary: SidecarBinary, raw: &str) -> Result<String, SidecarErro
                 .unwrap_or(first_line);
             Ok(version.to_string())
         }
+        // aria2c --version -> "aria2 version 1.37.0"
+        SidecarBinary::Aria2c => {
+            let version = first_line
+                .strip_prefix("aria2 version ")
+                .and_then(|s| s.split_whitespace().next())
+                .unwrap_or(first_line);
+            Ok(version.to_string())
+        }
     }
 }
 
@@ -305,6 +332,13 @@ mod tests {
         assert_eq!(result, "N-118193-gc660a3a5f6-20250213");
     }
 
+    #[test]
+    fn test_parse_aria2c_version() {
+        let raw = "aria2 version 1.37.0\nCopyright (C) 2006, 2019 Tatsuhiro Tsujikawa\n";
+        let result = parse_version(SidecarBinary::Aria2c, raw).unwrap();
+        assert_eq!(result, "1.37.0");
+    }
+
     #[test]
     fn test_parse_empty_output_errors() {
         let raw = "";
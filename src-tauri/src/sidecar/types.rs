@@ -7,15 +7,19 @@ pub enum SidecarBinary {
     YtDlp,
     Ffmpeg,
     Deno,
+    /// Optional multi-connection downloader yt-dlp can hand transfers to. It isn't
+    /// bundled; a copy in the binaries folder is used, else one on `PATH`.
+    Aria2c,
 }
 
 impl SidecarBinary {
-    /// The sidecar program name as registered in `tauri.conf.json`.
+    /// The sidecar program name as registered in `tauri.conf.json` (aria2c is not).
     pub fn program_name(&self) -> &'static str {
         match self {
             Self::YtDlp => "binaries/yt-dlp",
             Self::Ffmpeg => "binaries/ffmpeg",
             Self::Deno => "binaries/deno",
+            Self::Aria2c => "binaries/aria2c",
         }
     }
 
@@ -25,6 +29,7 @@ impl SidecarBinary {
             Self::YtDlp => &["--version"],
             Self::Ffmpeg => &["-version"],
             Self::Deno => &["--version"],
+            Self::Aria2c => &["--version"],
         }
     }
 
@@ -34,6 +39,7 @@ impl SidecarBinary {
             Self::YtDlp => "yt-dlp",
             Self::Ffmpeg => "ffmpeg",
             Self::Deno => "deno",
+            Self::Aria2c => "aria2c",
         }
     }
 }
@@ -55,4 +61,5 @@ pub struct SidecarStatus {
     pub yt_dlp: SidecarInfo,
     pub ffmpeg: SidecarInfo,
     pub deno: SidecarInfo,
+    pub aria2c: SidecarInfo,
 }
@@ -8,7 +8,7 @@ async fn test_migrations_apply_and_seed_data() {
         .await
         .expect("Failed to initialize test database");
 
-    // ── Verify all 8 tables exist ─────────────────────────────
+    // ── Verify all 12 tables exist ────────────────────────────
     let tables_query = Statement::from_string(
         DatabaseBackend::Sqlite,
         "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' AND name != 'seaql_migrations' ORDER BY name".to_string(),
@@ -25,23 +25,27 @@ async fn test_migrations_apply_and_seed_data() {
         .collect();
 
     let expected_tables = vec![
+        "chapters",
         "creators",
+        "download_archive",
         "download_tasks",
+        "folders",
         "media",
         "platform_sessions",
         "platforms",
         "posts",
         "settings",
         "sources",
+        "sponsor_segments",
     ];
 
     assert_eq!(
         table_names, expected_tables,
-        "Expected 8 tables, got: {:?}",
+        "Expected 12 tables, got: {:?}",
         table_names
     );
 
-    // ── Verify platforms seed data (4 rows) ───────────────────
+    // ── Verify platforms seed data (5 rows) ───────────────────
     let platforms_query = Statement::from_string(
         DatabaseBackend::Sqlite,
         "SELECT COUNT(*) FROM platforms".to_string(),
@@ -52,7 +56,7 @@ async fn test_migrations_apply_and_seed_data() {
         .expect("Failed to query platforms")
         .expect("No result from platforms count");
     let count: i32 = result.try_get_by_index(0).unwrap();
-    assert_eq!(count, 4, "Expected 4 seeded platforms, got {}", count);
+    assert_eq!(count, 5, "Expected 5 seeded platforms, got {}", count);
 
     // ── Verify settings seed data (12 rows) ───────────────────
     let settings_query = Statement::from_string(
@@ -104,5 +108,5 @@ async fn test_migrations_apply_and_seed_data() {
         .expect("Failed to query indexes")
         .expect("No result from index count");
     let count: i32 = result.try_get_by_index(0).unwrap();
-    assert_eq!(count, 11, "Expected 11 custom indexes, got {}", count);
+    assert_eq!(count, 14, "Expected 14 custom indexes, got {}", count);
 }
@@ -1,16 +1,17 @@
-import React, { useState } from 'react';
+import React, { useEffect, useState } from 'react';
 import { useDownloadManager } from '../hooks/useDownloadManager';
 import {
   X, Download, Loader2, Search, Image as ImageIcon,
   ChevronDown, ChevronUp, Music, Subtitles, Film,
-  Clock, ListVideo, Settings2, Repeat, LayoutGrid, Check
+  Clock, ListVideo, Settings2, Repeat, LayoutGrid, Check, Radio
 } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
 import type {
-  ProcessedMetadata, DownloadOptions
+  ProcessedMetadata, DownloadOptions, SponsorBlockMode, TranscodeProfile
 } from '../types/formats';
 import { formatFileSize } from '../types/formats';
 import { PLATFORM_CONTEXTS, PlatformConfig } from '../features/sources/config/platformContexts';
+import { useSettingsStore } from '../features/settings/SettingsStore';
 
 interface AddDownloadModalProps {
   isOpen: boolean;
@@ -33,6 +34,29 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
   const [selectedAudioId, setSelectedAudioId] = useState<string>(''); // '' = Best Auto
   const [selectedSubtitles, setSelectedSubtitles] = useState<Set<string>>(new Set());
   const [embedSubs, setEmbedSubs] = useState(true);
+  const [subsAsSrt, setSubsAsSrt] = useState(false);
+  const [embedChapters, setEmbedChapters] = useState(false);
+  const [splitChapters, setSplitChapters] = useState(false);
+  const [sectionsText, setSectionsText] = useState(''); // one range or chapter regex per line
+  const [forceKeyframes, setForceKeyframes] = useState(false);
+  const [transcodeProfiles, setTranscodeProfiles] = useState<TranscodeProfile[]>([]);
+  const [transcodeProfile, setTranscodeProfile] = useState<string | undefined>(undefined); // undefined = Default
+  const [sponsorBlockMode, setSponsorBlockMode] = useState<SponsorBlockMode | ''>('');
+  // Archival flags: undefined until toggled, so the settings default applies.
+  const { settings } = useSettingsStore();
+  const [embedMetadata, setEmbedMetadata] = useState<boolean | undefined>(undefined);
+  const [embedThumbnail, setEmbedThumbnail] = useState<boolean | undefined>(undefined);
+  const [writeInfoJson, setWriteInfoJson] = useState<boolean | undefined>(undefined);
+  const [parseArtistTitle, setParseArtistTitle] = useState<boolean | undefined>(undefined);
+  const [loudnorm, setLoudnorm] = useState<boolean | undefined>(undefined);
+  const [liveFromStart, setLiveFromStart] = useState(false);
+
+  useEffect(() => {
+    if (!isOpen || transcodeProfiles.length > 0) return;
+    invoke<TranscodeProfile[]>('get_transcode_profiles')
+      .then(setTranscodeProfiles)
+      .catch(() => setTranscodeProfiles([]));
+  }, [isOpen, transcodeProfiles.length]);
   const [selectedContainer, setSelectedContainer] = useState<string>(''); // '' = Auto
   const [audioOnly, setAudioOnly] = useState(false);
   const [audioExtractFormat, setAudioExtractFormat] = useState('mp3');
@@ -44,6 +68,7 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
 
   // Fallback mode when metadata fetch fails
   const [fallbackMode, setFallbackMode] = useState(false);
+  const [upcoming, setUpcoming] = useState<ProcessedMetadata | null>(null); // scheduled premiere/stream
   const [simpleFormat, setSimpleFormat] = useState('best');
 
   // Source Configuration state
@@ -60,6 +85,20 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
     setSelectedAudioId('');
     setSelectedSubtitles(new Set());
     setEmbedSubs(true);
+    setSubsAsSrt(false);
+    setEmbedChapters(false);
+    setSplitChapters(false);
+    setSectionsText('');
+    setForceKeyframes(false);
+    setTranscodeProfile(undefined);
+    setSponsorBlockMode('');
+    setEmbedMetadata(undefined);
+    setEmbedThumbnail(undefined);
+    setWriteInfoJson(undefined);
+    setParseArtistTitle(undefined);
+    setLoudnorm(undefined);
+    setLiveFromStart(false);
+    setUpcoming(null);
     setSelectedContainer('');
     setAudioOnly(false);
     setAudioExtractFormat('mp3');
@@ -139,6 +178,7 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
         setMetadata(output);
       } else {
         // No formats available and not a playlist — fallback
+        setUpcoming(output.is_upcoming ? output : null);
         setFallbackMode(true);
       }
     } catch (err) {
@@ -199,14 +239,28 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
       formatOption = simpleFormat === 'best' ? undefined : simpleFormat;
     } else if (metadata) {
       // Build DownloadOptions JSON
+      const sections = sectionsText.split('\n').map(s => s.trim()).filter(Boolean);
       const opts: DownloadOptions = {
         format_id: selectedVideoId || undefined,
         audio_format_id: selectedAudioId || undefined,
         audio_only: audioOnly,
         audio_extract_format: audioOnly ? audioExtractFormat : undefined,
+        parse_artist_title: audioOnly ? parseArtistTitle : undefined,
         subtitle_langs: Array.from(selectedSubtitles),
         embed_subs: selectedSubtitles.size > 0 && embedSubs,
+        subs_as_srt: selectedSubtitles.size > 0 && subsAsSrt,
         container: selectedContainer || undefined,
+        embed_chapters: embedChapters,
+        split_chapters: splitChapters,
+        sections: sections.length > 0 ? sections : undefined,
+        force_keyframes_at_cuts: sections.length > 0 && forceKeyframes,
+        transcode_profile: transcodeProfile,
+        loudnorm,
+        sponsorblock_mode: sponsorBlockMode || undefined,
+        embed_metadata: embedMetadata,
+        embed_thumbnail: embedThumbnail,
+        write_info_json: writeInfoJson,
+        live_from_start: metadata.is_live && liveFromStart,
       };
       formatOption = JSON.stringify(opts);
     }
@@ -356,9 +410,22 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
           {/* Fallback Mode */}
           {fallbackMode && (
             <div className="animate-in fade-in slide-in-from-top-2">
-              <div className="px-4 py-3 bg-surface-900 border border-surface-700 rounded-lg mb-4 text-sm text-surface-300">
-                Could not fetch detailed formats. Using basic download mode.
-              </div>
+              {upcoming ? (
+                <div className="px-4 py-3 bg-brand-500/10 border border-brand-500/20 rounded-lg mb-4 text-sm text-brand-300 flex items-start gap-2">
+                  <Clock size={16} className="mt-0.5 shrink-0" />
+                  <span>
+                    <span className="font-medium">{upcoming.title}</span> hasn't started yet
+                    {upcoming.release_timestamp
+                      ? ` (scheduled for ${new Date(upcoming.release_timestamp * 1000).toLocaleString()})`
+                      : ''}
+                    . The download will wait in the queue and start when it begins.
+                  </span>
+                </div>
+              ) : (
+                <div className="px-4 py-3 bg-surface-900 border border-surface-700 rounded-lg mb-4 text-sm text-surface-300">
+                  Could not fetch detailed formats. Using basic download mode.
+                </div>
+              )}
               <label className="block text-sm font-medium text-surface-400 mb-1">Format</label>
               <div className="grid grid-cols-2 gap-2">
                 <button
@@ -514,6 +581,27 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
                 </div>
               ) : (
                 <>
+                  {/* ── Live Stream Notice ── */}
+                  {metadata.is_live && (
+                    <div className="p-3 bg-red-500/10 border border-red-500/20 rounded-lg space-y-2">
+                      <div className="flex items-center gap-2 text-sm font-medium text-red-300">
+                        <Radio size={16} /> Live stream
+                      </div>
+                      <p className="text-xs text-red-300/70">
+                        The stream is recorded until it ends or you stop the recording from the queue.
+                      </p>
+                      <label className="flex items-center gap-2 text-xs text-surface-300 cursor-pointer">
+                        <input
+                          type="checkbox"
+                          checked={liveFromStart}
+                          onChange={(e) => setLiveFromStart(e.target.checked)}
+                          className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                        />
+                        Record from the start of the broadcast (instead of from now)
+                      </label>
+                    </div>
+                  )}
+
                   {/* ── Mode Toggle: Video / Audio Only ── */}
                   <div className="flex gap-2">
                     <button
@@ -648,6 +736,15 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
                           </button>
                         ))}
                       </div>
+                      <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                        <input
+                          type="checkbox"
+                          checked={parseArtistTitle ?? settings.audio_parse_artist_title === 'true'}
+                          onChange={(e) => setParseArtistTitle(e.target.checked)}
+                          className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                        />
+                        Read artist from "Artist - Title" video titles
+                      </label>
                     </div>
                   )}
 
@@ -718,6 +815,17 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
                           Embed subtitles in video file
                         </label>
                       )}
+                      {selectedSubtitles.size > 0 && (
+                        <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                          <input
+                            type="checkbox"
+                            checked={subsAsSrt}
+                            onChange={(e) => setSubsAsSrt(e.target.checked)}
+                            className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                          />
+                          Save subtitle files as SRT
+                        </label>
+                      )}
                     </div>
                   )}
 
@@ -747,6 +855,145 @@ export const AddDownloadModal: React.FC<AddDownloadModalProps> = ({ isOpen, onCl
                       </p>
                     </div>
                   )}
+
+                  {/* ── Chapters ── */}
+                  <div>
+                    <label className="block text-sm font-medium text-surface-400 mb-2">
+                      Chapters
+                    </label>
+                    <label className="flex items-center gap-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={embedChapters}
+                        onChange={(e) => setEmbedChapters(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Embed chapter markers
+                    </label>
+                    <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={splitChapters}
+                        onChange={(e) => setSplitChapters(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Also save each chapter as a separate file
+                    </label>
+                  </div>
+
+                  {/* ── Clip ── */}
+                  <div>
+                    <label className="block text-sm font-medium text-surface-400 mb-2">
+                      Clip (optional)
+                    </label>
+                    <textarea
+                      value={sectionsText}
+                      onChange={(e) => setSectionsText(e.target.value)}
+                      rows={2}
+                      placeholder={'1:02:03-1:02:33\nIntro'}
+                      className="w-full px-3 py-2 bg-surface-900 border border-surface-700 rounded-lg text-xs font-mono text-surface-200 focus:outline-none focus:border-brand-500 resize-y"
+                    />
+                    <p className="text-[11px] text-surface-500 mt-1">
+                      One time range or chapter title per line. Each clip is saved as its own file.
+                    </p>
+                    <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={forceKeyframes}
+                        onChange={(e) => setForceKeyframes(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Precise cuts (re-encodes around the cut points)
+                    </label>
+                  </div>
+
+                  {/* ── Transcode ── */}
+                  <div>
+                    <label className="block text-sm font-medium text-surface-400 mb-2">
+                      Transcode
+                    </label>
+                    <select
+                      value={transcodeProfile ?? '__default'}
+                      onChange={(e) => setTranscodeProfile(e.target.value === '__default' ? undefined : e.target.value)}
+                      className="w-full px-3 py-2 bg-surface-900 border border-surface-700 rounded-lg text-xs text-surface-200 focus:outline-none focus:border-brand-500"
+                    >
+                      <option value="__default">Default (from Settings)</option>
+                      <option value="">None</option>
+                      {transcodeProfiles
+                        .filter(p => !audioOnly || p.audio_only)
+                        .map(p => (
+                          <option key={p.id} value={p.id}>{p.name}</option>
+                        ))}
+                    </select>
+                    <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={loudnorm ?? settings.loudnorm_enabled === 'true'}
+                        onChange={(e) => setLoudnorm(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Normalize loudness ({settings.loudnorm_target || '-16'} LUFS)
+                    </label>
+                  </div>
+
+                  {/* ── SponsorBlock ── */}
+                  <div>
+                    <label className="block text-sm font-medium text-surface-400 mb-2">
+                      SponsorBlock
+                    </label>
+                    <div className="flex gap-2">
+                      {(['', 'off', 'mark', 'remove'] as const).map(m => (
+                        <button
+                          key={m}
+                          type="button"
+                          onClick={() => setSponsorBlockMode(m)}
+                          className={`px-3 py-1.5 rounded-lg text-xs font-medium transition-all ${sponsorBlockMode === m
+                            ? 'bg-brand-600 text-white'
+                            : 'bg-surface-900 border border-surface-700 text-surface-400 hover:bg-surface-700'
+                            }`}
+                        >
+                          {m === '' ? 'Default' : m === 'off' ? 'Off' : m === 'mark' ? 'Mark' : 'Remove'}
+                        </button>
+                      ))}
+                    </div>
+                    <p className="text-[11px] text-surface-500 mt-1">
+                      Categories come from Settings. Mark adds chapters; Remove cuts the segments out.
+                    </p>
+                  </div>
+
+                  {/* ── Archival ── */}
+                  <div>
+                    <label className="block text-sm font-medium text-surface-400 mb-2">
+                      Archival
+                    </label>
+                    <label className="flex items-center gap-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={embedMetadata ?? settings.embed_metadata !== 'false'}
+                        onChange={(e) => setEmbedMetadata(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Embed metadata (title, uploader, date, description, URL)
+                    </label>
+                    <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={embedThumbnail ?? settings.embed_thumbnail === 'true'}
+                        onChange={(e) => setEmbedThumbnail(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Embed thumbnail as cover art
+                    </label>
+                    <label className="flex items-center gap-2 mt-2 text-xs text-surface-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        checked={writeInfoJson ?? settings.write_info_json === 'true'}
+                        onChange={(e) => setWriteInfoJson(e.target.checked)}
+                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                      />
+                      Save .info.json next to the file
+                    </label>
+                  </div>
                 </>
               )}
             </div>
@@ -8,7 +8,10 @@ import {
   RotateCw,
   AlertCircle,
   CheckCircle2,
-  Image as ImageIcon
+  Image as ImageIcon,
+  Scissors,
+  Square,
+  Clock
 } from 'lucide-react';
 
 interface DownloadItemProps {
@@ -16,7 +19,7 @@ interface DownloadItemProps {
 }
 
 export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
-  const { pauseDownload, resumeDownload, cancelDownload, retryDownload } = useDownloadManager();
+  const { pauseDownload, resumeDownload, cancelDownload, retryDownload, stopRecording } = useDownloadManager();
 
   const getStatusColor = (status: string) => {
     switch (status) {
@@ -24,6 +27,7 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
       case DownloadStatus.Failed: return 'text-red-400';
       case DownloadStatus.Paused: return 'text-yellow-400';
       case DownloadStatus.Processing: return 'text-brand-400';
+      case DownloadStatus.Waiting: return 'text-purple-400';
       default: return 'text-zinc-400';
     }
   };
@@ -38,6 +42,7 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
 
   const isActive = task.status === DownloadStatus.Processing || task.status === DownloadStatus.Paused;
   const isPaused = task.status === DownloadStatus.Paused;
+  const isRecording = !!task.is_live && task.status === DownloadStatus.Processing;
   const [isImageLoaded, setIsImageLoaded] = React.useState(false);
 
   return (
@@ -64,7 +69,11 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
 
           {/* Status Overlay on Thumbnail */}
           <div className="absolute inset-0 bg-black/20 group-hover:bg-transparent transition-colors" />
-          {isActive && (
+          {isRecording ? (
+            <div className="absolute bottom-1 right-1 px-1.5 py-0.5 bg-red-600/90 rounded text-[10px] font-bold text-white shadow-sm animate-pulse">
+              REC
+            </div>
+          ) : isActive && (
             <div className="absolute bottom-1 right-1 px-1.5 py-0.5 bg-black/60 backdrop-blur-md rounded text-[10px] font-medium text-white shadow-sm" style={{ fontVariantNumeric: 'tabular-nums' }}>
               {Math.round(task.progress)}%
             </div>
@@ -82,11 +91,19 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
                 {task.url}
               </p>
             )}
+            {task.sections && (
+              <p className="flex items-center gap-1 mt-0.5 text-xs text-brand-400 truncate" title={task.sections}>
+                <Scissors size={12} className="shrink-0" />
+                {task.sections}
+              </p>
+            )}
           </div>
 
           <div className="space-y-2">
-            {/* Progress Bar (Only for active/paused) */}
-            {isActive && (
+            {/* Progress Bar (Only for active/paused; a recording has no end to measure against) */}
+            {isRecording ? (
+              <div className="h-1.5 w-full bg-red-500/60 rounded-full animate-pulse" />
+            ) : isActive && (
               <div className="h-1.5 w-full bg-surface-900 rounded-full overflow-hidden">
                 <div
                   className={`h-full transition-all duration-300 ${isPaused ? 'bg-yellow-500/80' : 'bg-brand-500 shadow-[0_0_10px_rgba(59,130,246,0.5)]'
@@ -103,13 +120,25 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
                 {task.status === DownloadStatus.Paused && <Pause size={12} className="fill-current" />}
                 {task.status === DownloadStatus.Completed && <CheckCircle2 size={12} />}
                 {task.status === DownloadStatus.Failed && <AlertCircle size={12} />}
+                {task.status === DownloadStatus.Waiting && <Clock size={12} />}
                 <span>{task.status}</span>
               </div>
 
+              {task.status === DownloadStatus.Waiting && task.scheduled_at && (
+                <>
+                  <span className="text-surface-600">•</span>
+                  <span title={task.wait_deadline ? `Gives up at ${new Date(task.wait_deadline).toLocaleString()}` : undefined}>
+                    Starts {new Date(task.scheduled_at).toLocaleString()}
+                  </span>
+                </>
+              )}
+
               {isActive && (
                 <>
                   <span className="text-surface-600">•</span>
-                  <span style={{ fontVariantNumeric: 'tabular-nums' }}>{formatBytes(task.downloaded_bytes)} / {formatBytes(task.total_bytes)}</span>
+                  <span style={{ fontVariantNumeric: 'tabular-nums' }}>
+                    {isRecording ? formatBytes(task.downloaded_bytes) : `${formatBytes(task.downloaded_bytes)} / ${formatBytes(task.total_bytes)}`}
+                  </span>
                   {task.speed && (
                     <>
                       <span className="text-surface-600">•</span>
@@ -143,7 +172,17 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
 
         {/* Actions Section */}
         <div className="flex flex-col items-end gap-1 pl-2 border-l border-surface-700/30">
-          {task.status === DownloadStatus.Processing && (
+          {isRecording && (
+            <button
+              onClick={() => stopRecording(task.id)}
+              className="p-2 text-surface-400 hover:text-red-400 hover:bg-surface-700/50 rounded-lg transition-colors"
+              title="Stop recording (keeps what was recorded)"
+            >
+              <Square size={18} />
+            </button>
+          )}
+
+          {((task.status === DownloadStatus.Processing && !isRecording) || task.status === DownloadStatus.Waiting) && (
             <button
               onClick={() => pauseDownload(task.id)}
               className="p-2 text-surface-400 hover:text-yellow-400 hover:bg-surface-700/50 rounded-lg transition-colors"
@@ -173,7 +212,7 @@ export const DownloadItem = React.memo(({ task }: DownloadItemProps) => {
             </button>
           )}
 
-          {(isActive || task.status === DownloadStatus.Queued) && (
+          {(isActive || task.status === DownloadStatus.Queued || task.status === DownloadStatus.Waiting) && (
             <button
               onClick={() => cancelDownload(task.id)}
               className="p-2 text-surface-400 hover:text-red-400 hover:bg-surface-700/50 rounded-lg transition-colors"
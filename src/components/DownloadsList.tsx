@@ -23,7 +23,8 @@ export const DownloadsList: React.FC = () => {
   const activeTasks = useMemo(() => tasks.filter(task =>
     task.status === DownloadStatus.Processing ||
     task.status === DownloadStatus.Paused ||
-    task.status === DownloadStatus.Queued
+    task.status === DownloadStatus.Queued ||
+    task.status === DownloadStatus.Waiting
   ), [tasks]);
 
   const historyTasks = useMemo(() => tasks.filter(task =>
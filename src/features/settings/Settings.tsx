@@ -1,13 +1,16 @@
 import React, { useEffect, useState } from 'react';
 import { useTranslation } from 'react-i18next';
-import { open } from '@tauri-apps/plugin-dialog';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { useSettingsStore } from './SettingsStore';
 import { Folder, Sliders, Languages, HardDrive, Trash2, Moon, Sun, Monitor, Info, Users, RefreshCw, Plus } from 'lucide-react';
 import { AccountCard } from '../../components/settings/AccountCard';
 import { PlatformPickerModal } from '../../components/settings/PlatformPickerModal';
 import { ConnectAccountModal } from '../../components/settings/ConnectAccountModal';
 import { PLATFORMS } from '../../types/auth';
+import type { TranscodeProfile } from '../../types/formats';
+import type { ArchiveImportReport, BackupReport, CatalogCounts, CatalogFormat, CatalogImportReport, ImportProgress, ImportReport, MaintenanceReport, ReconcileReport, RelocateMode, RelocateProgress, RelocateReport, Relocation, RestoreReport } from '../../types/library';
 import { useAuthStatus, useVerifyAllSessions } from '../../hooks/useAuth';
 
 export const Settings = () => {
@@ -22,6 +25,25 @@ export const Settings = () => {
     const [isUpdatingYtDlp, setIsUpdatingYtDlp] = useState(false);
     const [updateMessage, setUpdateMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
     const [ytdlpVersion, setYtdlpVersion] = useState<string | null>(null);
+    const [transcodeProfiles, setTranscodeProfiles] = useState<TranscodeProfile[]>([]);
+    const [templateError, setTemplateError] = useState<string | null>(null);
+    const [relocateTarget, setRelocateTarget] = useState<string | null>(null);
+    const [pendingRelocation, setPendingRelocation] = useState<Relocation | null>(null);
+    const [relocateProgress, setRelocateProgress] = useState<RelocateProgress | null>(null);
+    const [relocateMessage, setRelocateMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
+    const [isScanning, setIsScanning] = useState(false);
+    const [importSource, setImportSource] = useState<string | null>(null);
+    const [importRefetch, setImportRefetch] = useState(false);
+    const [importProgress, setImportProgress] = useState<ImportProgress | null>(null);
+    const [importMessage, setImportMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
+    const [archiveMessage, setArchiveMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
+    const [catalogFormat, setCatalogFormat] = useState<CatalogFormat>('json');
+    const [catalogMessage, setCatalogMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
+    const [restoreSource, setRestoreSource] = useState<string | null>(null);
+    const [isBackingUp, setIsBackingUp] = useState(false);
+    const [backupMessage, setBackupMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
+    const [isMaintaining, setIsMaintaining] = useState(false);
+    const [maintenanceMessage, setMaintenanceMessage] = useState<{ text: string; type: 'success' | 'error' } | null>(null);
 
     const activeSessions = authStatus?.filter(s => s.status !== 'NONE') || [];
     const connectedPlatforms = PLATFORMS.filter(p => activeSessions.some(s => s.platform_id === p.id));
@@ -32,8 +54,27 @@ export const Settings = () => {
         invoke<string>('get_ytdlp_version')
             .then(setYtdlpVersion)
             .catch(() => setYtdlpVersion(null));
+        invoke<TranscodeProfile[]>('get_transcode_profiles')
+            .then(setTranscodeProfiles)
+            .catch(() => setTranscodeProfiles([]));
+        invoke<Relocation | null>('get_pending_relocation')
+            .then(setPendingRelocation)
+            .catch(() => setPendingRelocation(null));
     }, [fetchSettings]);
 
+    useEffect(() => {
+        const unlisten = listen<RelocateProgress>('library-relocate-progress', (event) => {
+            setRelocateProgress(event.payload);
+        });
+        const unlistenImport = listen<ImportProgress>('library-import-progress', (event) => {
+            setImportProgress(event.payload);
+        });
+        return () => {
+            unlisten.then((f) => f());
+            unlistenImport.then((f) => f());
+        };
+    }, []);
+
     const handlePathChange = async () => {
         try {
             const selected = await open({
@@ -49,6 +90,277 @@ export const Settings = () => {
         }
     };
 
+    const handlePickRelocateTarget = async () => {
+        try {
+            const selected = await open({
+                directory: true,
+                multiple: false,
+                title: 'Move Library To',
+            });
+            if (selected && typeof selected === 'string') {
+                setRelocateMessage(null);
+                setRelocateTarget(selected);
+            }
+        } catch (err) {
+            console.error('Failed to select directory:', err);
+        }
+    };
+
+    const handleScanLibrary = async () => {
+        setIsScanning(true);
+        setRelocateMessage(null);
+        try {
+            const report = await invoke<ReconcileReport>('reconcile_library');
+            setRelocateMessage({
+                text: `Checked ${report.checked} files: ${report.missing} missing, ${report.relocated} found moved, ${report.restored} restored, ${report.orphan_assets.length} orphaned thumbnails`,
+                type: report.missing > 0 ? 'error' : 'success',
+            });
+        } catch (error) {
+            setRelocateMessage({ text: `Library scan failed: ${error}`, type: 'error' });
+        } finally {
+            setIsScanning(false);
+        }
+    };
+
+    const handlePickImportSource = async () => {
+        try {
+            const selected = await open({
+                directory: true,
+                multiple: false,
+                title: 'Import Files From',
+            });
+            if (selected && typeof selected === 'string') {
+                setImportMessage(null);
+                setImportSource(selected);
+            }
+        } catch (err) {
+            console.error('Failed to select directory:', err);
+        }
+    };
+
+    const handleImport = async (source: string, mode: RelocateMode) => {
+        setImportSource(null);
+        setImportMessage(null);
+        setImportProgress({ files_done: 0, files_total: 0, current: null });
+        try {
+            const report = await invoke<ImportReport>('import_local_files', { source, mode, refetchMetadata: importRefetch });
+            const failed = report.failed.length > 0 ? `, ${report.failed.length} failed` : '';
+            setImportMessage({
+                text: `Imported ${report.identified} identified and ${report.local} local files, ${report.skipped} already in the library${failed}`,
+                type: report.failed.length > 0 ? 'error' : 'success',
+            });
+        } catch (error) {
+            setImportMessage({ text: `Import failed: ${error}`, type: 'error' });
+        } finally {
+            setImportProgress(null);
+        }
+    };
+
+    const handlePickArchiveFile = async () => {
+        try {
+            const selected = await save({
+                title: 'Keep Download Archive At',
+                defaultPath: settings.download_archive_path || 'archive.txt',
+                filters: [{ name: 'Download archive', extensions: ['txt'] }],
+            });
+            if (selected) {
+                setArchiveMessage(null);
+                await updateSetting('download_archive_path', selected);
+                setArchiveMessage({ text: 'Archive written; it is kept up to date as downloads complete', type: 'success' });
+            }
+        } catch (error) {
+            setArchiveMessage({ text: `Failed to write archive: ${error}`, type: 'error' });
+        }
+    };
+
+    const handleImportArchive = async () => {
+        try {
+            const selected = await open({
+                multiple: false,
+                title: 'Import Download Archive',
+                filters: [{ name: 'Download archive', extensions: ['txt'] }],
+            });
+            if (selected && typeof selected === 'string') {
+                setArchiveMessage(null);
+                const report = await invoke<ArchiveImportReport>('import_download_archive', { path: selected });
+                setArchiveMessage({
+                    text: `Imported ${report.entries} entries, ${report.posts_marked} pending posts marked as downloaded`,
+                    type: 'success',
+                });
+            }
+        } catch (error) {
+            setArchiveMessage({ text: `Archive import failed: ${error}`, type: 'error' });
+        }
+    };
+
+    // JSON is one file; CSV is a folder with one file per table.
+    const pickCatalogPath = async (title: string, saving: boolean) => {
+        if (catalogFormat === 'csv') {
+            const selected = await open({ directory: true, multiple: false, title });
+            return typeof selected === 'string' ? selected : null;
+        }
+        const filters = [{ name: 'Catalog', extensions: ['json'] }];
+        if (saving) {
+            return await save({ title, defaultPath: 'catalog.json', filters });
+        }
+        const selected = await open({ multiple: false, title, filters });
+        return typeof selected === 'string' ? selected : null;
+    };
+
+    const handleExportCatalog = async () => {
+        try {
+            const path = await pickCatalogPath('Export Catalog To', true);
+            if (path) {
+                setCatalogMessage(null);
+                const counts = await invoke<CatalogCounts>('export_catalog', { path, format: catalogFormat });
+                setCatalogMessage({
+                    text: `Exported ${counts.creators} creators, ${counts.sources} sources, ${counts.posts} posts and ${counts.media} media files`,
+                    type: 'success',
+                });
+            }
+        } catch (error) {
+            setCatalogMessage({ text: `Catalog export failed: ${error}`, type: 'error' });
+        }
+    };
+
+    const handleImportCatalog = async () => {
+        try {
+            const path = await pickCatalogPath('Import Catalog', false);
+            if (path) {
+                setCatalogMessage(null);
+                const report = await invoke<CatalogImportReport>('import_catalog', { path, format: catalogFormat });
+                setCatalogMessage({
+                    text: `Added ${report.added.creators} creators, ${report.added.sources} sources, ${report.added.posts} posts and ${report.added.media} media files (${report.existing.posts} posts already in the library)`,
+                    type: 'success',
+                });
+            }
+        } catch (error) {
+            setCatalogMessage({ text: `Catalog import failed: ${error}`, type: 'error' });
+        }
+    };
+
+    const handlePickBackupDir = async () => {
+        try {
+            const selected = await open({
+                directory: true,
+                multiple: false,
+                title: 'Keep Backups In',
+            });
+            if (selected && typeof selected === 'string') {
+                await updateSetting('backup_dir', selected);
+            }
+        } catch (err) {
+            console.error('Failed to select directory:', err);
+        }
+    };
+
+    const handleBackupNow = async () => {
+        try {
+            const path = await save({
+                title: 'Back Up Library To',
+                defaultPath: `videodownloaderpro-backup-${new Date().toISOString().slice(0, 10)}.tar.gz`,
+                filters: [{ name: 'Backup', extensions: ['gz'] }],
+            });
+            if (path) {
+                setBackupMessage(null);
+                setIsBackingUp(true);
+                const report = await invoke<BackupReport>('create_backup', {
+                    path,
+                    includeSessions: settings.backup_include_sessions === 'true',
+                });
+                setBackupMessage({ text: `Backup written (${(report.size / 1048576).toFixed(1)} MB)`, type: 'success' });
+            }
+        } catch (error) {
+            setBackupMessage({ text: `Backup failed: ${error}`, type: 'error' });
+        } finally {
+            setIsBackingUp(false);
+        }
+    };
+
+    const handlePickRestoreSource = async () => {
+        try {
+            const selected = await open({
+                multiple: false,
+                title: 'Restore Backup',
+                filters: [{ name: 'Backup', extensions: ['gz'] }],
+            });
+            if (selected && typeof selected === 'string') {
+                setBackupMessage(null);
+                setRestoreSource(selected);
+            }
+        } catch (err) {
+            console.error('Failed to select backup:', err);
+        }
+    };
+
+    const handleRestore = async (path: string) => {
+        setRestoreSource(null);
+        try {
+            const report = await invoke<RestoreReport>('restore_backup', { path });
+            setBackupMessage({
+                text: `Restoring the backup from ${new Date(report.manifest.created_at).toLocaleString()}; restarting…`,
+                type: 'success',
+            });
+            await invoke('restart_app');
+        } catch (error) {
+            setBackupMessage({ text: `Restore failed: ${error}`, type: 'error' });
+        }
+    };
+
+    const handleRunMaintenance = async () => {
+        setMaintenanceMessage(null);
+        setIsMaintaining(true);
+        try {
+            const report = await invoke<MaintenanceReport>('run_db_maintenance');
+            if (report.integrity_errors.length > 0) {
+                setMaintenanceMessage({
+                    text: `The database is damaged (${report.integrity_errors[0]}); restore a backup`,
+                    type: 'error',
+                });
+                return;
+            }
+            const removed = [
+                [report.media_removed, 'media rows'],
+                [report.chapters_removed + report.segments_removed, 'chapters and segments'],
+                [report.tasks_removed, 'tasks'],
+                [report.posts_removed, 'unused posts'],
+                [report.creators_removed, 'creators'],
+                [report.folders_removed, 'empty folders'],
+            ]
+                .filter(([count]) => (count as number) > 0)
+                .map(([count, what]) => `${count} ${what}`);
+            const detached = report.tasks_detached > 0 ? `, ${report.tasks_detached} tasks detached` : '';
+            const saved = Math.max(0, report.size_before - report.size_after) / 1048576;
+            setMaintenanceMessage({
+                text: `${removed.length > 0 ? `Removed ${removed.join(', ')}` : 'No orphaned rows'}${detached}; ${saved.toFixed(1)} MB reclaimed`,
+                type: 'success',
+            });
+        } catch (error) {
+            setMaintenanceMessage({ text: `Maintenance failed: ${error}`, type: 'error' });
+        } finally {
+            setIsMaintaining(false);
+        }
+    };
+
+    const handleRelocate = async (target: string, mode: RelocateMode) => {
+        setRelocateTarget(null);
+        setRelocateMessage(null);
+        setRelocateProgress({ files_done: 0, files_total: 0, bytes_done: 0, bytes_total: 0, current: null });
+        try {
+            const report = await invoke<RelocateReport>('relocate_library', { target, mode });
+            const missing = report.missing > 0 ? `, ${report.missing} missing` : '';
+            setRelocateMessage({ text: `Library ${mode === 'move' ? 'moved' : 'copied'}: ${report.copied + report.reused} files${missing}`, type: 'success' });
+            await fetchSettings();
+        } catch (error) {
+            setRelocateMessage({ text: `Relocation failed: ${error}`, type: 'error' });
+        } finally {
+            setRelocateProgress(null);
+            invoke<Relocation | null>('get_pending_relocation')
+                .then(setPendingRelocation)
+                .catch(() => setPendingRelocation(null));
+        }
+    };
+
     const handleLanguageChange = async (e: React.ChangeEvent<HTMLSelectElement>) => {
         const lang = e.target.value;
         await updateSetting('language', lang);
@@ -119,11 +431,177 @@ export const Settings = () => {
                                 </div>
                                 <button
                                     onClick={handlePathChange}
-                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                    disabled={relocateProgress !== null}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap disabled:opacity-50"
                                 >
                                     Change
                                 </button>
+                                <button
+                                    onClick={handlePickRelocateTarget}
+                                    disabled={relocateProgress !== null || pendingRelocation !== null}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap disabled:opacity-50"
+                                >
+                                    Move Library
+                                </button>
+                                <button
+                                    onClick={handleScanLibrary}
+                                    disabled={relocateProgress !== null || isScanning}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap disabled:opacity-50"
+                                >
+                                    {isScanning ? 'Scanning...' : 'Scan'}
+                                </button>
+                            </div>
+                            <p className="text-xs text-surface-400">Change only sets where files are looked up and saved; Move Library also brings the existing files along. Scan finds missing and moved files.</p>
+
+                            {relocateTarget && (
+                                <div className="flex items-center gap-2 px-4 py-3 bg-surface-900 border border-surface-700 rounded-xl text-sm">
+                                    <span className="flex-1 truncate text-surface-300">To {relocateTarget}</span>
+                                    <button onClick={() => handleRelocate(relocateTarget, 'move')} className="px-3 py-1.5 bg-brand-600 hover:bg-brand-500 text-white text-xs font-medium rounded-lg transition-colors">Move files</button>
+                                    <button onClick={() => handleRelocate(relocateTarget, 'copy')} className="px-3 py-1.5 bg-surface-800 hover:bg-surface-700 border border-surface-700 text-surface-200 text-xs font-medium rounded-lg transition-colors">Copy files</button>
+                                    <button onClick={() => setRelocateTarget(null)} className="px-3 py-1.5 text-surface-400 hover:text-surface-200 text-xs font-medium transition-colors">Cancel</button>
+                                </div>
+                            )}
+
+                            {pendingRelocation && !relocateProgress && (
+                                <div className="flex items-center gap-2 px-4 py-3 bg-amber-500/10 border border-amber-500/30 rounded-xl text-sm">
+                                    <span className="flex-1 truncate text-amber-300">Unfinished {pendingRelocation.mode} to {pendingRelocation.target}</span>
+                                    <button onClick={() => handleRelocate(pendingRelocation.target, pendingRelocation.mode)} className="px-3 py-1.5 bg-amber-600 hover:bg-amber-500 text-white text-xs font-medium rounded-lg transition-colors">Resume</button>
+                                </div>
+                            )}
+
+                            {relocateProgress && (
+                                <div className="space-y-1">
+                                    <div className="w-full h-2 bg-surface-900 rounded-full overflow-hidden">
+                                        <div
+                                            className="h-full bg-brand-500 transition-all"
+                                            style={{ width: `${relocateProgress.bytes_total > 0 ? (relocateProgress.bytes_done / relocateProgress.bytes_total) * 100 : 0}%` }}
+                                        />
+                                    </div>
+                                    <p className="text-xs text-surface-400 truncate">
+                                        {relocateProgress.files_done} / {relocateProgress.files_total} files{relocateProgress.current ? ` — ${relocateProgress.current}` : ''}
+                                    </p>
+                                </div>
+                            )}
+
+                            {relocateMessage && (
+                                <p className={`text-xs ${relocateMessage.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>{relocateMessage.text}</p>
+                            )}
+                        </div>
+
+                        {/* Import Existing Files */}
+                        <div className="space-y-2">
+                            <div className="flex items-center justify-between gap-4">
+                                <div>
+                                    <label className="text-sm font-medium text-surface-200">Import Existing Files</label>
+                                    <p className="text-xs text-surface-400">Add videos downloaded elsewhere, identified by their .info.json or the [id] in their name.</p>
+                                </div>
+                                <button
+                                    onClick={handlePickImportSource}
+                                    disabled={importProgress !== null || relocateProgress !== null}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap disabled:opacity-50"
+                                >
+                                    Import Folder
+                                </button>
+                            </div>
+
+                            {importSource && (
+                                <div className="flex items-center gap-2 px-4 py-3 bg-surface-900 border border-surface-700 rounded-xl text-sm">
+                                    <span className="flex-1 truncate text-surface-300">From {importSource}</span>
+                                    <label className="flex items-center gap-1.5 text-xs text-surface-300 whitespace-nowrap">
+                                        <input type="checkbox" checked={importRefetch} onChange={(e) => setImportRefetch(e.target.checked)} />
+                                        Fetch missing metadata
+                                    </label>
+                                    <button onClick={() => handleImport(importSource, 'move')} className="px-3 py-1.5 bg-brand-600 hover:bg-brand-500 text-white text-xs font-medium rounded-lg transition-colors">Move files</button>
+                                    <button onClick={() => handleImport(importSource, 'copy')} className="px-3 py-1.5 bg-surface-800 hover:bg-surface-700 border border-surface-700 text-surface-200 text-xs font-medium rounded-lg transition-colors">Copy files</button>
+                                    <button onClick={() => setImportSource(null)} className="px-3 py-1.5 text-surface-400 hover:text-surface-200 text-xs font-medium transition-colors">Cancel</button>
+                                </div>
+                            )}
+
+                            {importProgress && (
+                                <div className="space-y-1">
+                                    <div className="w-full h-2 bg-surface-900 rounded-full overflow-hidden">
+                                        <div
+                                            className="h-full bg-brand-500 transition-all"
+                                            style={{ width: `${importProgress.files_total > 0 ? (importProgress.files_done / importProgress.files_total) * 100 : 0}%` }}
+                                        />
+                                    </div>
+                                    <p className="text-xs text-surface-400 truncate">
+                                        {importProgress.files_done} / {importProgress.files_total} files{importProgress.current ? ` — ${importProgress.current}` : ''}
+                                    </p>
+                                </div>
+                            )}
+
+                            {importMessage && (
+                                <p className={`text-xs ${importMessage.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>{importMessage.text}</p>
+                            )}
+                        </div>
+
+                        {/* yt-dlp Download Archive */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Download Archive</label>
+                            <div className="flex gap-2">
+                                <div className="flex-1 flex items-center gap-3 px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm text-surface-400 select-none overflow-hidden">
+                                    <span className="truncate">{settings.download_archive_path || 'Not exported'}</span>
+                                </div>
+                                <button
+                                    onClick={handlePickArchiveFile}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                >
+                                    Export To
+                                </button>
+                                {settings.download_archive_path && (
+                                    <button
+                                        onClick={() => updateSetting('download_archive_path', '')}
+                                        className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                    >
+                                        Stop
+                                    </button>
+                                )}
+                                <button
+                                    onClick={handleImportArchive}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                >
+                                    Import
+                                </button>
                             </div>
+                            <p className="text-xs text-surface-400">Shares yt-dlp's --download-archive file: imported videos are skipped by syncs, and the exported file lists every completed download.</p>
+                            {archiveMessage && (
+                                <p className={`text-xs ${archiveMessage.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>{archiveMessage.text}</p>
+                            )}
+                        </div>
+
+                        {/* Catalog Export / Import */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Catalog</label>
+                            <div className="flex gap-2">
+                                <div className="flex-1 flex bg-surface-900 rounded-xl p-1 border border-surface-700/50">
+                                    {(['json', 'csv'] as CatalogFormat[]).map((format) => (
+                                        <button
+                                            key={format}
+                                            onClick={() => setCatalogFormat(format)}
+                                            className={`flex-1 px-3 py-1.5 rounded-lg text-sm font-medium transition-all ${catalogFormat === format ? 'bg-surface-700 text-surface-100 shadow-sm' : 'text-surface-400 hover:text-surface-200'}`}
+                                        >
+                                            {format.toUpperCase()}
+                                        </button>
+                                    ))}
+                                </div>
+                                <button
+                                    onClick={handleExportCatalog}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                >
+                                    Export
+                                </button>
+                                <button
+                                    onClick={handleImportCatalog}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                >
+                                    Import
+                                </button>
+                            </div>
+                            <p className="text-xs text-surface-400">Platforms, creators, sources, posts and media with relative paths and checksums. Importing merges into this library by external IDs; media files are copied separately.</p>
+                            {catalogMessage && (
+                                <p className={`text-xs ${catalogMessage.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>{catalogMessage.text}</p>
+                            )}
                         </div>
 
                         {/* Concurrent Downloads */}
@@ -161,6 +639,54 @@ export const Settings = () => {
                             <p className="text-xs text-surface-400">Maximum download speed per active task.</p>
                         </div>
 
+                        {/* Fragment Downloads */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Parallel Fragments</label>
+                            <div className="grid grid-cols-2 gap-2">
+                                {[...PLATFORMS.map(p => [p.id, p.name]), ['other', 'Other sites']].map(([id, name]) => (
+                                    <label key={id} className="flex items-center justify-between gap-2 text-sm text-surface-300">
+                                        {name}
+                                        <select
+                                            value={settings[`concurrent_fragments_${id}`] || '1'}
+                                            onChange={(e) => updateSetting(`concurrent_fragments_${id}`, e.target.value)}
+                                            className="px-3 py-1.5 bg-surface-900 border border-surface-700 rounded-lg text-sm focus:outline-none focus:border-brand-500"
+                                        >
+                                            {['1', '2', '4', '8', '16'].map(n => (
+                                                <option key={n} value={n}>{n}</option>
+                                            ))}
+                                        </select>
+                                    </label>
+                                ))}
+                            </div>
+                            <label className="flex items-center gap-2 text-sm text-surface-300 cursor-pointer">
+                                <input
+                                    type="checkbox"
+                                    checked={settings.use_aria2c === 'true'}
+                                    onChange={(e) => updateSetting('use_aria2c', String(e.target.checked))}
+                                    className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                                />
+                                Download with aria2c (multiple connections per file)
+                            </label>
+                            <p className="text-xs text-surface-400">DASH/HLS fragments fetched at once per download. aria2c is used only if installed.</p>
+                        </div>
+
+                        {/* Premiere / Scheduled Stream Wait */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Scheduled Premieres &amp; Streams</label>
+                            <select
+                                value={settings.wait_give_up_hours || ""}
+                                onChange={(e) => updateSetting('wait_give_up_hours', e.target.value)}
+                                className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                            >
+                                <option value="">Wait until it starts</option>
+                                <option value="1">Give up 1 hour after the scheduled start</option>
+                                <option value="6">Give up 6 hours after the scheduled start</option>
+                                <option value="24">Give up 1 day after the scheduled start</option>
+                                <option value="168">Give up 1 week after the scheduled start</option>
+                            </select>
+                            <p className="text-xs text-surface-400">Downloads of events that haven't started wait without taking a download slot.</p>
+                        </div>
+
                         {/* Language */}
                         <div className="space-y-2">
                             <label className="text-sm font-medium text-surface-200 flex items-center gap-2">
@@ -207,6 +733,230 @@ export const Settings = () => {
                             </select>
                         </div>
 
+                        {/* Backups */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Backups</label>
+                            <div className="flex gap-2">
+                                <select
+                                    value={settings.backup_interval_days || '0'}
+                                    onChange={(e) => updateSetting('backup_interval_days', e.target.value)}
+                                    className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                                >
+                                    <option value="0">No automatic backups</option>
+                                    <option value="1">Back up daily</option>
+                                    <option value="7">Back up weekly</option>
+                                </select>
+                                <select
+                                    value={settings.backup_keep || '7'}
+                                    onChange={(e) => updateSetting('backup_keep', e.target.value)}
+                                    className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                                >
+                                    <option value="3">Keep 3 backups</option>
+                                    <option value="7">Keep 7 backups</option>
+                                    <option value="14">Keep 14 backups</option>
+                                    <option value="30">Keep 30 backups</option>
+                                </select>
+                            </div>
+                            <div className="flex gap-2">
+                                <div className="flex-1 flex items-center gap-3 px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm text-surface-400 select-none overflow-hidden">
+                                    <span className="truncate">{settings.backup_dir || 'App data folder / backups'}</span>
+                                </div>
+                                <button
+                                    onClick={handlePickBackupDir}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                >
+                                    Change
+                                </button>
+                                <button
+                                    onClick={handleBackupNow}
+                                    disabled={isBackingUp}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap disabled:opacity-50"
+                                >
+                                    {isBackingUp ? 'Backing Up…' : 'Back Up Now'}
+                                </button>
+                                <button
+                                    onClick={handlePickRestoreSource}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap"
+                                >
+                                    Restore
+                                </button>
+                            </div>
+                            <label className="flex items-center gap-1.5 text-xs text-surface-300">
+                                <input
+                                    type="checkbox"
+                                    checked={settings.backup_include_sessions === 'true'}
+                                    onChange={(e) => updateSetting('backup_include_sessions', String(e.target.checked))}
+                                />
+                                Include signed-in accounts (encrypted cookies)
+                            </label>
+                            {restoreSource && (
+                                <div className="flex items-center gap-2 px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm">
+                                    <span className="flex-1 truncate text-surface-300">Replace the library with {restoreSource}? The app restarts.</span>
+                                    <button onClick={() => handleRestore(restoreSource)} className="px-3 py-1.5 bg-brand-600 hover:bg-brand-500 text-white text-xs font-medium rounded-lg transition-colors">Restore</button>
+                                    <button onClick={() => setRestoreSource(null)} className="px-3 py-1.5 text-surface-400 hover:text-surface-200 text-xs font-medium transition-colors">Cancel</button>
+                                </div>
+                            )}
+                            <p className="text-xs text-surface-400">A backup holds the database and settings; media files are not included. The replaced database is kept as videodownloaderpro.db.pre-restore.</p>
+                            {backupMessage && (
+                                <p className={`text-xs ${backupMessage.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>{backupMessage.text}</p>
+                            )}
+                        </div>
+
+                        {/* Database Maintenance */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Database Maintenance</label>
+                            <div className="flex gap-2">
+                                <select
+                                    value={settings.maintenance_interval_days || '7'}
+                                    onChange={(e) => updateSetting('maintenance_interval_days', e.target.value)}
+                                    className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                                >
+                                    <option value="0">Never</option>
+                                    <option value="7">Weekly</option>
+                                    <option value="30">Monthly</option>
+                                </select>
+                                <button
+                                    onClick={handleRunMaintenance}
+                                    disabled={isMaintaining}
+                                    className="px-4 py-2.5 bg-surface-900 hover:bg-surface-700 border border-surface-700 text-surface-200 hover:text-surface-100 text-sm font-medium rounded-xl transition-colors whitespace-nowrap disabled:opacity-50"
+                                >
+                                    {isMaintaining ? 'Running…' : 'Run Now'}
+                                </button>
+                            </div>
+                            <p className="text-xs text-surface-400">Checks the database for damage, removes rows left behind by deleted posts, and compacts it.</p>
+                            {maintenanceMessage && (
+                                <p className={`text-xs ${maintenanceMessage.type === 'success' ? 'text-green-400' : 'text-red-400'}`}>{maintenanceMessage.text}</p>
+                            )}
+                        </div>
+
+                        {/* Filename Template */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Filename Template</label>
+                            <input
+                                type="text"
+                                key={settings.filename_template}
+                                defaultValue={settings.filename_template || '{title}'}
+                                onBlur={(e) => {
+                                    setTemplateError(null);
+                                    updateSetting('filename_template', e.target.value)
+                                        .catch((err) => setTemplateError(String(err)));
+                                }}
+                                placeholder="{platform}/{creator}/{upload_date} {title} [{id}]"
+                                className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm font-mono focus:outline-none focus:border-brand-500"
+                            />
+                            {templateError && <p className="text-xs text-red-400">{templateError}</p>}
+                            <p className="text-xs text-surface-400">Folders and file name under the download path; sources can override it. Tokens: {'{platform} {creator} {handle} {source} {feed_type} {upload_date} {year} {id} {title} {playlist_index}'}.</p>
+                        </div>
+
+                        {/* SponsorBlock */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">SponsorBlock</label>
+                            <select
+                                value={settings.sponsorblock_mode || 'off'}
+                                onChange={(e) => updateSetting('sponsorblock_mode', e.target.value)}
+                                className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                            >
+                                <option value="off">Off</option>
+                                <option value="mark">Mark segments as chapters</option>
+                                <option value="remove">Remove segments</option>
+                            </select>
+                            <input
+                                type="text"
+                                key={settings.sponsorblock_categories}
+                                defaultValue={settings.sponsorblock_categories || 'sponsor,selfpromo'}
+                                onBlur={(e) => updateSetting('sponsorblock_categories', e.target.value)}
+                                placeholder="sponsor,selfpromo"
+                                className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm font-mono focus:outline-none focus:border-brand-500"
+                            />
+                            <p className="text-xs text-surface-400">Default for YouTube downloads. Comma-separated categories (sponsor, selfpromo, interaction, intro, outro, preview, music_offtopic, filler).</p>
+                        </div>
+
+                        {/* Transcoding */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Transcode After Download</label>
+                            <select
+                                value={settings.transcode_profile || ''}
+                                onChange={(e) => updateSetting('transcode_profile', e.target.value)}
+                                className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                            >
+                                <option value="">None (keep as downloaded)</option>
+                                {transcodeProfiles.map(p => (
+                                    <option key={p.id} value={p.id}>{p.name}</option>
+                                ))}
+                            </select>
+                            <label className="flex items-center gap-2 text-sm text-surface-300 cursor-pointer">
+                                <input
+                                    type="checkbox"
+                                    checked={settings.transcode_keep_original !== 'false'}
+                                    onChange={(e) => updateSetting('transcode_keep_original', String(e.target.checked))}
+                                    className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                                />
+                                Keep the original file next to the transcoded copy
+                            </label>
+                            <p className="text-xs text-surface-400">For devices that can't play VP9 or AV1. Can be changed per download.</p>
+                        </div>
+
+                        {/* Loudness Normalization */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Loudness Normalization</label>
+                            <label className="flex items-center gap-2 text-sm text-surface-300 cursor-pointer">
+                                <input
+                                    type="checkbox"
+                                    checked={settings.loudnorm_enabled === 'true'}
+                                    onChange={(e) => updateSetting('loudnorm_enabled', String(e.target.checked))}
+                                    className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                                />
+                                Normalize new downloads (EBU R128, two-pass)
+                            </label>
+                            <select
+                                value={settings.loudnorm_target || '-16'}
+                                onChange={(e) => updateSetting('loudnorm_target', e.target.value)}
+                                className="w-full px-4 py-2.5 bg-surface-900 border border-surface-700 rounded-xl text-sm focus:outline-none focus:border-brand-500"
+                            >
+                                <option value="-14">-14 LUFS (music streaming)</option>
+                                <option value="-16">-16 LUFS (podcasts)</option>
+                                <option value="-19">-19 LUFS (spoken word)</option>
+                                <option value="-23">-23 LUFS (EBU R128 broadcast)</option>
+                            </select>
+                        </div>
+
+                        {/* Video Previews */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Video Previews</label>
+                            <label className="flex items-center gap-2 text-sm text-surface-300 cursor-pointer">
+                                <input
+                                    type="checkbox"
+                                    checked={settings.generate_previews === 'true'}
+                                    onChange={(e) => updateSetting('generate_previews', String(e.target.checked))}
+                                    className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                                />
+                                Generate hover previews and seek-bar thumbnails
+                            </label>
+                            <p className="text-xs text-surface-400">Rendered in the background after each video download. Uses extra disk space and CPU.</p>
+                        </div>
+
+                        {/* Archival Metadata */}
+                        <div className="space-y-2">
+                            <label className="text-sm font-medium text-surface-200">Archival Metadata</label>
+                            {([
+                                ['embed_metadata', 'Embed metadata (title, uploader, date, description, URL)'],
+                                ['embed_thumbnail', 'Embed thumbnail as cover art'],
+                                ['write_info_json', 'Save .info.json next to each download'],
+                                ['audio_parse_artist_title', 'Audio: read artist from "Artist - Title" video titles'],
+                            ] as const).map(([key, label]) => (
+                                <label key={key} className="flex items-center gap-2 text-sm text-surface-300 cursor-pointer">
+                                    <input
+                                        type="checkbox"
+                                        checked={settings[key] === 'true'}
+                                        onChange={(e) => updateSetting(key, String(e.target.checked))}
+                                        className="rounded border-surface-600 bg-surface-900 text-brand-500 focus:ring-brand-500 focus:ring-offset-0"
+                                    />
+                                    {label}
+                                </label>
+                            ))}
+                            <p className="text-xs text-surface-400">Defaults for new downloads, so files stay self-describing outside the library.</p>
+                        </div>
+
                         {/* yt-dlp Version */}
                         <div className="pt-4 border-t border-surface-700">
                             <div className="flex items-center justify-between">
@@ -25,7 +25,15 @@ export const useSettingsStore = create<SettingsState>((set) => ({
                 concurrent_downloads: '3',
                 language: 'en',
                 trash_auto_clean_days: '30',
+                backup_interval_days: '0',
+                backup_keep: '7',
+                backup_include_sessions: 'false',
+                maintenance_interval_days: '7',
                 player_volume: '1',
+                embed_metadata: 'true',
+                embed_thumbnail: 'false',
+                write_info_json: 'false',
+                generate_previews: 'false',
             };
 
             set({
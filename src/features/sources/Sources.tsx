@@ -2,7 +2,7 @@ import React, { useEffect, useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { 
     ListVideo, Trash2, RefreshCw, Plus, X, ToggleLeft, ToggleRight,
-    PlaySquare, Smartphone, Radio, Bookmark, Heart, Box, User, Folder, Layers
+    PlaySquare, Smartphone, Radio, Bookmark, Heart, Box, User, Folder, Layers, FileText
 } from 'lucide-react';
 import { ConfirmModal } from '../../components/ui/ConfirmModal';
 import { AddSourceModal } from './components/AddSourceModal';
@@ -21,6 +21,7 @@ export interface SourceResponse {
     post_count: number;
     is_self: boolean;
     avatar_url: string | null;
+    filename_template: string | null;
 }
 
 function formatRelativeTime(isoDate: string): string {
@@ -74,6 +75,9 @@ export const Sources: React.FC = () => {
     const [isConfirmOpen, setIsConfirmOpen] = useState(false);
     const [sourceToDelete, setSourceToDelete] = useState<{ id: number, name: string } | null>(null);
 
+    // Per-source filename template override being edited
+    const [templateEdit, setTemplateEdit] = useState<{ id: number, value: string } | null>(null);
+
     const fetchSources = useCallback(async () => {
         try {
             setIsLoading(true);
@@ -128,6 +132,57 @@ export const Sources: React.FC = () => {
         }
     };
 
+    const handleSaveTemplate = async () => {
+        if (!templateEdit) return;
+        setError(null);
+        try {
+            await invoke('update_source_command', {
+                request: {
+                    source_id: templateEdit.id,
+                    filename_template: templateEdit.value,
+                },
+            });
+            setTemplateEdit(null);
+            fetchSources();
+        } catch (err) {
+            console.error(err);
+            setError(String(err) || 'Failed to update source');
+        }
+    };
+
+    const renderTemplateButton = (source: SourceResponse, size: number) => (
+        <button
+            onClick={() => setTemplateEdit(templateEdit?.id === source.id ? null : { id: source.id, value: source.filename_template || '' })}
+            className={`p-1 rounded transition-colors ${source.filename_template ? 'text-brand-400 hover:text-brand-300' : 'text-surface-500 hover:text-surface-300'}`}
+            title={source.filename_template ? `Filename template: ${source.filename_template}` : 'Filename template (default)'}
+        >
+            <FileText size={size} />
+        </button>
+    );
+
+    const renderTemplateEditor = (source: SourceResponse) => templateEdit?.id === source.id && (
+        <div className="flex items-center gap-2">
+            <input
+                type="text"
+                autoFocus
+                value={templateEdit.value}
+                onChange={(e) => setTemplateEdit({ id: source.id, value: e.target.value })}
+                onKeyDown={(e) => {
+                    if (e.key === 'Enter') handleSaveTemplate();
+                    if (e.key === 'Escape') setTemplateEdit(null);
+                }}
+                placeholder="Use the default filename template"
+                className="flex-1 min-w-0 px-2 py-1 bg-surface-900 border border-surface-700 rounded text-xs font-mono focus:outline-none focus:border-brand-500"
+            />
+            <button
+                onClick={handleSaveTemplate}
+                className="px-2 py-1 text-xs font-medium text-white bg-brand-600 hover:bg-brand-500 rounded transition-colors"
+            >
+                Save
+            </button>
+        </div>
+    );
+
     const { myAccounts, channels, standalone } = React.useMemo(() => {
         const myAccounts: Group[] = [];
         const channels: Group[] = [];
@@ -248,6 +303,7 @@ export const Sources: React.FC = () => {
                         >
                             {group.sources[0].is_active ? <ToggleRight size={22} /> : <ToggleLeft size={22} />}
                         </button>
+                        {renderTemplateButton(group.sources[0], 18)}
                         <button
                             onClick={() => handleDeleteClick(group.sources[0].id, group.sources[0].name)}
                             className="p-1.5 text-surface-500 hover:text-red-400 hover:bg-red-500/10 rounded-lg transition-colors"
@@ -258,13 +314,14 @@ export const Sources: React.FC = () => {
                     </div>
                 )}
             </div>
+            {group.creator_id === null && group.sources.length === 1 && renderTemplateEditor(group.sources[0])}
 
             {/* Feed Pills Area (Only for Channels & My Accounts) */}
             {group.creator_id !== null && (
                 <div className="mt-2 pt-3 border-t border-surface-700/50 flex flex-col gap-2">
                     {group.sources.map(source => (
+                        <React.Fragment key={source.id}>
                         <div 
-                            key={source.id} 
                             className={`flex items-center justify-between p-2.5 rounded-lg bg-surface-900 border transition-all ${
                                 source.is_active ? 'border-surface-600 hover:border-surface-500' : 'border-surface-800 opacity-60 grayscale-[50%]'
                             }`}
@@ -299,6 +356,7 @@ export const Sources: React.FC = () => {
                                 >
                                     {source.is_active ? <ToggleRight size={20} /> : <ToggleLeft size={20} />}
                                 </button>
+                                {renderTemplateButton(source, 16)}
                                 <button
                                     onClick={() => handleDeleteClick(source.id, `${group.name} - ${source.feed_type || source.source_type}`)}
                                     className="p-1 text-surface-500 hover:text-red-400 hover:bg-red-500/10 rounded transition-colors"
@@ -307,6 +365,8 @@ export const Sources: React.FC = () => {
                                 </button>
                             </div>
                         </div>
+                        {renderTemplateEditor(source)}
+                        </React.Fragment>
                     ))}
                 </div>
             )}
@@ -1,11 +1,15 @@
 import { useEffect, useRef, useMemo, useState } from 'react';
 import { useTranslation } from 'react-i18next';
+import { useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { usePostsInfinite } from './api/usePostsInfinite';
 import { WallGrid } from './components/WallGrid';
 import { useResponsiveColumns } from './hooks/useResponsiveColumns';
 import { MediaViewer } from './components/viewer/MediaViewer';
-import { Loader2, Image as ImageIcon } from 'lucide-react';
+import { Loader2, Image as ImageIcon, FileX, X } from 'lucide-react';
 import type { Post } from '../../types/wall';
+import type { ReconcileReport } from '../../types/library';
 
 export function Wall() {
     const { t } = useTranslation();
@@ -15,6 +19,41 @@ export function Wall() {
     const observerRef = useRef<HTMLDivElement>(null);
     const columnCount = useResponsiveColumns();
     const [selectedPost, setSelectedPost] = useState<Post | null>(null);
+    const [reconcile, setReconcile] = useState<ReconcileReport | null>(null);
+    const [isRedownloading, setIsRedownloading] = useState(false);
+    const queryClient = useQueryClient();
+
+    // The library scan (on startup or from Settings) may mark media missing or moved.
+    useEffect(() => {
+        const unlisten = listen<ReconcileReport>('library-reconciled', (event) => {
+            setReconcile(event.payload);
+            if (event.payload.missing > 0 || event.payload.relocated > 0 || event.payload.restored > 0) {
+                queryClient.invalidateQueries({ queryKey: ['posts'] });
+            }
+        });
+        // The download folder watcher followed a rename or noticed a deletion.
+        const unlistenFiles = listen('library-files-changed', () => {
+            queryClient.invalidateQueries({ queryKey: ['posts'] });
+        });
+        return () => {
+            unlisten.then(f => f());
+            unlistenFiles.then(f => f());
+        };
+    }, [queryClient]);
+
+    const handleRedownloadMissing = async () => {
+        if (!reconcile) return;
+        setIsRedownloading(true);
+        try {
+            await invoke<number>('redownload_missing', { postIds: reconcile.missing_post_ids });
+            setReconcile(null);
+            queryClient.invalidateQueries({ queryKey: ['posts'] });
+        } catch (error) {
+            console.error('Failed to queue missing downloads:', error);
+        } finally {
+            setIsRedownloading(false);
+        }
+    };
 
     useEffect(() => {
         const obs = new IntersectionObserver(
@@ -91,6 +130,26 @@ export function Wall() {
 
     return (
         <div className="h-[calc(100vh-140px)] flex flex-col relative">
+            {reconcile && reconcile.missing > 0 && (
+                <div className="flex items-center gap-3 mb-4 px-4 py-3 bg-red-500/10 border border-red-500/30 rounded-xl text-sm">
+                    <FileX size={18} className="text-red-300 flex-shrink-0" />
+                    <span className="flex-1 text-red-200">
+                        {reconcile.missing} file{reconcile.missing === 1 ? '' : 's'} missing from the library
+                        {reconcile.relocated > 0 && `, ${reconcile.relocated} found in a new location`}
+                    </span>
+                    <button
+                        onClick={handleRedownloadMissing}
+                        disabled={isRedownloading || reconcile.missing_post_ids.length === 0}
+                        className="px-3 py-1.5 bg-red-600 hover:bg-red-500 text-white text-xs font-medium rounded-lg transition-colors disabled:opacity-50"
+                    >
+                        Re-download missing
+                    </button>
+                    <button onClick={() => setReconcile(null)} className="p-1 text-red-300 hover:text-red-100 transition-colors">
+                        <X size={16} />
+                    </button>
+                </div>
+            )}
+
             <WallGrid posts={allPosts} columnCount={columnCount} gap={16} onPostClick={setSelectedPost} />
 
             {/* Infinite Scroll Sentinel - Placed at bottom of container */}
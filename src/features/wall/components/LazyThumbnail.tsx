@@ -4,12 +4,15 @@ import { Image as ImageIcon } from 'lucide-react';
 
 export const LazyThumbnail = memo(function LazyThumbnail({
     filePath,
+    previewPath,
     alt,
 }: {
     filePath: string | null;
+    previewPath?: string | null;
     alt: string;
 }) {
     const [isVisible, setIsVisible] = useState(false);
+    const [isHovered, setIsHovered] = useState(false);
     const [isLoaded, setIsLoaded] = useState(false);
     const [hasError, setHasError] = useState(false);
     const ref = useRef<HTMLDivElement>(null);
@@ -29,9 +32,16 @@ export const LazyThumbnail = memo(function LazyThumbnail({
     }, []);
 
     const src = filePath ? convertFileSrc(filePath) : null;
+    // The animated preview is only mounted while hovered so the wall doesn't decode
+    // dozens of animations at once.
+    const previewSrc = isHovered && previewPath ? convertFileSrc(previewPath) : null;
 
     return (
-        <div ref={ref} className="relative w-full aspect-video bg-surface-800 rounded-lg overflow-hidden flex items-center justify-center">
+        <div
+            ref={ref}
+            onMouseEnter={() => setIsHovered(true)}
+            onMouseLeave={() => setIsHovered(false)}
+            className="relative w-full aspect-video bg-surface-800 rounded-lg overflow-hidden flex items-center justify-center">
             {isVisible && src && !hasError ? (
                 <img
                     src={src}
@@ -51,6 +61,13 @@ export const LazyThumbnail = memo(function LazyThumbnail({
                     )}
                 </div>
             )}
+            {previewSrc && (
+                <img
+                    src={previewSrc}
+                    alt=""
+                    className="absolute top-0 left-0 w-full h-full object-cover"
+                />
+            )}
         </div>
     );
 });
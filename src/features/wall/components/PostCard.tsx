@@ -1,11 +1,13 @@
 import { memo } from 'react';
-import { Layers } from 'lucide-react';
+import { Layers, FileX } from 'lucide-react';
 import type { Post } from '../../../types/wall';
 import { LazyThumbnail } from './LazyThumbnail';
 
 export const PostCard = memo(
     function PostCard({ post, onClick }: { post: Post; onClick?: () => void }) {
         const thumb = post.media[0]?.thumbnail_path;
+        const preview = post.media[0]?.preview_path;
+        const missing = post.media.some(m => m.status === 'MISSING');
 
         return (
             <article
@@ -13,7 +15,7 @@ export const PostCard = memo(
                 className="group relative flex flex-col gap-2 rounded-xl bg-surface-800/50 p-2 hover:bg-surface-800 transition-colors border border-transparent hover:border-surface-700 shadow-sm cursor-pointer"
             >
                 <div className="relative w-full rounded-lg overflow-hidden">
-                    <LazyThumbnail filePath={thumb} alt={post.title ?? 'Media'} />
+                    <LazyThumbnail filePath={thumb} previewPath={preview} alt={post.title ?? 'Media'} />
 
                     {post.media.length > 1 && (
                         <div className="absolute top-2 right-2 bg-black/60 backdrop-blur-sm px-2 py-1 rounded-md flex items-center gap-1.5 shadow-sm border border-white/10">
@@ -21,6 +23,13 @@ export const PostCard = memo(
                             <span className="text-xs font-medium text-white">{post.media.length}</span>
                         </div>
                     )}
+
+                    {missing && (
+                        <div className="absolute top-2 left-2 bg-red-900/80 backdrop-blur-sm px-2 py-1 rounded-md flex items-center gap-1.5 shadow-sm border border-red-500/30">
+                            <FileX size={14} className="text-red-200" />
+                            <span className="text-xs font-medium text-red-100">Missing</span>
+                        </div>
+                    )}
                 </div>
 
                 <div className="px-1 pb-1">
@@ -47,5 +56,12 @@ export const PostCard = memo(
             </article>
         );
     },
-    (prev, next) => prev.post.id === next.post.id && prev.post.status === next.post.status
+    (prev, next) =>
+        prev.post.id === next.post.id &&
+        prev.post.status === next.post.status &&
+        prev.post.media.every(
+            (m, i) =>
+                m.status === next.post.media[i]?.status &&
+                m.preview_path === next.post.media[i]?.preview_path
+        )
 );
@@ -1,15 +1,18 @@
 import { useState, useRef, useEffect, MouseEvent as ReactMouseEvent } from 'react';
 import { useSettingsStore } from '../../../settings/SettingsStore';
 import { Play, Pause, Volume2, VolumeX, Maximize, FastForward, Subtitles } from 'lucide-react';
+import type { Chapter, Storyboard } from '../../../../types/wall';
 
 interface CustomVideoPlayerProps {
     src: string;
     poster?: string;
     subtitleSrc?: string;
+    chapters?: Chapter[];
+    storyboard?: Storyboard;
     onError: () => void;
 }
 
-export function CustomVideoPlayer({ src, poster, subtitleSrc, onError }: CustomVideoPlayerProps) {
+export function CustomVideoPlayer({ src, poster, subtitleSrc, chapters = [], storyboard, onError }: CustomVideoPlayerProps) {
     const { settings, updateSetting } = useSettingsStore();
     const savedVolume = parseFloat(settings.player_volume) || 1;
 
@@ -22,6 +25,13 @@ export function CustomVideoPlayer({ src, poster, subtitleSrc, onError }: CustomV
     const [isHoldingToSpeed, setIsHoldingToSpeed] = useState(false);
     const [showControls, setShowControls] = useState(true);
     const [subtitlesEnabled, setSubtitlesEnabled] = useState(true);
+    const [hoverTime, setHoverTime] = useState<number | null>(null);
+
+    const currentChapter = chapters.filter(c => c.start_time <= currentTime).pop();
+    const hoverTile = hoverTime !== null && storyboard
+        ? storyboard.tiles.find(t => hoverTime >= t.start && hoverTime < t.end)
+            ?? storyboard.tiles[storyboard.tiles.length - 1]
+        : undefined;
 
     const holdTimeoutRef = useRef<number | null>(null);
     const controlsTimeoutRef = useRef<number | null>(null);
@@ -97,6 +107,13 @@ export function CustomVideoPlayer({ src, poster, subtitleSrc, onError }: CustomV
         }
     };
 
+    const handleSliderHover = (e: ReactMouseEvent<HTMLDivElement>) => {
+        if (!duration) return;
+        const rect = e.currentTarget.getBoundingClientRect();
+        const ratio = Math.min(Math.max((e.clientX - rect.left) / rect.width, 0), 1);
+        setHoverTime(ratio * duration);
+    };
+
     const handleTimeUpdate = () => {
         if (videoRef.current) {
             setCurrentTime(videoRef.current.currentTime);
@@ -260,7 +277,29 @@ export function CustomVideoPlayer({ src, poster, subtitleSrc, onError }: CustomV
                             {formatTime(currentTime)}
                         </span>
 
-                        <div className="relative flex-1 group/slider h-5 flex items-center">
+                        <div
+                            className="relative flex-1 group/slider h-5 flex items-center"
+                            onMouseMove={handleSliderHover}
+                            onMouseLeave={() => setHoverTime(null)}
+                        >
+                            {/* Storyboard scrub preview */}
+                            {hoverTime !== null && storyboard && hoverTile && (
+                                <div
+                                    className="absolute bottom-6 -translate-x-1/2 flex flex-col items-center gap-1 pointer-events-none z-20"
+                                    style={{ left: `${(hoverTime / duration) * 100}%` }}
+                                >
+                                    <div
+                                        className="rounded-md border border-white/20 shadow-lg bg-no-repeat"
+                                        style={{
+                                            width: hoverTile.w,
+                                            height: hoverTile.h,
+                                            backgroundImage: `url("${storyboard.spriteSrc}")`,
+                                            backgroundPosition: `-${hoverTile.x}px -${hoverTile.y}px`,
+                                        }}
+                                    />
+                                    <span className="text-white text-xs font-medium">{formatTime(hoverTime)}</span>
+                                </div>
+                            )}
                             <input
                                 type="range"
                                 min="0"
@@ -276,6 +315,15 @@ export function CustomVideoPlayer({ src, poster, subtitleSrc, onError }: CustomV
                                     style={{ width: `${(currentTime / (duration || 1)) * 100}%` }}
                                 />
                             </div>
+                            {/* Chapter Markers */}
+                            {duration > 0 && chapters.filter(c => c.start_time > 0).map(c => (
+                                <div
+                                    key={c.id}
+                                    title={c.title ?? undefined}
+                                    className="absolute h-2.5 w-0.5 bg-white/70 pointer-events-none"
+                                    style={{ left: `${(c.start_time / duration) * 100}%` }}
+                                />
+                            ))}
                             {/* Thumb */}
                             <div
                                 className="absolute h-3 w-3 bg-white rounded-full pointer-events-none scale-0 group-hover/slider:scale-100 transition-transform shadow-lg"
@@ -289,6 +337,11 @@ export function CustomVideoPlayer({ src, poster, subtitleSrc, onError }: CustomV
                             {formatTime(duration)}
                         </span>
                     </div>
+                    {currentChapter?.title && (
+                        <div className="text-white/70 text-xs font-medium text-center -mt-2">
+                            {currentChapter.title}
+                        </div>
+                    )}
 
                     {/* Secondary Controls (Play/Pause, Volume, Fullscreen) */}
                     <div className="flex items-center justify-between mt-1">
@@ -1,20 +1,50 @@
-import { convertFileSrc } from '@tauri-apps/api/core';
-import type { Media } from '../../../../types/wall';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+import type { Chapter, Media, Storyboard, StoryboardTile } from '../../../../types/wall';
 import { Image as ImageIcon } from 'lucide-react';
 import { useState, useEffect } from 'react';
 import { CustomVideoPlayer } from './CustomVideoPlayer';
 
-export function MediaPlayer({ media }: { media: Media }) {
+// `HH:MM:SS.mmm` or `MM:SS.mmm`
+function parseVttTime(value: string): number {
+    return value.split(':').reduce((acc, part) => acc * 60 + parseFloat(part), 0);
+}
+
+// Cues of the form `start --> end` followed by `sprite.jpg#xywh=x,y,w,h`.
+function parseStoryboardVtt(text: string): StoryboardTile[] {
+    const tiles: StoryboardTile[] = [];
+    const lines = text.split(/\r?\n/);
+    for (let i = 0; i < lines.length - 1; i++) {
+        const timing = lines[i].split('-->');
+        const fragment = lines[i + 1].match(/#xywh=(\d+),(\d+),(\d+),(\d+)/);
+        if (timing.length !== 2 || !fragment) continue;
+        const [x, y, w, h] = fragment.slice(1).map(Number);
+        tiles.push({ start: parseVttTime(timing[0].trim()), end: parseVttTime(timing[1].trim()), x, y, w, h });
+    }
+    return tiles;
+}
+
+export function MediaPlayer({ media, postId }: { media: Media; postId: number }) {
     const [hasError, setHasError] = useState(false);
     const [subtitleUrl, setSubtitleUrl] = useState<string>();
+    const [chapters, setChapters] = useState<Chapter[]>([]);
+    const [storyboard, setStoryboard] = useState<Storyboard>();
 
     const src = convertFileSrc(media.file_path);
     const isVideoOrAudio = media.media_type === "VIDEO" || media.media_type === "AUDIO";
-    const posterSrc = media.thumbnail_path ? convertFileSrc(media.thumbnail_path) : undefined;
+    const posterPath = media.poster_path ?? media.thumbnail_path;
+    const posterSrc = posterPath ? convertFileSrc(posterPath) : undefined;
 
     useEffect(() => {
         const checkSubtitle = async () => {
             if (isVideoOrAudio && media.file_path) {
+                // Prefer a registered WebVTT track; browsers can't render SRT in <track>.
+                const subtitles = await invoke<Media[]>('get_post_subtitles', { postId }).catch(() => []);
+                const vtt = subtitles.find(s => s.file_path.toLowerCase().endsWith('.vtt'));
+                if (vtt) {
+                    setSubtitleUrl(convertFileSrc(vtt.file_path));
+                    return;
+                }
+
                 const lastDot = media.file_path.lastIndexOf('.');
                 if (lastDot !== -1) {
                     const vttPath = media.file_path.substring(0, lastDot) + '.vtt';
@@ -25,7 +55,34 @@ export function MediaPlayer({ media }: { media: Media }) {
             }
         };
         checkSubtitle();
-    }, [media.file_path, isVideoOrAudio]);
+    }, [media.file_path, isVideoOrAudio, postId]);
+
+    useEffect(() => {
+        // Chapter times refer to the full download, not to split-chapter pieces.
+        if (isVideoOrAudio && media.order_index === 0) {
+            invoke<Chapter[]>('get_post_chapters', { postId })
+                .then(setChapters)
+                .catch(() => setChapters([]));
+        }
+    }, [isVideoOrAudio, media.order_index, postId]);
+
+    useEffect(() => {
+        setStoryboard(undefined);
+        const { sprite_path, sprite_vtt_path } = media;
+        if (!sprite_path || !sprite_vtt_path) return;
+
+        let cancelled = false;
+        fetch(convertFileSrc(sprite_vtt_path))
+            .then(res => (res.ok ? res.text() : Promise.reject(res.status)))
+            .then(text => {
+                const tiles = parseStoryboardVtt(text);
+                if (!cancelled && tiles.length > 0) {
+                    setStoryboard({ spriteSrc: convertFileSrc(sprite_path), tiles });
+                }
+            })
+            .catch(() => { /* no scrub previews */ });
+        return () => { cancelled = true; };
+    }, [media.sprite_path, media.sprite_vtt_path]);
 
     if (hasError) {
         return (
@@ -44,6 +101,8 @@ export function MediaPlayer({ media }: { media: Media }) {
                     src={src}
                     poster={posterSrc}
                     subtitleSrc={subtitleUrl}
+                    chapters={chapters}
+                    storyboard={storyboard}
                     onError={() => setHasError(true)}
                 />
             ) : (
@@ -1,8 +1,8 @@
-import { ExternalLink, FolderOpen, Trash2, Calendar, FileType, HardDrive, RotateCcw } from 'lucide-react';
-import type { Post, Media } from '../../../../types/wall';
+import { ExternalLink, FolderOpen, Trash2, Calendar, FileType, HardDrive, RotateCcw, Scissors, Volume2 } from 'lucide-react';
+import type { Post, Media, SponsorSegment } from '../../../../types/wall';
 import { revealInExplorer, deletePost } from '../../api/viewer';
 import { invoke } from '@tauri-apps/api/core';
-import { useState } from 'react';
+import { useEffect, useState } from 'react';
 import { useQueryClient } from '@tanstack/react-query';
 import { ConfirmModal } from '../../../../components/ui/ConfirmModal';
 
@@ -18,6 +18,14 @@ export function MediaSidebar({ post, media, onClose, isTrashMode }: MediaSidebar
     const [isDeleting, setIsDeleting] = useState(false);
     const [isRestoring, setIsRestoring] = useState(false);
     const [isConfirmOpen, setIsConfirmOpen] = useState(false);
+    const [sponsorSegments, setSponsorSegments] = useState<SponsorSegment[]>([]);
+    const [isNormalizing, setIsNormalizing] = useState(false);
+
+    useEffect(() => {
+        invoke<SponsorSegment[]>('get_post_sponsor_segments', { postId: post.id })
+            .then(setSponsorSegments)
+            .catch(() => setSponsorSegments([]));
+    }, [post.id]);
 
     const handleReveal = async () => {
         try {
@@ -58,6 +66,18 @@ export function MediaSidebar({ post, media, onClose, isTrashMode }: MediaSidebar
         }
     };
 
+    const handleNormalize = async () => {
+        setIsNormalizing(true);
+        try {
+            await invoke('normalize_post_loudness', { postId: post.id });
+            queryClient.invalidateQueries({ queryKey: ['posts'] });
+        } catch (error) {
+            console.error('Failed to normalize loudness:', error);
+        } finally {
+            setIsNormalizing(false);
+        }
+    };
+
     // Format bytes to human readable
     const formatBytes = (bytes: number | null) => {
         if (!bytes) return 'Unknown';
@@ -72,6 +92,12 @@ export function MediaSidebar({ post, media, onClose, isTrashMode }: MediaSidebar
         return new Date(dateStr).toLocaleString();
     };
 
+    const formatTimestamp = (seconds: number) => {
+        const m = Math.floor(seconds / 60);
+        const s = Math.floor(seconds % 60);
+        return `${m}:${s.toString().padStart(2, '0')}`;
+    };
+
     return (
         <div className="w-80 md:w-96 flex flex-col bg-surface-900 border-l border-surface-800 h-full overflow-y-auto custom-scrollbar">
             {/* Creator Header */}
@@ -117,9 +143,35 @@ export function MediaSidebar({ post, media, onClose, isTrashMode }: MediaSidebar
                         <Calendar size={16} className="text-surface-500" />
                         <span className="truncate">{formatDate(post.downloaded_at)}</span>
                     </div>
+                    {media.loudness_lufs != null && (
+                        <div className="flex items-center gap-2">
+                            <Volume2 size={16} className="text-surface-500" />
+                            <span>{media.loudness_lufs.toFixed(1)} LUFS</span>
+                        </div>
+                    )}
                 </div>
             </div>
 
+            {/* SponsorBlock Segments */}
+            {sponsorSegments.length > 0 && (
+                <div className="p-4 border-t border-surface-800">
+                    <h4 className="text-xs font-semibold text-surface-400 uppercase tracking-wider mb-3">
+                        {sponsorSegments[0].action === 'remove' ? 'Removed Segments' : 'Marked Segments'}
+                    </h4>
+                    <div className="space-y-1.5 text-sm text-surface-300">
+                        {sponsorSegments.map(seg => (
+                            <div key={seg.id} className="flex items-center gap-2">
+                                <Scissors size={14} className="text-surface-500" />
+                                <span className="capitalize">{seg.category}</span>
+                                <span className="ml-auto text-xs font-mono text-surface-400">
+                                    {formatTimestamp(seg.start_time)} – {formatTimestamp(seg.end_time)}
+                                </span>
+                            </div>
+                        ))}
+                    </div>
+                </div>
+            )}
+
             {/* Actions */}
             <div className="p-4 space-y-2 border-t border-surface-800">
                 <a
@@ -140,6 +192,17 @@ export function MediaSidebar({ post, media, onClose, isTrashMode }: MediaSidebar
                     Show in Explorer
                 </button>
 
+                {!isTrashMode && (media.media_type === 'VIDEO' || media.media_type === 'AUDIO') && (
+                    <button
+                        onClick={handleNormalize}
+                        disabled={isNormalizing}
+                        className="flex items-center justify-center gap-2 w-full px-4 py-2 bg-surface-700 hover:bg-surface-600 text-surface-100 rounded-lg transition-colors font-medium text-sm disabled:opacity-50"
+                    >
+                        <Volume2 size={16} />
+                        {isNormalizing ? 'Normalizing...' : 'Normalize Loudness'}
+                    </button>
+                )}
+
                 {isTrashMode ? (
                     <>
                         <button
@@ -87,7 +87,7 @@ export function MediaViewer({ post, onClose, onNextPost, onPrevPost, isTrashMode
 
             {/* Main Player Area Left */}
             <div className="relative flex-1 flex items-center justify-center min-h-[50vh] bg-black group/nav">
-                <MediaPlayer key={currentMedia.id} media={currentMedia} />
+                <MediaPlayer key={currentMedia.id} media={currentMedia} postId={post.id} />
 
                 {/* Post Navigation (Edge buttons) */}
                 {onPrevPost && (
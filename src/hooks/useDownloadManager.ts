@@ -64,6 +64,8 @@ export function useDownloadManager() {
           eta: undefined,
           error_message: undefined
         });
+        // Refresh to get any post-processing warnings from backend
+        fetchQueueStatus();
       });
 
       // Task failure
@@ -87,6 +89,12 @@ export function useDownloadManager() {
         });
       });
 
+      // Task parked until its scheduled premiere/stream starts
+      const unlistenWaiting = await listen<number>('download-waiting', () => {
+        // Refresh to get the scheduled start time from backend
+        fetchQueueStatus();
+      });
+
       // Task cancelled
       const unlistenCancelled = await listen<number>('download-cancelled', (event) => {
         updateTask(event.payload, {
@@ -101,6 +109,7 @@ export function useDownloadManager() {
         unlistenCompleted();
         unlistenFailed();
         unlistenPaused();
+        unlistenWaiting();
         unlistenCancelled();
       };
     };
@@ -147,6 +156,15 @@ export function useDownloadManager() {
     }
   };
 
+  const stopRecording = async (taskId: number) => {
+    try {
+      await invoke('stop_recording', { taskId });
+    } catch (error) {
+      console.error('Failed to stop recording:', error);
+      fetchQueueStatus();
+    }
+  };
+
   const pauseDownload = async (taskId: number) => {
     try {
       // Optimistic update — clear stale speed/eta
@@ -226,6 +244,7 @@ export function useDownloadManager() {
     isQueuePaused,
     createDownload,
     cancelDownload,
+    stopRecording,
     pauseDownload,
     resumeDownload,
     retryDownload,
@@ -2,6 +2,7 @@ export enum DownloadStatus {
   Queued = "QUEUED",
   Processing = "PROCESSING",
   Paused = "PAUSED",
+  Waiting = "WAITING",
   Completed = "COMPLETED",
   Failed = "FAILED",
   Cancelled = "CANCELLED",
@@ -27,6 +28,14 @@ export interface DownloadTask {
   thumbnail?: string;
   source_id?: number | null;
   source_name?: string;
+  /** Requested time ranges / chapters when only part of the video is downloaded */
+  sections?: string;
+  /** Live stream: progress is elapsed time and bytes, and it can be stopped */
+  is_live?: boolean;
+  /** WAITING tasks: when the scheduled event starts (or is checked again) */
+  scheduled_at?: string;
+  /** WAITING tasks: when waiting is given up */
+  wait_deadline?: string;
 }
 
 export interface CreateDownloadRequest {
@@ -41,4 +50,6 @@ export interface DownloadProgressPayload {
   eta: string;
   downloaded_bytes: number;
   total_bytes?: number;
+  /** Seconds recorded so far, for live streams */
+  elapsed_secs?: number;
 }
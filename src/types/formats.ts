@@ -15,6 +15,12 @@ export interface ProcessedMetadata {
     audio_tracks: AudioTrack[];
     subtitle_tracks: SubtitleTrack[];
     is_playlist: boolean;
+    /** The URL is broadcasting now; downloading it records the stream. */
+    is_live: boolean;
+    /** A scheduled premiere or stream that hasn't started; the download waits for it. */
+    is_upcoming: boolean;
+    /** Unix timestamp (seconds) the upcoming event is scheduled to start at. */
+    release_timestamp: number | null;
     /** Populated only when `is_playlist` is true. */
     playlist_entries?: PlaylistEntry[];
 }
@@ -86,10 +92,48 @@ export interface DownloadOptions {
     subtitle_langs: string[];
     /** Embed subtitles into the video container */
     embed_subs: boolean;
+    /** Convert subtitle files from VTT to SRT */
+    subs_as_srt?: boolean;
     /** Override output container: "mp4", "mkv", "webm" */
     container?: string;
+    /** Write chapter markers into the output file */
+    embed_chapters?: boolean;
+    /** Also save one file per chapter */
+    split_chapters?: boolean;
+    /** SponsorBlock handling. undefined = use the default from settings */
+    sponsorblock_mode?: SponsorBlockMode;
+    /** SponsorBlock categories. Empty = use the defaults from settings */
+    sponsorblock_categories?: string[];
+    /** Write title/uploader/date/description/URL tags. undefined = use the default from settings */
+    embed_metadata?: boolean;
+    /** Embed the thumbnail as cover art. undefined = use the default from settings */
+    embed_thumbnail?: boolean;
+    /** Save yt-dlp's .info.json next to the file. undefined = use the default from settings */
+    write_info_json?: boolean;
+    /** Download only these parts: "1:02:03-1:02:33" ranges or chapter title regexes */
+    sections?: string[];
+    /** Re-encode around cuts so clips start exactly at the requested time */
+    force_keyframes_at_cuts?: boolean;
+    /** Transcode profile id. undefined = use the default from settings, '' = none */
+    transcode_profile?: string;
+    /** Split "Artist - Title" titles into audio tags. undefined = use the default from settings */
+    parse_artist_title?: boolean;
+    /** EBU R128 loudness normalization. undefined = use the default from settings */
+    loudnorm?: boolean;
+    /** Record a live stream from the start of the broadcast instead of from now */
+    live_from_start?: boolean;
 }
 
+/** A named ffmpeg transcode profile (from `get_transcode_profiles`) */
+export interface TranscodeProfile {
+    id: string;
+    name: string;
+    extension: string;
+    audio_only: boolean;
+}
+
+export type SponsorBlockMode = 'off' | 'mark' | 'remove';
+
 // ─── Helpers ────────────────────────────────────────────────────────────────
 
 /** Format bytes into a human-readable string */
@@ -0,0 +1,108 @@
+/**
+ * Library relocation, reconcile, import and archive types.
+ * These mirror the Rust backend structs from `library::relocate`, `library::reconcile`,
+ * `library::import` and `library::archive`.
+ */
+
+export type RelocateMode = 'move' | 'copy';
+
+export interface Relocation {
+    source: string;
+    target: string;
+    mode: RelocateMode;
+    committed: boolean;
+}
+
+export interface RelocateProgress {
+    files_done: number;
+    files_total: number;
+    bytes_done: number;
+    bytes_total: number;
+    current: string | null;
+}
+
+export interface RelocateReport {
+    copied: number;
+    reused: number;
+    missing: number;
+    removed: number;
+    bytes: number;
+}
+
+export interface ReconcileReport {
+    checked: number;
+    missing: number;
+    relocated: number;
+    restored: number;
+    missing_post_ids: number[];
+    orphan_assets: string[];
+}
+
+export interface ImportProgress {
+    files_done: number;
+    files_total: number;
+    current: string | null;
+}
+
+export interface ImportReport {
+    identified: number;
+    local: number;
+    skipped: number;
+    failed: string[];
+}
+
+export interface ArchiveImportReport {
+    entries: number;
+    unsupported: number;
+    posts_marked: number;
+}
+
+export type CatalogFormat = 'json' | 'csv';
+
+export interface CatalogCounts {
+    platforms: number;
+    creators: number;
+    sources: number;
+    posts: number;
+    media: number;
+}
+
+export interface CatalogImportReport {
+    added: CatalogCounts;
+    existing: CatalogCounts;
+}
+
+export interface BackupManifest {
+    format: number;
+    app_version: string;
+    created_at: string;
+    schema_version: string;
+    includes_sessions: boolean;
+}
+
+export interface BackupReport {
+    path: string;
+    size: number;
+    manifest: BackupManifest;
+}
+
+export interface RestoreReport {
+    manifest: BackupManifest;
+    migrations_applied: number;
+    sessions_kept: boolean;
+}
+
+export interface MaintenanceReport {
+    integrity_errors: string[];
+    foreign_key_errors: string[];
+    media_removed: number;
+    chapters_removed: number;
+    segments_removed: number;
+    tasks_removed: number;
+    tasks_detached: number;
+    posts_removed: number;
+    creators_removed: number;
+    folders_removed: number;
+    size_before: number;
+    size_after: number;
+}
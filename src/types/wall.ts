@@ -8,6 +8,29 @@ export interface Media {
     height: number | null;
     duration: number | null;
     file_size: number | null;
+    preview_path: string | null;
+    sprite_path: string | null;
+    sprite_vtt_path: string | null;
+    poster_path: string | null;
+    language: string | null;
+    loudness_lufs: number | null;
+    /** AVAILABLE, or MISSING once a library scan can't find the file. */
+    status: string;
+}
+
+/** One tile of a storyboard sprite, parsed from its WebVTT index. */
+export interface StoryboardTile {
+    start: number;
+    end: number;
+    x: number;
+    y: number;
+    w: number;
+    h: number;
+}
+
+export interface Storyboard {
+    spriteSrc: string;
+    tiles: StoryboardTile[];
 }
 
 export interface Post {
@@ -38,3 +61,21 @@ export interface PostsPage {
     limit: number;
     total_pages: number;
 }
+
+export interface SponsorSegment {
+    id: number;
+    post_id: number;
+    category: string;
+    start_time: number;
+    end_time: number;
+    action: 'mark' | 'remove';
+}
+
+export interface Chapter {
+    id: number;
+    post_id: number;
+    chapter_index: number;
+    start_time: number;
+    end_time: number | null;
+    title: string | null;
+}